@@ -1,4 +1,10 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use crate::render::{ColorChoice, Format, REPORT_FIELDS, TemperatureUnit};
+use crate::template::Template;
+use clap::builder::PossibleValuesParser;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+use wezzapp_core::credentials::DEFAULT_PROFILE;
 use wezzapp_core::provider::Provider;
 
 /// Top-level CLI for the `wezzapp` command.
@@ -8,6 +14,21 @@ use wezzapp_core::provider::Provider;
 ///   wezzapp get "Kyiv, Ukraine"
 ///   wezzapp get "Kyiv, Ukraine" "2024-11-29"
 ///   wezzapp get "Kyiv, Ukraine" "2024-11-29" --provider accuweather
+///   wezzapp get --file addresses.txt
+///   wezzapp get "Kyiv, Ukraine" --format compact
+///   wezzapp configure weatherapi --profile work
+///   wezzapp get "Kyiv, Ukraine" --profile work
+///   wezzapp get "Kyiv, Ukraine" --format json --output forecast.json
+///   wezzapp get "Kyiv, Ukraine" --raw
+///   wezzapp get "Kyiv, Ukraine" --field max_temperature
+///   wezzapp get "Kyiv, Ukraine" --save home
+///   wezzapp get home
+///   wezzapp get "Kyiv, Ukraine" --from 2024-12-02 --to 2024-12-05
+///   wezzapp get "Kyiv, Ukraine" --now
+///   wezzapp migrate-store --from toml --to encrypted
+///   wezzapp compare "Kyiv, Ukraine" "Lviv, Ukraine"
+///   wezzapp compare "Kyiv, Ukraine" --date 2024-12-01 --compare-date 2024-12-03
+///   wezzapp config dump --format json
 #[derive(Debug, Parser)]
 #[command(
     name = "wezzapp",
@@ -19,33 +40,493 @@ pub struct Cli {
     /// Top-level command.
     #[command(subcommand)]
     pub command: Command,
+
+    /// If the config file can't be parsed, back it up to `credentials.toml.bak` and start fresh.
+    #[arg(long, global = true)]
+    pub reset_config: bool,
+
+    /// Keep config encrypted at rest in `credentials.enc`, unlocked with a passphrase
+    /// asked once per run.
+    #[arg(long, global = true)]
+    pub encrypted: bool,
+
+    /// Write reports to this file instead of stdout, replacing it only once the command succeeds.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Never touch the network: serve reports only from the cache, of any age, and fail
+    /// when there's none.
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Log line format, `json` for log aggregation.
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Compact)]
+    pub log_format: LogFormat,
 }
 
+/// Format of diagnostic log lines.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-friendly single line per event.
+    #[default]
+    Compact,
+
+    /// One JSON object per event.
+    Json,
+}
+
+// Parsed once per run, so the size of `Get` doesn't matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Configure credentials for a specific weather provider.
     ///
     /// Interactively prompts user for credentials. Allows to update default provider
     Configure {
-        /// Weather provider to configure credentials for.
+        /// Weather provider to configure credentials for. Picked from a list if omitted.
+        #[arg(value_enum)]
+        provider: Option<ProviderCli>,
+
+        /// Default language for condition text, e.g. "uk".
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Named credentials profile to store credentials under.
+        #[arg(long, default_value = DEFAULT_PROFILE)]
+        profile: String,
+
+        /// Never set or ask to set the provider as default, e.g. when scripting several providers.
+        #[arg(long)]
+        no_default_write: bool,
+
+        /// API key to store without prompting, overwriting the existing one.
+        #[arg(long, requires = "provider", conflicts_with_all = ["api_key_file", "api_key_stdin"])]
+        api_key: Option<String>,
+
+        /// Read the API key from a file instead, keeping it out of shell history.
+        #[arg(
+            long,
+            value_name = "PATH",
+            requires = "provider",
+            conflicts_with = "api_key_stdin"
+        )]
+        api_key_file: Option<PathBuf>,
+
+        /// Read the API key from stdin instead, e.g. piped from a secret manager.
+        #[arg(long, requires = "provider")]
+        api_key_stdin: bool,
+
+        /// AccuWeather regional or proxy endpoint. Kept when only the key is replaced, pass an
+        /// empty value to use the public API again.
+        #[arg(long, value_name = "URL", requires = "provider")]
+        base_url: Option<String>,
+    },
+
+    /// Make an already configured provider the default, without re-entering credentials.
+    SetDefault {
+        /// Weather provider to use by default.
         #[arg(value_enum)]
         provider: ProviderCli,
+
+        /// Named credentials profile to set the default of.
+        #[arg(long, default_value = DEFAULT_PROFILE)]
+        profile: String,
     },
 
     /// Get weather for a given address (and optional date).
     ///
     /// If date is omitted, "now" is used.
+    /// Use `--file` or `--stdin` to get weather for a newline-separated list of addresses.
     Get {
         /// Address/location string, e.g. "Kyiv, Ukraine"
+        #[arg(required_unless_present_any = ["file", "stdin"])]
+        address: Option<String>,
+
+        /// Optional date, e.g. "2024-11-29" or a Unix timestamp like "1732881600".
+        /// If not provided, we treat it as "now".
+        #[arg(requires = "address")]
+        date: Option<String>,
+
+        /// First day of a forecast range, e.g. "2024-12-02". Requires `--to`.
+        #[arg(
+            long,
+            alias = "since",
+            value_name = "DATE",
+            requires_all = ["address", "to"],
+            conflicts_with_all = ["date", "raw"]
+        )]
+        from: Option<String>,
+
+        /// Last day (inclusive) of a forecast range, e.g. "2024-12-05". Requires `--from`.
+        #[arg(long, alias = "until", value_name = "DATE", requires = "from")]
+        to: Option<String>,
+
+        /// Read newline-separated addresses from a file.
+        #[arg(long, conflicts_with_all = ["address", "stdin"])]
+        file: Option<PathBuf>,
+
+        /// Read newline-separated addresses from stdin.
+        #[arg(long, conflicts_with = "address")]
+        stdin: bool,
+
+        /// Optional provider override. If omitted, user's default is used.
+        #[arg(long, value_enum)]
+        provider: Option<ProviderCli>,
+
+        /// If the provider can't forecast that far ahead, use another configured one which can.
+        #[arg(long)]
+        auto_provider: bool,
+
+        /// Count the date's days ahead from today where the location is, not on this machine.
+        ///
+        /// Looks the location up first, e.g. "tomorrow" in Tokyo is "today" in the US evening.
+        #[arg(long, requires = "date", conflicts_with = "raw")]
+        tz_aware: bool,
+
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+
+        /// Language for condition text, e.g. "uk". Overrides configured default (English).
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Named credentials profile to use.
+        #[arg(long, default_value = DEFAULT_PROFILE)]
+        profile: String,
+
+        /// When to color text output. `auto` honors `NO_COLOR` and disables colors when piping.
+        #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+        color: ColorChoice,
+
+        /// Shorthand for `--color never`.
+        #[arg(long, conflicts_with = "color")]
+        no_color: bool,
+
+        /// Request and show air quality (uses extra provider quota on some plans).
+        #[arg(long)]
+        aqi: bool,
+
+        /// Show active weather alerts (AccuWeather uses an extra call, Tomorrow.io has none).
+        #[arg(long)]
+        alerts: bool,
+
+        /// Unit to show temperatures in. Converted on display, JSON and `--field` keep °C.
+        #[arg(long, value_enum, default_value_t = TemperatureUnit::Celsius)]
+        display_units: TemperatureUnit,
+
+        /// Write JSON temperatures in both units, e.g. `max_temperature_c` and `max_temperature_f`.
+        ///
+        /// Only with `--format json` or `--format json-pretty`.
+        #[arg(long, conflicts_with_all = ["field", "template", "raw"])]
+        both_units: bool,
+
+        /// Decimal places for temperatures in text and compact output (JSON keeps full precision).
+        #[arg(
+            long,
+            default_value_t = 0,
+            value_name = "N",
+            value_parser = clap::value_parser!(u8).range(..=6)
+        )]
+        precision: u8,
+
+        /// Warn and exit with code 6 if the forecast high is above this temperature, in
+        /// `--display-units`.
+        #[arg(
+            long,
+            value_name = "TEMP",
+            allow_negative_numbers = true,
+            conflicts_with = "raw"
+        )]
+        alert_above: Option<f64>,
+
+        /// Warn and exit with code 6 if the forecast high is below this temperature, in
+        /// `--display-units`.
+        #[arg(
+            long,
+            value_name = "TEMP",
+            allow_negative_numbers = true,
+            conflicts_with = "raw"
+        )]
+        alert_below: Option<f64>,
+
+        /// Save the address under a name, so later `wezzapp get <NAME>` uses it.
+        #[arg(long, value_name = "NAME", requires = "address")]
+        save: Option<String>,
+
+        /// Show the last fetched report, marked stale, if the provider is unreachable.
+        #[arg(long)]
+        allow_stale: bool,
+
+        /// Look up the address again instead of using the cached location.
+        #[arg(long)]
+        refresh_location: bool,
+
+        /// Print only this report field, undecorated, e.g. `max_temperature`.
+        #[arg(
+            long,
+            value_name = "NAME",
+            value_parser = PossibleValuesParser::new(REPORT_FIELDS),
+            conflicts_with_all = ["format", "raw"]
+        )]
+        field: Option<String>,
+
+        /// Custom layout, e.g. "{location} {date}: {max}/{min}{unit}".
+        ///
+        /// Placeholders: location, date, provider, condition, description, max, min, avg,
+        /// feels_like, unit, uv_index, wind_kph, wind_direction, humidity, sunrise, sunset.
+        /// Missing values are empty, `{{` and `}}` are literal braces.
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            value_parser = Template::parse,
+            conflicts_with_all = ["format", "field", "raw"]
+        )]
+        template: Option<Template>,
+
+        /// Print provider's untouched JSON response instead of the report.
+        #[arg(long, conflicts_with = "format")]
+        raw: bool,
+
+        /// Ping the provider first, telling rejected credentials from outages (one extra call).
+        #[arg(long)]
+        preflight: bool,
+
+        /// Print to stderr which provider served the request and why.
+        #[arg(long)]
+        explain: bool,
+
+        /// Give up on a provider request after this many seconds, including connecting.
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
+
+        /// Give up connecting to a provider after this many seconds.
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        connect_timeout: Option<u64>,
+
+        /// Send provider requests through this proxy, e.g. "http://127.0.0.1:3128".
+        ///
+        /// Without it, `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY` environment variables are honored.
+        #[arg(long, value_name = "URL")]
+        proxy: Option<String>,
+
+        /// Write every provider request URL (API key redacted) and full response to this file.
+        #[arg(long, value_name = "FILE")]
+        debug_log: Option<PathBuf>,
+
+        /// Re-fetch and redraw the report every SECS seconds until Ctrl-C.
+        ///
+        /// Reports fetched less than 5 minutes ago are redrawn from cache, to save provider quota.
+        #[arg(
+            long,
+            value_name = "SECS",
+            value_parser = clap::value_parser!(u64).range(10..),
+            requires = "address",
+            conflicts_with_all = ["file", "stdin", "from", "raw"]
+        )]
+        watch: Option<u64>,
+
+        /// Show real-time conditions with the observation time instead of a daily forecast.
+        #[arg(
+            long,
+            requires = "address",
+            conflicts_with_all = [
+                "date", "from", "file", "stdin", "raw", "field", "template", "watch",
+                "alert_above", "alert_below",
+            ]
+        )]
+        now: bool,
+
+        /// Extra provider query parameter, e.g. `aqi=yes`. Repeatable, passed through verbatim.
+        #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+    },
+
+    /// Check every provider: credentials present, reachable and accepting the credentials.
+    ///
+    /// Uses one call of quota of each configured provider.
+    Status {
+        /// Named credentials profile to check.
+        #[arg(long, default_value = DEFAULT_PROFILE)]
+        profile: String,
+    },
+
+    /// List every supported provider with its forecast range and features.
+    Providers,
+
+    /// Compare weather of two locations, or of one location on two dates, side by side.
+    ///
+    /// The delta column is the second report minus the first.
+    Compare {
+        /// First address or saved location name, e.g. "Kyiv, Ukraine".
         address: String,
 
-        /// Optional date, e.g. "2024-11-29". If not provided, we treat it as "now".
+        /// Second address to compare with. Omit to compare `--date` with `--compare-date`.
+        #[arg(
+            required_unless_present = "compare_date",
+            conflicts_with = "compare_date"
+        )]
+        other_address: Option<String>,
+
+        /// Date of the (first) report, e.g. "2024-11-29". "now" if omitted.
+        #[arg(long, value_name = "DATE")]
         date: Option<String>,
 
+        /// Date of the second report of the same address.
+        #[arg(long, value_name = "DATE")]
+        compare_date: Option<String>,
+
         /// Optional provider override. If omitted, user's default is used.
         #[arg(long, value_enum)]
         provider: Option<ProviderCli>,
+
+        /// Language for condition text, e.g. "uk". Overrides configured default (English).
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Named credentials profile to use.
+        #[arg(long, default_value = DEFAULT_PROFILE)]
+        profile: String,
+
+        /// Unit to show temperatures in.
+        #[arg(long, value_enum, default_value_t = TemperatureUnit::Celsius)]
+        display_units: TemperatureUnit,
+
+        /// Decimal places of temperatures, 0 to 6.
+        #[arg(
+            long,
+            default_value_t = 0,
+            value_name = "N",
+            value_parser = clap::value_parser!(u8).range(..=6)
+        )]
+        precision: u8,
+    },
+
+    /// Interactive terminal UI: type a location, pick a configured provider and browse
+    /// the next days' forecast.
+    Tui {
+        /// Language for condition text, e.g. "uk". Overrides configured default (English).
+        #[arg(long)]
+        lang: Option<String>,
+
+        /// Named credentials profile to use.
+        #[arg(long, default_value = DEFAULT_PROFILE)]
+        profile: String,
+
+        /// Unit to show temperatures in.
+        #[arg(long, value_enum, default_value_t = TemperatureUnit::Celsius)]
+        display_units: TemperatureUnit,
+
+        /// Decimal places of temperatures, 0 to 6.
+        #[arg(
+            long,
+            default_value_t = 0,
+            value_name = "N",
+            value_parser = clap::value_parser!(u8).range(..=6)
+        )]
+        precision: u8,
     },
+
+    /// Copy all profiles, credentials, default providers, language and saved locations
+    /// from one config backend to another.
+    ///
+    /// Entries already in the destination are overwritten, others are kept.
+    MigrateStore {
+        /// Backend to read config from.
+        #[arg(long, value_enum)]
+        from: StoreBackend,
+
+        /// Backend to write config to.
+        #[arg(long, value_enum)]
+        to: StoreBackend,
+
+        /// Delete the source config file after a successful migration.
+        #[arg(long)]
+        purge: bool,
+    },
+
+    /// Inspect the configuration.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+/// `config` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print the effective configuration with all secrets redacted, safe to paste into issues.
+    Dump {
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = DumpFormat::Text)]
+        format: DumpFormat,
+    },
+}
+
+/// Output format of `config dump`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum DumpFormat {
+    /// Human-readable summary.
+    #[default]
+    Text,
+
+    /// Indented JSON, for scripts.
+    Json,
+}
+
+impl Cli {
+    /// Check combinations of arguments clap can't express, failing like a clap parse error.
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        if let Command::Get {
+            both_units: true,
+            format,
+            ..
+        } = &self.command
+            && !matches!(format, Format::Json | Format::JsonPretty)
+        {
+            return Err(Cli::command().error(
+                ErrorKind::ArgumentConflict,
+                "`--both-units` requires `--format json` or `--format json-pretty`",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Command {
+    /// `--lang` of commands which have it.
+    pub fn lang(&self) -> Option<&str> {
+        match self {
+            Command::Configure { lang, .. }
+            | Command::Get { lang, .. }
+            | Command::Compare { lang, .. }
+            | Command::Tui { lang, .. } => lang.as_deref(),
+            Command::SetDefault { .. }
+            | Command::Status { .. }
+            | Command::Providers
+            | Command::MigrateStore { .. }
+            | Command::Config { .. } => None,
+        }
+    }
+}
+
+/// Where config is kept.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum StoreBackend {
+    /// Plain `credentials.toml`.
+    Toml,
+
+    /// Passphrase-encrypted `credentials.enc`.
+    Encrypted,
+}
+
+/// Parse `key=value` pair, value may be empty or contain `=`.
+fn parse_param(param: &str) -> Result<(String, String), String> {
+    match param.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got `{param}`")),
+    }
 }
 
 /// Supported weather providers.
@@ -53,6 +534,7 @@ pub enum Command {
 /// Right now we only support:
 /// - WeatherApi
 /// - AccuWeather
+/// - Tomorrow.io
 #[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
 pub enum ProviderCli {
     /// https://www.weatherapi.com/
@@ -62,6 +544,15 @@ pub enum ProviderCli {
     /// https://developer.accuweather.com/
     #[value(name = "accuweather")]
     AccuWeather,
+
+    /// https://www.tomorrow.io/
+    #[value(name = "tomorrowio")]
+    TomorrowIo,
+
+    /// Offline canned reports, only in builds with the `mock` feature.
+    #[cfg(feature = "mock")]
+    #[value(name = "mock")]
+    Mock,
 }
 
 impl From<Provider> for ProviderCli {
@@ -69,6 +560,9 @@ impl From<Provider> for ProviderCli {
         match provider {
             Provider::WeatherApi => Self::WeatherApi,
             Provider::AccuWeather => Self::AccuWeather,
+            Provider::TomorrowIo => Self::TomorrowIo,
+            #[cfg(feature = "mock")]
+            Provider::Mock => Self::Mock,
         }
     }
 }
@@ -78,15 +572,260 @@ impl From<ProviderCli> for Provider {
         match provider {
             ProviderCli::WeatherApi => Self::WeatherApi,
             ProviderCli::AccuWeather => Self::AccuWeather,
+            ProviderCli::TomorrowIo => Self::TomorrowIo,
+            #[cfg(feature = "mock")]
+            ProviderCli::Mock => Self::Mock,
         }
     }
 }
 
 impl std::fmt::Display for ProviderCli {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ProviderCli::WeatherApi => write!(f, "weatherapi"),
-            ProviderCli::AccuWeather => write!(f, "accuweather"),
+        f.write_str(Provider::from(*self).as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("aqi=yes", ("aqi", "yes"))]
+    #[case("alerts=", ("alerts", ""))]
+    #[case("q=a=b", ("q", "a=b"))]
+    fn parses_param(#[case] param: &str, #[case] expected: (&str, &str)) {
+        assert_eq!(
+            parse_param(param),
+            Ok((expected.0.to_string(), expected.1.to_string()))
+        );
+    }
+
+    #[test]
+    fn every_provider_is_selectable_by_its_key() {
+        for &provider in Provider::ALL {
+            let parsed = ProviderCli::from_str(provider.as_str(), false).unwrap();
+
+            assert_eq!(Provider::from(parsed), provider);
+            assert_eq!(parsed.to_string(), provider.as_str());
         }
     }
+
+    #[rstest]
+    #[case("aqi")]
+    #[case("=yes")]
+    fn rejects_malformed_param(#[case] param: &str) {
+        assert!(parse_param(param).is_err());
+    }
+
+    #[rstest]
+    #[case::json(&["--format", "json"], true)]
+    #[case::json_pretty(&["--format", "json-pretty"], true)]
+    #[case::json_now(&["--format", "json", "--now"], true)]
+    #[case::default_text(&[], false)]
+    #[case::compact(&["--format", "compact"], false)]
+    fn both_units_needs_json_output(#[case] args: &[&str], #[case] valid: bool) {
+        let cli = Cli::try_parse_from([&["wezzapp", "get", "Kyiv", "--both-units"], args].concat())
+            .unwrap();
+
+        assert_eq!(cli.validate().is_ok(), valid);
+    }
+
+    #[test]
+    fn timeouts_are_separate_and_positive() {
+        let cli = Cli::try_parse_from([
+            "wezzapp",
+            "get",
+            "Kyiv",
+            "--connect-timeout",
+            "3",
+            "--timeout",
+            "60",
+        ])
+        .unwrap();
+        let Command::Get {
+            timeout,
+            connect_timeout,
+            ..
+        } = cli.command
+        else {
+            panic!("expected get command");
+        };
+        assert_eq!((connect_timeout, timeout), (Some(3), Some(60)));
+
+        assert!(Cli::try_parse_from(["wezzapp", "get", "Kyiv", "--timeout", "0"]).is_err());
+    }
+
+    #[test]
+    fn alert_thresholds_accept_negative_temperatures() {
+        let cli = Cli::try_parse_from([
+            "wezzapp",
+            "get",
+            "Kyiv",
+            "--alert-above",
+            "30",
+            "--alert-below",
+            "-10.5",
+        ])
+        .unwrap();
+
+        let Command::Get {
+            alert_above,
+            alert_below,
+            ..
+        } = cli.command
+        else {
+            panic!("expected get command");
+        };
+        assert_eq!((alert_above, alert_below), (Some(30.0), Some(-10.5)));
+    }
+
+    #[rstest]
+    #[case("2024-11-29")]
+    #[case("--raw")]
+    #[case("--stdin")]
+    fn now_conflicts_with_forecast_options(#[case] arg: &str) {
+        assert!(Cli::try_parse_from(["wezzapp", "get", "Kyiv", "--now", arg]).is_err());
+    }
+
+    #[rstest]
+    #[case(&["--api-key", "KEY", "--api-key-stdin"])]
+    #[case(&["--api-key", "KEY", "--api-key-file", "key.txt"])]
+    #[case(&["--api-key-file", "key.txt", "--api-key-stdin"])]
+    fn api_key_sources_are_mutually_exclusive(#[case] args: &[&str]) {
+        let args = ["wezzapp", "configure", "weatherapi"].iter().chain(args);
+
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn api_key_requires_provider() {
+        assert!(Cli::try_parse_from(["wezzapp", "configure", "--api-key-stdin"]).is_err());
+    }
+
+    #[test]
+    fn template_with_unknown_placeholder_is_rejected() {
+        let err =
+            Cli::try_parse_from(["wezzapp", "get", "Kyiv", "--template", "{temp}"]).unwrap_err();
+
+        assert!(
+            err.to_string().contains("unknown placeholder `{temp}`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn log_format_is_global_and_defaults_to_compact() {
+        let cli = Cli::try_parse_from(["wezzapp", "status"]).unwrap();
+        assert_eq!(cli.log_format, LogFormat::Compact);
+
+        let cli = Cli::try_parse_from(["wezzapp", "status", "--log-format", "json"]).unwrap();
+        assert_eq!(cli.log_format, LogFormat::Json);
+    }
+
+    #[test]
+    fn since_and_until_are_aliases_of_from_and_to() {
+        let cli = Cli::try_parse_from([
+            "wezzapp",
+            "get",
+            "Kyiv",
+            "--since",
+            "2024-12-01",
+            "--until",
+            "2024-12-05",
+        ])
+        .unwrap();
+
+        let Command::Get { from, to, .. } = cli.command else {
+            panic!("expected get command");
+        };
+        assert_eq!(from.as_deref(), Some("2024-12-01"));
+        assert_eq!(to.as_deref(), Some("2024-12-05"));
+    }
+
+    #[test]
+    fn param_flag_is_repeatable() {
+        let cli = Cli::try_parse_from([
+            "wezzapp", "get", "Kyiv", "--param", "aqi=yes", "--param", "aqi=no",
+        ])
+        .unwrap();
+
+        let Command::Get { params, .. } = cli.command else {
+            panic!("expected get command");
+        };
+        assert_eq!(
+            params,
+            vec![
+                ("aqi".to_string(), "yes".to_string()),
+                ("aqi".to_string(), "no".to_string())
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::two_locations(&["Kyiv", "Lviv"], Some("Lviv"), None)]
+    #[case::two_dates(&["Kyiv", "--compare-date", "2024-12-03"], None, Some("2024-12-03"))]
+    fn parses_compare(
+        #[case] args: &[&str],
+        #[case] expected_other: Option<&str>,
+        #[case] expected_compare_date: Option<&str>,
+    ) {
+        let cli = Cli::try_parse_from(["wezzapp", "compare"].iter().chain(args)).unwrap();
+
+        let Command::Compare {
+            address,
+            other_address,
+            compare_date,
+            ..
+        } = cli.command
+        else {
+            panic!("expected compare command");
+        };
+        assert_eq!(address, "Kyiv");
+        assert_eq!(other_address.as_deref(), expected_other);
+        assert_eq!(compare_date.as_deref(), expected_compare_date);
+    }
+
+    #[rstest]
+    #[case::nothing_to_compare_with(&["Kyiv"])]
+    #[case::both_second_location_and_date(&["Kyiv", "Lviv", "--compare-date", "2024-12-03"])]
+    fn rejects_ambiguous_compare(#[case] args: &[&str]) {
+        assert!(Cli::try_parse_from(["wezzapp", "compare"].iter().chain(args)).is_err());
+    }
+
+    #[test]
+    fn parses_migrate_store() {
+        let cli = Cli::try_parse_from([
+            "wezzapp",
+            "migrate-store",
+            "--from",
+            "toml",
+            "--to",
+            "encrypted",
+            "--purge",
+        ])
+        .unwrap();
+
+        let Command::MigrateStore { from, to, purge } = cli.command else {
+            panic!("expected migrate-store command");
+        };
+        assert_eq!(from, StoreBackend::Toml);
+        assert_eq!(to, StoreBackend::Encrypted);
+        assert!(purge);
+    }
+
+    #[test]
+    fn parses_tui() {
+        let cli = Cli::try_parse_from(["wezzapp", "tui", "--profile", "work", "--precision", "1"])
+            .unwrap();
+
+        let Command::Tui {
+            profile, precision, ..
+        } = cli.command
+        else {
+            panic!("expected tui command");
+        };
+        assert_eq!(profile, "work");
+        assert_eq!(precision, 1);
+    }
 }
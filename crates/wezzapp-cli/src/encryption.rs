@@ -0,0 +1,112 @@
+use crate::store::ConfigCodec;
+use anyhow::{Context, Result, anyhow};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Encrypts config files with a key derived from a passphrase via Argon2.
+///
+/// File layout: 16-byte salt, 24-byte nonce, then XChaCha20-Poly1305 ciphertext.
+/// Salt is kept across saves, so the slow key derivation runs once per session,
+/// while every save gets a fresh nonce.
+///
+/// Not `Debug`, to keep the passphrase out of logs.
+pub struct ConfigCipher {
+    passphrase: String,
+    /// Salt of the current file and the key derived from it, set on first use.
+    derived: Option<([u8; SALT_LEN], Key)>,
+}
+
+impl ConfigCipher {
+    pub fn new(passphrase: String) -> Self {
+        Self {
+            passphrase,
+            derived: None,
+        }
+    }
+
+    /// Key for `salt`, deriving it only if the salt changed.
+    fn key(&mut self, salt: [u8; SALT_LEN]) -> Result<Key> {
+        if let Some((derived_salt, key)) = &self.derived
+            && *derived_salt == salt
+        {
+            return Ok(*key);
+        }
+
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(self.passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|err| anyhow!("failed to derive encryption key: {err}"))?;
+        self.derived = Some((salt, key));
+
+        Ok(key)
+    }
+}
+
+impl ConfigCodec for ConfigCipher {
+    fn decode(&mut self, contents: &[u8]) -> Result<Vec<u8>> {
+        let (salt, rest) = contents
+            .split_first_chunk::<SALT_LEN>()
+            .context("encrypted config is truncated")?;
+        let (nonce, ciphertext) = rest
+            .split_first_chunk::<NONCE_LEN>()
+            .context("encrypted config is truncated")?;
+
+        let cipher = XChaCha20Poly1305::new(&self.key(*salt)?);
+        cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("failed to decrypt config, wrong passphrase?"))
+    }
+
+    fn encode(&mut self, config: &[u8]) -> Result<Vec<u8>> {
+        let salt = match &self.derived {
+            Some((salt, _)) => *salt,
+            None => {
+                let mut salt = [0; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                salt
+            }
+        };
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let cipher = XChaCha20Poly1305::new(&self.key(salt)?);
+        let ciphertext = cipher
+            .encrypt(&nonce, config)
+            .map_err(|_| anyhow!("failed to encrypt config"))?;
+
+        Ok([salt.as_slice(), nonce.as_slice(), &ciphertext].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_with_fresh_nonce_per_save() {
+        let mut cipher = ConfigCipher::new("secret".into());
+
+        let first = cipher.encode(b"language = \"uk\"").unwrap();
+        let second = cipher.encode(b"language = \"uk\"").unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(first[..SALT_LEN], second[..SALT_LEN], "salt is kept");
+        assert_eq!(
+            ConfigCipher::new("secret".into()).decode(&first).unwrap(),
+            b"language = \"uk\""
+        );
+    }
+
+    #[test]
+    fn truncated_contents_fail_to_decode() {
+        let err = ConfigCipher::new("secret".into())
+            .decode(&[0; SALT_LEN])
+            .unwrap_err();
+
+        assert!(err.to_string().contains("truncated"), "{err}");
+    }
+}
@@ -0,0 +1,109 @@
+use crate::handlers::get::ThresholdCrossed;
+use crate::prompter::Cancelled;
+use wezzapp_core::error::WeatherError;
+
+/// Any failure without a more specific code.
+pub const FAILURE: u8 = 1;
+/// Bad usage or arguments, also used by clap for parse errors.
+pub const USAGE: u8 = 2;
+/// Missing or invalid credentials or configuration.
+pub const CONFIG: u8 = 3;
+/// Provider unreachable or failing (network, HTTP, rate limit, unexpected response).
+pub const PROVIDER: u8 = 4;
+/// Provider couldn't find the address.
+pub const ADDRESS_NOT_FOUND: u8 = 5;
+/// Forecast crossed `--alert-above`/`--alert-below`, the report itself was printed.
+pub const THRESHOLD: u8 = 6;
+/// Prompt cancelled with Ctrl-C or Esc, like a shell's SIGINT status. `configure` exits 0 instead.
+pub const CANCELLED: u8 = 130;
+
+/// Exit code for `err`, from the first `WeatherError` in its chain.
+pub fn for_error(err: &anyhow::Error) -> u8 {
+    if err.is::<ThresholdCrossed>() {
+        return THRESHOLD;
+    }
+    if err.chain().any(|cause| cause.is::<Cancelled>()) {
+        return CANCELLED;
+    }
+
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<WeatherError>())
+        .map_or(FAILURE, for_weather_error)
+}
+
+fn for_weather_error(err: &WeatherError) -> u8 {
+    match err {
+        WeatherError::EmptyAddress
+        | WeatherError::AddressTooLong { .. }
+        | WeatherError::ForecastRangeExceeded { .. }
+        | WeatherError::NoProviderCoversRange { .. }
+        | WeatherError::InvalidDate(_)
+        | WeatherError::DateInPast
+        | WeatherError::DateTooFarAhead { .. }
+        | WeatherError::InvalidDateRange { .. } => USAGE,
+        WeatherError::InvalidApiKey(_)
+        | WeatherError::MissingCredentials(_)
+        | WeatherError::NoDefaultProvider
+        | WeatherError::NoProvidersConfigured
+        | WeatherError::CredentialsMismatch(_)
+        | WeatherError::IgnoredHttpSettings(_)
+        | WeatherError::Store(_) => CONFIG,
+        WeatherError::RateLimited
+        | WeatherError::Url(_)
+        | WeatherError::Http(_)
+        | WeatherError::ProviderUnavailable(_)
+        | WeatherError::ForecastTooShort { .. }
+        | WeatherError::Offline
+        | WeatherError::UnexpectedResponse(_) => PROVIDER,
+        WeatherError::AddressNotFound => ADDRESS_NOT_FOUND,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Context, anyhow};
+    use rstest::rstest;
+    use wezzapp_core::provider::Provider;
+
+    #[rstest]
+    #[case(WeatherError::DateInPast, USAGE)]
+    #[case(WeatherError::DateTooFarAhead { max: 365 }, USAGE)]
+    #[case(WeatherError::MissingCredentials(Provider::WeatherApi), CONFIG)]
+    #[case(WeatherError::NoDefaultProvider, CONFIG)]
+    #[case(WeatherError::NoProvidersConfigured, CONFIG)]
+    #[case(WeatherError::RateLimited, PROVIDER)]
+    #[case(WeatherError::UnexpectedResponse("empty".into()), PROVIDER)]
+    #[case(WeatherError::AddressNotFound, ADDRESS_NOT_FOUND)]
+    fn maps_weather_errors(#[case] err: WeatherError, #[case] expected: u8) {
+        assert_eq!(for_error(&err.into()), expected);
+    }
+
+    #[test]
+    fn finds_weather_error_behind_context() {
+        let err = Err::<(), _>(WeatherError::AddressNotFound)
+            .context("failed to get weather")
+            .unwrap_err();
+
+        assert_eq!(for_error(&err), ADDRESS_NOT_FOUND);
+    }
+
+    #[test]
+    fn crossed_threshold_has_own_code() {
+        assert_eq!(for_error(&ThresholdCrossed.into()), THRESHOLD);
+    }
+
+    #[test]
+    fn cancelled_prompt_has_own_code() {
+        let err = Err::<(), _>(Cancelled)
+            .context("failed to read passphrase")
+            .unwrap_err();
+
+        assert_eq!(for_error(&err), CANCELLED);
+    }
+
+    #[test]
+    fn other_errors_are_generic_failures() {
+        assert_eq!(for_error(&anyhow!("2 of 3 addresses failed")), FAILURE);
+    }
+}
@@ -0,0 +1,383 @@
+use crate::cli::ProviderCli;
+use crate::render::{TemperatureUnit, round};
+use anyhow::{Context, Result, anyhow};
+use std::fmt::Write as _;
+use std::io::Write;
+use tracing::trace;
+use wezzapp_core::apis::{ProviderClientFactory, WeatherReport};
+use wezzapp_core::credentials::ServiceStore;
+use wezzapp_core::weather_service::WeatherService;
+
+/// One side of a comparison: an address and an optional date ("now" if omitted).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Side {
+    pub address: String,
+    pub date: Option<String>,
+}
+
+impl Side {
+    pub fn new(address: String, date: Option<String>) -> Self {
+        Self { address, date }
+    }
+
+    /// Human-readable name for errors, e.g. "`Lviv` on 2024-11-30".
+    fn describe(&self) -> String {
+        match &self.date {
+            Some(date) => format!("`{}` on {date}", self.address),
+            None => format!("`{}`", self.address),
+        }
+    }
+}
+
+/// `compare` command handler.
+///
+/// Table goes to `writer`, so tests can inject a buffer.
+pub struct CompareHandler<S, F, W>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+    W: Write,
+{
+    service: WeatherService<S, F>,
+    writer: W,
+    precision: usize,
+    units: TemperatureUnit,
+}
+
+impl<S, F, W> CompareHandler<S, F, W>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+    W: Write,
+{
+    pub fn with_writer(service: WeatherService<S, F>, writer: W) -> Self {
+        Self {
+            service,
+            writer,
+            precision: 0,
+            units: TemperatureUnit::default(),
+        }
+    }
+
+    /// Round temperatures to `precision` decimals.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Show temperatures in `units`, converting from °C.
+    pub fn with_units(mut self, units: TemperatureUnit) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Fetch both sides concurrently and write them side by side with the difference,
+    /// right minus left.
+    ///
+    /// Fails naming the side (or both) which couldn't be fetched.
+    pub fn run(&mut self, left: Side, right: Side, provider: Option<ProviderCli>) -> Result<()> {
+        trace!("Comparing {left:?} with {right:?}, provider: {provider:?}");
+        let provider = provider.map(Into::into);
+
+        let mut reports = self.service.get_weather_all(
+            vec![
+                (left.address.clone(), left.date.clone()),
+                (right.address.clone(), right.date.clone()),
+            ],
+            provider,
+        );
+        let right_report = reports.pop().context("missing right report")?;
+        let left_report = reports.pop().context("missing left report")?;
+
+        let (left_report, right_report) = match (left_report, right_report) {
+            (Ok(left), Ok(right)) => (left, right),
+            (Err(err), Ok(_)) => {
+                return Err(err)
+                    .with_context(|| format!("failed to get weather for {}", left.describe()));
+            }
+            (Ok(_), Err(err)) => {
+                return Err(err)
+                    .with_context(|| format!("failed to get weather for {}", right.describe()));
+            }
+            (Err(left_err), Err(right_err)) => {
+                return Err(anyhow!(
+                    "failed to get weather for both sides: {}: {left_err}; {}: {right_err}",
+                    left.describe(),
+                    right.describe()
+                ));
+            }
+        };
+
+        let table = render_comparison(&left_report, &right_report, self.precision, self.units);
+        write!(self.writer, "{table}").context("failed to write comparison")
+    }
+}
+
+/// Side-by-side table of two reports with a delta column for numeric rows.
+///
+/// Columns are headed by location, or by date when both reports are for the same location,
+/// and the other one gets a row.
+fn render_comparison(
+    left: &WeatherReport,
+    right: &WeatherReport,
+    precision: usize,
+    units: TemperatureUnit,
+) -> String {
+    let mut rows = Vec::new();
+    let (left_header, right_header) = if left.location == right.location {
+        rows.push((
+            "Location",
+            left.location.clone(),
+            right.location.clone(),
+            String::new(),
+        ));
+        (&left.date, &right.date)
+    } else {
+        rows.push(("Date", left.date.clone(), right.date.clone(), String::new()));
+        (&left.location, &right.location)
+    };
+    rows.push((
+        "Condition",
+        left.description.clone(),
+        right.description.clone(),
+        String::new(),
+    ));
+    let symbol = units.symbol();
+
+    let mut numeric = |label, left: Option<f64>, right: Option<f64>, unit: &str| {
+        if left.is_none() && right.is_none() {
+            return;
+        }
+        let delta = match (left, right) {
+            (Some(left), Some(right)) => delta(right - left, precision, unit),
+            _ => "-".to_string(),
+        };
+        let value = |value: Option<f64>| {
+            value.map_or("-".to_string(), |value| {
+                format!("{}{unit}", round(value, precision))
+            })
+        };
+        rows.push((label, value(left), value(right), delta));
+    };
+
+    let converted = |value: Option<f64>| value.map(|value| units.convert(value));
+    numeric(
+        "Max",
+        converted(Some(left.max_temperature)),
+        converted(Some(right.max_temperature)),
+        symbol,
+    );
+    numeric(
+        "Min",
+        converted(Some(left.min_temperature)),
+        converted(Some(right.min_temperature)),
+        symbol,
+    );
+    numeric(
+        "Avg",
+        converted(left.avg_temperature),
+        converted(right.avg_temperature),
+        symbol,
+    );
+    numeric(
+        "Feels like",
+        converted(left.feels_like),
+        converted(right.feels_like),
+        symbol,
+    );
+    numeric("Humidity", left.humidity, right.humidity, "%");
+
+    let width = |column: fn(&(&str, String, String, String)) -> usize, header: usize| {
+        rows.iter().map(column).max().unwrap_or(0).max(header)
+    };
+    let left_width = width(|row| row.1.chars().count(), left_header.chars().count());
+    let right_width = width(|row| row.2.chars().count(), right_header.chars().count());
+
+    let mut table = format!(
+        "{:<10}  {left_header:<left_width$}  {right_header:<right_width$}  DELTA\n",
+        ""
+    );
+    for (label, left, right, delta) in &rows {
+        let line = format!("{label:<10}  {left:<left_width$}  {right:<right_width$}  {delta}");
+        let _ = writeln!(table, "{}", line.trim_end());
+    }
+
+    table
+}
+
+/// Signed difference, e.g. `+3°C`, `-1.5°C` or `0°C`.
+fn delta(value: f64, precision: usize, unit: &str) -> String {
+    let rounded = round(value, precision);
+    let sign = if rounded.parse::<f64>().is_ok_and(|value| value > 0.0) {
+        "+"
+    } else {
+        ""
+    };
+
+    format!("{sign}{rounded}{unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Days, Local};
+    use wezzapp_core::credentials::{Credentials, DEFAULT_PROFILE};
+    use wezzapp_core::error::WeatherError;
+    use wezzapp_core::provider::Provider;
+    use wezzapp_core::testing::{self, FakeClient, FakeFactory, InMemoryStore};
+
+    /// Client with known reports for Kyiv and Lviv, a degree warmer every day ahead.
+    fn client() -> FakeClient {
+        FakeClient::default().with_response(|address, days| {
+            let (max, min, feels_like, humidity) = match address {
+                "Kyiv" => (12.2, 3.0, Some(1.6), Some(72.0)),
+                "Lviv" => (9.0, 1.0, None, Some(80.0)),
+                _ => return Err(WeatherError::AddressNotFound),
+            };
+            let date = Local::now().date_naive() + Days::new(days.into());
+
+            Ok(WeatherReport {
+                date: date.to_string(),
+                location: address.to_string(),
+                max_temperature: max + f64::from(days),
+                min_temperature: min,
+                feels_like,
+                humidity,
+                ..testing::report()
+            })
+        })
+    }
+
+    fn handler(buffer: &mut Vec<u8>) -> CompareHandler<InMemoryStore, FakeFactory, &mut Vec<u8>> {
+        let mut store = InMemoryStore::default();
+        store.providers.insert(
+            (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+            Credentials::WeatherApi {
+                api_key: "KEY".into(),
+            },
+        );
+        store
+            .defaults
+            .insert(DEFAULT_PROFILE.to_string(), Provider::WeatherApi);
+
+        CompareHandler::with_writer(
+            WeatherService::new(store, FakeFactory::new(client())),
+            buffer,
+        )
+    }
+
+    fn days_ahead(days: u64) -> String {
+        (Local::now().date_naive() + Days::new(days)).to_string()
+    }
+
+    #[test]
+    fn compares_two_locations() {
+        let mut buffer = Vec::new();
+
+        handler(&mut buffer)
+            .run(
+                Side::new("Kyiv".into(), None),
+                Side::new("Lviv".into(), None),
+                None,
+            )
+            .unwrap();
+
+        let today = days_ahead(0);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            format!(
+                "            Kyiv        Lviv        DELTA\n\
+                 Date        {today}  {today}\n\
+                 Condition   Sunny       Sunny\n\
+                 Max         12°C        9°C         -3°C\n\
+                 Min         3°C         1°C         -2°C\n\
+                 Feels like  2°C         -           -\n\
+                 Humidity    72%         80%         +8%\n"
+            )
+        );
+    }
+
+    #[test]
+    fn compares_two_dates_of_one_location() {
+        let mut buffer = Vec::new();
+
+        handler(&mut buffer)
+            .with_precision(1)
+            .with_units(TemperatureUnit::Fahrenheit)
+            .run(
+                Side::new("Kyiv".into(), Some(days_ahead(1))),
+                Side::new("Kyiv".into(), Some(days_ahead(3))),
+                None,
+            )
+            .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(
+            output.starts_with(&format!(
+                "            {}  {}  DELTA\nLocation    Kyiv",
+                days_ahead(1),
+                days_ahead(3)
+            )),
+            "{output}"
+        );
+        // Two days apart is 2°C, which is 3.6°F.
+        assert!(
+            output.contains("Max         55.8°F      59.4°F      +3.6°F\n"),
+            "{output}"
+        );
+        assert!(
+            output.contains("Min         37.4°F      37.4°F      0.0°F\n"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn one_failing_side_is_named() {
+        let mut buffer = Vec::new();
+
+        let err = handler(&mut buffer)
+            .run(
+                Side::new("Kyiv".into(), None),
+                Side::new("Nowhere".into(), Some(days_ahead(1))),
+                None,
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            format!("failed to get weather for `Nowhere` on {}", days_ahead(1))
+        );
+        assert!(
+            err.chain().any(|cause| matches!(
+                cause.downcast_ref::<WeatherError>(),
+                Some(WeatherError::AddressNotFound)
+            )),
+            "{err:?}"
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn both_failing_sides_are_reported() {
+        let mut buffer = Vec::new();
+
+        let err = handler(&mut buffer)
+            .run(
+                Side::new("Atlantis".into(), None),
+                Side::new("Nowhere".into(), None),
+                None,
+            )
+            .unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains("`Atlantis`: "), "{message}");
+        assert!(message.contains("`Nowhere`: "), "{message}");
+    }
+
+    #[test]
+    fn delta_is_signed() {
+        assert_eq!(delta(3.0, 0, "°C"), "+3°C");
+        assert_eq!(delta(-1.54, 1, "°C"), "-1.5°C");
+        assert_eq!(delta(0.2, 0, "°C"), "0°C");
+    }
+}
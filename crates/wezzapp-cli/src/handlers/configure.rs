@@ -1,56 +1,150 @@
 use crate::cli::ProviderCli;
 use crate::prompter::ConfigurePrompter;
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use std::io::Read;
 use tracing::debug;
-use wezzapp_core::credentials::CredentialsStore;
+use wezzapp_core::credentials::{Credentials, CredentialsStore, DEFAULT_PROFILE, SettingsStore};
 use wezzapp_core::provider::Provider;
 
 /// `configure` command handler.
 pub struct ConfigureHandler<S, P>
 where
-    S: CredentialsStore,
+    S: CredentialsStore + SettingsStore,
     P: ConfigurePrompter,
 {
     store: S,
     prompter: P,
+    /// Credentials profile to configure.
+    profile: String,
+    /// Never touch the default provider, e.g. when scripting several providers.
+    no_default_write: bool,
+    /// API key given up front, stored without prompting.
+    api_key: Option<String>,
+    /// Base URL given up front, empty to drop the stored one.
+    base_url: Option<String>,
 }
 
 impl<S, P> ConfigureHandler<S, P>
 where
-    S: CredentialsStore,
+    S: CredentialsStore + SettingsStore,
     P: ConfigurePrompter,
 {
     pub fn new(store: S, prompter: P) -> Self {
-        Self { store, prompter }
+        Self {
+            store,
+            prompter,
+            profile: DEFAULT_PROFILE.to_string(),
+            no_default_write: false,
+            api_key: None,
+            base_url: None,
+        }
+    }
+
+    /// Store credentials under the given profile.
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Leave the default provider as is, without asking, even if there's none yet.
+    pub fn with_no_default_write(mut self, no_default_write: bool) -> Self {
+        self.no_default_write = no_default_write;
+        self
+    }
+
+    /// Store `api_key` without prompting for credentials or overwrite confirmation.
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
     }
-    pub fn run(&mut self, provider_cli: ProviderCli) -> Result<()> {
-        let provider: Provider = provider_cli.into();
+
+    /// Store `base_url`, or drop the stored one if it's empty. Without an API key, existing
+    /// credentials keep their key and nothing is asked.
+    pub fn with_base_url(mut self, base_url: Option<String>) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// Configure `provider_cli`, or the one user picks from a list if it's `None`.
+    pub fn run(
+        &mut self,
+        provider_cli: Option<ProviderCli>,
+        language: Option<String>,
+    ) -> Result<()> {
+        let provider: Provider = match provider_cli {
+            Some(provider_cli) => provider_cli.into(),
+            None => self.prompter.select_provider()?,
+        };
+        let provider_cli = ProviderCli::from(provider);
         debug!("Configuring provider: {:?}", provider);
 
-        let existing = self.store.get_credentials(provider)?;
+        let is_optional = |name: &str| {
+            provider
+                .optional_credential_fields()
+                .iter()
+                .any(|field| field.name == name)
+        };
+        let base_url = self.base_url.take();
+        if base_url.is_some() && !is_optional("base_url") {
+            bail!("{provider:?} doesn't take a base URL");
+        }
+
+        let existing = self.store.get_credentials(&self.profile, provider)?;
         debug!("Existing credentials {}", existing.is_some());
 
-        let overwrite = if existing.is_some() {
-            self.prompter.confirm_overwrite(provider)?
-        } else {
-            true
-        };
-        debug!("Overwrite credentials: {:?}", overwrite);
+        // Optional values are kept when only the key is replaced.
+        let mut optional: HashMap<&str, String> = existing
+            .as_ref()
+            .map(Credentials::fields)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(name, _)| is_optional(name))
+            .collect();
+        let base_url_only = base_url.is_some() && self.api_key.is_none();
+        match base_url {
+            Some(base_url) if base_url.is_empty() => {
+                optional.remove("base_url");
+            }
+            Some(base_url) => {
+                optional.insert("base_url", base_url);
+            }
+            None => {}
+        }
 
-        if overwrite {
-            let new_credentials = self.prompter.prompt_credentials(provider)?;
+        let new_credentials = match (self.api_key.take(), &existing) {
+            (Some(api_key), _) => {
+                let mut fields = optional;
+                fields.insert("api_key", api_key);
+                Some(
+                    Credentials::from_fields(provider, &fields)
+                        .with_context(|| format!("{provider:?} doesn't take an API key"))?,
+                )
+            }
+            (None, Some(existing)) if base_url_only => {
+                let mut fields = existing.fields();
+                fields.retain(|name, _| !is_optional(name));
+                fields.extend(optional);
+                Credentials::from_fields(provider, &fields)
+            }
+            (None, Some(_)) if !self.prompter.confirm_overwrite(provider)? => None,
+            (None, _) => Some(self.prompter.prompt_credentials(provider, &optional)?),
+        };
+        debug!("Overwrite credentials: {:?}", new_credentials.is_some());
 
+        if let Some(new_credentials) = new_credentials {
             self.store
-                .set_credentials(provider, &new_credentials)
+                .set_credentials(&self.profile, provider, &new_credentials)
                 .context("failed to save credentials")?;
 
             println!("Credentials for `{provider_cli}` were saved.");
         };
 
-        let current_default = self.store.get_default_provider()?;
+        let current_default = self.store.get_default_provider(&self.profile)?;
         debug!("Current default provider: {:?}", current_default);
 
         let set_default = match current_default {
+            _ if self.no_default_write => false,
             None => true,
             Some(default) if default == provider => false,
             Some(_) => self.prompter.confirm_set_default(provider)?,
@@ -59,48 +153,45 @@ where
 
         if set_default {
             self.store
-                .set_default_provider(provider)
+                .set_default_provider(&self.profile, provider)
                 .context("failed to set default provider")?;
 
             println!("Provider `{provider_cli}` was set as default.");
         }
 
+        if let Some(language) = language {
+            self.store
+                .set_language(&language)
+                .context("failed to save language")?;
+
+            println!("Language `{language}` was set as default.");
+        }
+
         Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::collections::HashMap;
-    use wezzapp_core::credentials::Credentials;
-
-    /// In-memory implementation of CredentialsStore for tests.
-    #[derive(Default)]
-    struct InMemoryStore {
-        default: Option<Provider>,
-        providers: HashMap<Provider, Credentials>,
-    }
-
-    impl CredentialsStore for &mut InMemoryStore {
-        fn set_credentials(&mut self, provider: Provider, credentials: &Credentials) -> Result<()> {
-            self.providers.insert(provider, credentials.clone());
-            Ok(())
-        }
+/// API key read from `reader`, e.g. a key file or stdin, without surrounding whitespace.
+pub fn read_api_key<R: Read>(mut reader: R) -> Result<String> {
+    let mut api_key = String::new();
+    reader
+        .read_to_string(&mut api_key)
+        .context("failed to read API key")?;
 
-        fn get_credentials(&self, provider: Provider) -> Result<Option<Credentials>> {
-            Ok(self.providers.get(&provider).cloned())
-        }
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        bail!("API key is empty");
+    }
 
-        fn set_default_provider(&mut self, provider: Provider) -> Result<()> {
-            self.default = Some(provider);
-            Ok(())
-        }
+    Ok(api_key.to_string())
+}
 
-        fn get_default_provider(&self) -> Result<Option<Provider>> {
-            Ok(self.default)
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Cursor;
+    use wezzapp_core::testing::InMemoryStore;
 
     /// Mock prompter that lets tests control answers.
     struct MockPrompter {
@@ -111,6 +202,13 @@ mod tests {
         pub overwrite_called: bool,
         pub set_default_called: bool,
         pub credentials_prompt_called: bool,
+
+        /// Answer to `select_provider`, `None` fails the prompt.
+        pub selected_provider: Option<Provider>,
+        pub select_called: bool,
+
+        /// Optional values passed to the last `prompt_credentials`.
+        pub optional_offered: HashMap<String, String>,
     }
 
     impl ConfigurePrompter for &mut MockPrompter {
@@ -124,10 +222,24 @@ mod tests {
             Ok(self.set_default_answer)
         }
 
-        fn prompt_credentials(&mut self, _provider: Provider) -> Result<Credentials> {
+        fn prompt_credentials(
+            &mut self,
+            _provider: Provider,
+            optional: &HashMap<&str, String>,
+        ) -> Result<Credentials> {
             self.credentials_prompt_called = true;
+            self.optional_offered = optional
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect();
             Ok(self.credentials_to_return.clone())
         }
+
+        fn select_provider(&mut self) -> Result<Provider> {
+            self.select_called = true;
+            self.selected_provider
+                .context("unexpected provider selection")
+        }
     }
 
     fn sample_weatherapi_creds() -> Credentials {
@@ -136,6 +248,200 @@ mod tests {
         }
     }
 
+    fn prompter() -> MockPrompter {
+        MockPrompter {
+            overwrite_answer: false,
+            set_default_answer: true,
+            credentials_to_return: sample_weatherapi_creds(),
+            overwrite_called: false,
+            set_default_called: false,
+            credentials_prompt_called: false,
+            selected_provider: None,
+            select_called: false,
+            optional_offered: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn api_key_from_file_is_trimmed_and_stored_without_prompts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key.txt");
+        fs::write(&path, "  FILE_KEY\n").unwrap();
+        let mut store = InMemoryStore {
+            providers: HashMap::from([(
+                (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+                sample_weatherapi_creds(),
+            )]),
+            ..Default::default()
+        };
+        let mut prompter = prompter();
+
+        let api_key = read_api_key(fs::File::open(&path).unwrap()).unwrap();
+        ConfigureHandler::new(&mut store, &mut prompter)
+            .with_api_key(Some(api_key))
+            .run(Some(ProviderCli::WeatherApi), None)
+            .expect("configuration should succeed");
+
+        assert!(
+            store.providers[&(DEFAULT_PROFILE.to_string(), Provider::WeatherApi)]
+                == Credentials::WeatherApi {
+                    api_key: "FILE_KEY".to_string()
+                }
+        );
+        assert!(!prompter.overwrite_called);
+        assert!(!prompter.credentials_prompt_called);
+    }
+
+    fn accuweather_store(base_url: Option<&str>) -> InMemoryStore {
+        InMemoryStore {
+            providers: HashMap::from([(
+                (DEFAULT_PROFILE.to_string(), Provider::AccuWeather),
+                Credentials::AccuWeather {
+                    api_key: "OLD_KEY".to_string(),
+                    base_url: base_url.map(str::to_string),
+                },
+            )]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn replacing_api_key_keeps_stored_base_url() {
+        let mut store = accuweather_store(Some("https://eu.example.com/"));
+        let mut prompter = prompter();
+
+        ConfigureHandler::new(&mut store, &mut prompter)
+            .with_api_key(Some("NEW_KEY".to_string()))
+            .run(Some(ProviderCli::AccuWeather), None)
+            .expect("configuration should succeed");
+
+        assert!(
+            store.providers[&(DEFAULT_PROFILE.to_string(), Provider::AccuWeather)]
+                == Credentials::AccuWeather {
+                    api_key: "NEW_KEY".to_string(),
+                    base_url: Some("https://eu.example.com/".to_string()),
+                }
+        );
+    }
+
+    #[test]
+    fn prompt_is_offered_stored_base_url() {
+        let mut store = accuweather_store(Some("https://eu.example.com/"));
+        let mut prompter = MockPrompter {
+            overwrite_answer: true,
+            ..prompter()
+        };
+
+        ConfigureHandler::new(&mut store, &mut prompter)
+            .run(Some(ProviderCli::AccuWeather), None)
+            .expect("configuration should succeed");
+
+        assert_eq!(
+            prompter.optional_offered,
+            HashMap::from([(
+                "base_url".to_string(),
+                "https://eu.example.com/".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn base_url_alone_keeps_stored_key_without_prompts() {
+        let mut store = accuweather_store(None);
+        let mut prompter = prompter();
+
+        ConfigureHandler::new(&mut store, &mut prompter)
+            .with_base_url(Some("https://eu.example.com/".to_string()))
+            .run(Some(ProviderCli::AccuWeather), None)
+            .expect("configuration should succeed");
+
+        assert!(
+            store.providers[&(DEFAULT_PROFILE.to_string(), Provider::AccuWeather)]
+                == Credentials::AccuWeather {
+                    api_key: "OLD_KEY".to_string(),
+                    base_url: Some("https://eu.example.com/".to_string()),
+                }
+        );
+        assert!(!prompter.overwrite_called);
+        assert!(!prompter.credentials_prompt_called);
+    }
+
+    #[test]
+    fn empty_base_url_drops_stored_one() {
+        let mut store = accuweather_store(Some("https://eu.example.com/"));
+        let mut prompter = prompter();
+
+        ConfigureHandler::new(&mut store, &mut prompter)
+            .with_base_url(Some(String::new()))
+            .run(Some(ProviderCli::AccuWeather), None)
+            .expect("configuration should succeed");
+
+        assert!(
+            store.providers[&(DEFAULT_PROFILE.to_string(), Provider::AccuWeather)]
+                == Credentials::AccuWeather {
+                    api_key: "OLD_KEY".to_string(),
+                    base_url: None,
+                }
+        );
+    }
+
+    #[test]
+    fn base_url_is_rejected_for_providers_without_one() {
+        let mut store = InMemoryStore::default();
+        let mut prompter = prompter();
+
+        let err = ConfigureHandler::new(&mut store, &mut prompter)
+            .with_base_url(Some("https://eu.example.com/".to_string()))
+            .run(Some(ProviderCli::WeatherApi), None)
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "WeatherApi doesn't take a base URL");
+        assert!(store.providers.is_empty());
+    }
+
+    #[test]
+    fn api_key_from_piped_stdin_is_trimmed() {
+        let stdin = Cursor::new("\tPIPED_KEY\r\n");
+
+        assert_eq!(read_api_key(stdin).unwrap(), "PIPED_KEY");
+    }
+
+    #[test]
+    fn blank_api_key_is_rejected() {
+        let err = read_api_key(Cursor::new(" \n")).unwrap_err();
+
+        assert_eq!(err.to_string(), "API key is empty");
+    }
+
+    #[test]
+    fn no_default_write_leaves_empty_store_without_default() {
+        let mut store = InMemoryStore::default();
+        let mut prompter = MockPrompter {
+            overwrite_answer: true,
+            set_default_answer: true,
+            credentials_to_return: sample_weatherapi_creds(),
+            overwrite_called: false,
+            set_default_called: false,
+            credentials_prompt_called: false,
+            selected_provider: None,
+            select_called: false,
+            optional_offered: HashMap::new(),
+        };
+
+        ConfigureHandler::new(&mut store, &mut prompter)
+            .with_no_default_write(true)
+            .run(Some(ProviderCli::WeatherApi), None)
+            .expect("configuration should succeed");
+
+        assert!(
+            store
+                .providers
+                .contains_key(&(DEFAULT_PROFILE.to_string(), Provider::WeatherApi))
+        );
+        assert_eq!(store.defaults.get(DEFAULT_PROFILE), None);
+        assert!(!prompter.set_default_called);
+    }
+
     #[test]
     fn configure_new_provider_with_no_default_sets_creds_and_default() {
         let provider = ProviderCli::WeatherApi;
@@ -148,15 +454,18 @@ mod tests {
             overwrite_called: false,
             set_default_called: false,
             credentials_prompt_called: false,
+            selected_provider: None,
+            select_called: false,
+            optional_offered: HashMap::new(),
         };
 
         ConfigureHandler::new(&mut store, &mut prompter)
-            .run(provider)
+            .run(Some(provider), None)
             .expect("configuration should succeed");
 
         let saved = store
             .providers
-            .get(&provider.into())
+            .get(&(DEFAULT_PROFILE.to_string(), provider.into()))
             .cloned()
             .expect("credentials must be present");
 
@@ -166,10 +475,107 @@ mod tests {
                     api_key: "TEST_KEY".to_string()
                 }
         );
-        assert_eq!(store.default, Some(provider.into()));
+        assert_eq!(
+            store.defaults.get(DEFAULT_PROFILE).copied(),
+            Some(provider.into())
+        );
         assert!(!prompter.overwrite_called);
         assert!(prompter.credentials_prompt_called);
         assert!(!prompter.set_default_called);
+        assert!(!prompter.select_called);
+    }
+
+    #[test]
+    fn configure_without_provider_asks_to_pick_one() {
+        let mut store = InMemoryStore::default();
+        let mut prompter = MockPrompter {
+            overwrite_answer: true,
+            set_default_answer: true,
+            credentials_to_return: Credentials::AccuWeather {
+                api_key: "TEST_KEY".to_string(),
+                base_url: None,
+            },
+            overwrite_called: false,
+            set_default_called: false,
+            credentials_prompt_called: false,
+            selected_provider: Some(Provider::AccuWeather),
+            select_called: false,
+            optional_offered: HashMap::new(),
+        };
+
+        ConfigureHandler::new(&mut store, &mut prompter)
+            .run(None, None)
+            .expect("configuration should succeed");
+
+        assert!(prompter.select_called);
+        assert!(
+            store
+                .providers
+                .contains_key(&(DEFAULT_PROFILE.to_string(), Provider::AccuWeather)),
+            "picked provider should be configured"
+        );
+        assert_eq!(
+            store.defaults.get(DEFAULT_PROFILE).copied(),
+            Some(Provider::AccuWeather)
+        );
+    }
+
+    #[test]
+    fn configure_with_language_persists_language() {
+        let mut store = InMemoryStore::default();
+        let mut prompter = MockPrompter {
+            overwrite_answer: true,
+            set_default_answer: true,
+            credentials_to_return: sample_weatherapi_creds(),
+            overwrite_called: false,
+            set_default_called: false,
+            credentials_prompt_called: false,
+            selected_provider: None,
+            select_called: false,
+            optional_offered: HashMap::new(),
+        };
+
+        ConfigureHandler::new(&mut store, &mut prompter)
+            .run(Some(ProviderCli::WeatherApi), Some("uk".to_string()))
+            .expect("configuration should succeed");
+
+        assert_eq!(store.language.as_deref(), Some("uk"));
+    }
+
+    #[test]
+    fn configure_with_profile_stores_credentials_under_profile() {
+        let provider = ProviderCli::WeatherApi;
+
+        let mut store = InMemoryStore::default();
+        let mut prompter = MockPrompter {
+            overwrite_answer: true,
+            set_default_answer: true,
+            credentials_to_return: sample_weatherapi_creds(),
+            overwrite_called: false,
+            set_default_called: false,
+            credentials_prompt_called: false,
+            selected_provider: None,
+            select_called: false,
+            optional_offered: HashMap::new(),
+        };
+
+        ConfigureHandler::new(&mut store, &mut prompter)
+            .with_profile("work")
+            .run(Some(provider), None)
+            .expect("configuration should succeed");
+
+        assert!(
+            store
+                .providers
+                .contains_key(&("work".to_string(), provider.into()))
+        );
+        assert!(
+            !store
+                .providers
+                .contains_key(&(DEFAULT_PROFILE.to_string(), provider.into()))
+        );
+        assert_eq!(store.defaults.get("work").copied(), Some(provider.into()));
+        assert!(!store.defaults.contains_key(DEFAULT_PROFILE));
     }
 
     #[test]
@@ -181,12 +587,16 @@ mod tests {
         };
 
         let mut store = InMemoryStore {
-            default: Some(provider.into()),
+            defaults: HashMap::from([(DEFAULT_PROFILE.to_string(), provider.into())]),
             providers: {
                 let mut m = HashMap::new();
-                m.insert(provider.into(), existing_creds.clone());
+                m.insert(
+                    (DEFAULT_PROFILE.to_string(), provider.into()),
+                    existing_creds.clone(),
+                );
                 m
             },
+            ..Default::default()
         };
 
         let mut prompter = MockPrompter {
@@ -196,15 +606,18 @@ mod tests {
             overwrite_called: false,
             set_default_called: false,
             credentials_prompt_called: false,
+            selected_provider: None,
+            select_called: false,
+            optional_offered: HashMap::new(),
         };
 
         ConfigureHandler::new(&mut store, &mut prompter)
-            .run(provider)
+            .run(Some(provider), None)
             .expect("configuration should succeed");
 
         let saved = store
             .providers
-            .get(&provider.into())
+            .get(&(DEFAULT_PROFILE.to_string(), provider.into()))
             .cloned()
             .expect("credentials must be present");
 
@@ -214,7 +627,10 @@ mod tests {
                     api_key: "EXISTING_KEY".to_string()
                 }
         );
-        assert_eq!(store.default, Some(provider.into()));
+        assert_eq!(
+            store.defaults.get(DEFAULT_PROFILE).copied(),
+            Some(provider.into())
+        );
         assert!(prompter.overwrite_called);
         assert!(!prompter.credentials_prompt_called);
         assert!(!prompter.set_default_called);
@@ -227,15 +643,20 @@ mod tests {
 
         let existing_creds = Credentials::AccuWeather {
             api_key: "OLD_KEY".to_string(),
+            base_url: None,
         };
 
         let mut store = InMemoryStore {
-            default: Some(other.into()), // some other provider is default
+            defaults: HashMap::from([(DEFAULT_PROFILE.to_string(), other.into())]), // some other provider is default
             providers: {
                 let mut m = HashMap::new();
-                m.insert(provider.into(), existing_creds);
+                m.insert(
+                    (DEFAULT_PROFILE.to_string(), provider.into()),
+                    existing_creds,
+                );
                 m
             },
+            ..Default::default()
         };
 
         let mut prompter = MockPrompter {
@@ -243,29 +664,37 @@ mod tests {
             set_default_answer: true,
             credentials_to_return: Credentials::AccuWeather {
                 api_key: "NEW_KEY".to_string(),
+                base_url: None,
             },
             overwrite_called: false,
             set_default_called: false,
             credentials_prompt_called: false,
+            selected_provider: None,
+            select_called: false,
+            optional_offered: HashMap::new(),
         };
 
         ConfigureHandler::new(&mut store, &mut prompter)
-            .run(provider)
+            .run(Some(provider), None)
             .expect("configuration should succeed");
 
         let saved = store
             .providers
-            .get(&provider.into())
+            .get(&(DEFAULT_PROFILE.to_string(), provider.into()))
             .cloned()
             .expect("credentials must be present");
 
         assert!(
             saved
                 == Credentials::AccuWeather {
-                    api_key: "NEW_KEY".to_string()
+                    api_key: "NEW_KEY".to_string(),
+                    base_url: None,
                 }
         );
-        assert_eq!(store.default, Some(provider.into()));
+        assert_eq!(
+            store.defaults.get(DEFAULT_PROFILE).copied(),
+            Some(provider.into())
+        );
         assert!(prompter.overwrite_called);
         assert!(prompter.credentials_prompt_called);
         assert!(prompter.set_default_called);
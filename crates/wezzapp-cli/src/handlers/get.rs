@@ -1,20 +1,161 @@
 use crate::cli::ProviderCli;
-use crate::store::TomlFileCredentialsStore;
-use anyhow::Result;
-use tracing::debug;
-use wezzapp_core::apis::{HttpProviderClientFactory, WeatherReport};
-use wezzapp_core::weather_service::WeatherService;
+use crate::i18n::Locale;
+use crate::render::{
+    Format, Icons, RenderOptions, TemperatureUnit, render, render_current, render_field,
+    render_json_both_units,
+};
+use crate::template::Template;
+use crate::watch::WatchClock;
+use anyhow::{Context, Result, anyhow};
+use chrono::{Local, NaiveDateTime};
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::time::Duration;
+use tracing::{debug, trace, warn};
+use wezzapp_core::apis::{ProviderClientFactory, WeatherReport, redact};
+use wezzapp_core::credentials::ServiceStore;
+use wezzapp_core::error::WeatherError;
+use wezzapp_core::weather_service::{ResolutionSource, WeatherService};
+
+/// Clears the terminal and moves the cursor home, before each `--watch` redraw.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Forecast high crossed `--alert-above` or `--alert-below`, exits with `exit_code::THRESHOLD`.
+#[derive(Debug)]
+pub struct ThresholdCrossed;
+
+impl fmt::Display for ThresholdCrossed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("forecast crossed an alert threshold")
+    }
+}
+
+impl std::error::Error for ThresholdCrossed {}
 
 /// `get` command handler.
-pub struct GetHandler {
-    service: WeatherService<TomlFileCredentialsStore, HttpProviderClientFactory>,
+///
+/// Rendered output goes to `writer` (stdout or `--output` file), so tests can inject a buffer.
+pub struct GetHandler<S, F, W>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+    W: Write,
+{
+    service: WeatherService<S, F>,
+    writer: W,
+    format: Format,
+    options: RenderOptions,
+    /// Write JSON temperatures in both °C and °F.
+    both_units: bool,
+    raw: bool,
+    /// Single report field to print instead of the whole report.
+    field: Option<String>,
+    /// Custom layout to render reports with instead of `format`.
+    template: Option<Template>,
+    /// Alias to save the address under after a successful lookup.
+    save_as: Option<String>,
+    /// Warn and fail when the forecast high is above this, in °C.
+    alert_above: Option<f64>,
+    /// Warn and fail when the forecast high is below this, in °C.
+    alert_below: Option<f64>,
 }
 
-impl GetHandler {
-    pub fn new(
-        service: WeatherService<TomlFileCredentialsStore, HttpProviderClientFactory>,
-    ) -> Self {
-        Self { service }
+impl<S, F, W> GetHandler<S, F, W>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+    W: Write,
+{
+    pub fn with_writer(service: WeatherService<S, F>, writer: W) -> Self {
+        Self {
+            service,
+            writer,
+            format: Format::default(),
+            options: RenderOptions::default(),
+            both_units: false,
+            raw: false,
+            field: None,
+            template: None,
+            save_as: None,
+            alert_above: None,
+            alert_below: None,
+        }
+    }
+
+    /// Set output format for rendered reports.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Enable colored text output.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.options.color = color;
+        self
+    }
+
+    /// Round temperatures in text and compact output to `precision` decimals.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.options.precision = precision;
+        self
+    }
+
+    /// Show temperatures in text and compact output in `units`, converting from °C.
+    pub fn with_units(mut self, units: TemperatureUnit) -> Self {
+        self.options.units = units;
+        self
+    }
+
+    /// Write every JSON temperature twice, as `<field>_c` and `<field>_f`.
+    ///
+    /// Text and compact output are unaffected.
+    pub fn with_both_units(mut self, both_units: bool) -> Self {
+        self.both_units = both_units;
+        self
+    }
+
+    /// Write provider's untouched JSON response instead of the rendered report.
+    pub fn with_raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// Write only the given report field (one of `REPORT_FIELDS`), undecorated.
+    pub fn with_field(mut self, field: Option<String>) -> Self {
+        self.field = field;
+        self
+    }
+
+    /// Render reports with a custom `--template` layout instead of the format.
+    pub fn with_template(mut self, template: Option<Template>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Save the address of a successful `run` under `alias`.
+    pub fn with_save_as(mut self, alias: Option<String>) -> Self {
+        self.save_as = alias;
+        self
+    }
+
+    /// Set condition icons for compact output.
+    pub fn with_icons(mut self, icons: Icons) -> Self {
+        self.options.icons = icons;
+        self
+    }
+
+    /// Set language of text output labels.
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.options.locale = locale;
+        self
+    }
+
+    /// Fail with `ThresholdCrossed` after writing a report whose high is above `above`
+    /// or below `below`, both in the display unit.
+    pub fn with_alert_thresholds(mut self, above: Option<f64>, below: Option<f64>) -> Self {
+        self.alert_above = above;
+        self.alert_below = below;
+        self
     }
 
     /// Run the `get` flow.
@@ -23,6 +164,8 @@ impl GetHandler {
     /// - Load credentials for that provider.
     /// - Create provider client from factory.
     /// - Fetch weather and print human-readable output.
+    /// - Save address under an alias, if requested.
+    /// - Check the forecast high against alert thresholds, if set.
     pub fn run(
         &mut self,
         address: String,
@@ -30,24 +173,1032 @@ impl GetHandler {
         provider: Option<ProviderCli>,
     ) -> Result<()> {
         debug!(
-            "Running get handler with address: {:?}, date: {:?}, provider: {:?}",
-            address, date, provider
+            "Running get handler with address: {}, date: {:?}, provider: {:?}",
+            redact::address(&address),
+            date,
+            provider
         );
 
+        if self.raw {
+            let output = self.fetch(address.clone(), date, provider)?;
+            self.write_output(&output)?;
+            return self.save_alias(&address);
+        }
+
+        let report = self.fetch_report(address.clone(), date, provider)?;
+        let output = self.render(&report);
+        self.write_output(&output)?;
+        self.save_alias(&address)?;
+
+        self.check_thresholds(&[report])
+    }
+
+    /// Ping `provider`, or the default one, failing early if it rejects credentials or is down.
+    pub fn preflight(&mut self, provider: Option<ProviderCli>) -> Result<()> {
+        self.service
+            .preflight(provider.map(Into::into))
+            .context("preflight check failed")
+    }
+
+    /// Describe which provider served the last request and why, for `--explain`.
+    ///
+    /// `None` if no request got as far as picking a provider.
+    pub fn explain(&self) -> Option<String> {
+        let (provider, source) = self.service.resolution()?;
+        let source = match source {
+            ResolutionSource::Flag => "--provider flag".to_string(),
+            ResolutionSource::StoredDefault => "stored default".to_string(),
+            ResolutionSource::OnlyConfigured => "only configured provider".to_string(),
+            ResolutionSource::AutoFallback { requested } => {
+                format!(
+                    "--auto-provider, {} can't forecast that far",
+                    requested.as_str()
+                )
+            }
+        };
+
+        Some(format!(
+            "provider: {} (source: {source})",
+            provider.as_str()
+        ))
+    }
+
+    /// Run the `get --now` flow: fetch real-time conditions instead of a daily forecast.
+    pub fn run_current(&mut self, address: String, provider: Option<ProviderCli>) -> Result<()> {
+        debug!(
+            "Running get handler for current conditions at {}, provider: {provider:?}",
+            redact::address(&address)
+        );
+
+        let current = self
+            .service
+            .get_current(address.clone(), provider.map(Into::into))?;
+        trace!("Current conditions: {current:?}");
+
+        let output = if self.both_units && matches!(self.format, Format::Json | Format::JsonPretty)
+        {
+            render_json_both_units(&current, self.format == Format::JsonPretty)
+        } else {
+            render_current(&current, self.format, &self.options)
+        };
+        self.write_output(&output)?;
+
+        self.save_alias(&address)
+    }
+
+    /// Run the `get` flow for every day from `from` to `to` (inclusive), one report per day.
+    pub fn run_range(
+        &mut self,
+        address: String,
+        from: String,
+        to: String,
+        provider: Option<ProviderCli>,
+    ) -> Result<()> {
+        debug!(
+            "Running get handler with address: {}, from: {:?}, to: {:?}, provider: {:?}",
+            redact::address(&address),
+            from,
+            to,
+            provider
+        );
+
+        let reports = self.service.get_weather_range(
+            address.clone(),
+            &from,
+            &to,
+            provider.map(Into::into),
+        )?;
+        let array = self.json_array();
+        for (index, report) in reports.iter().enumerate() {
+            let output = self.render(report);
+            if array {
+                self.write_element(index, &output)?;
+            } else {
+                self.write_output(&output)?;
+            }
+        }
+        if array {
+            self.end_array(reports.len())?;
+        }
+        self.save_alias(&address)?;
+
+        self.check_thresholds(&reports)
+    }
+
+    /// Warn on stderr about every report crossing an alert threshold, failing if any did.
+    fn check_thresholds(&self, reports: &[WeatherReport]) -> Result<()> {
+        let mut crossed = false;
+
+        let unit = self.options.units.symbol();
+        for report in reports {
+            let high = self.options.units.convert(report.max_temperature);
+            let warning = match (self.alert_above, self.alert_below) {
+                (Some(above), _) if high > above => Some(format!("above {above}{unit}")),
+                (_, Some(below)) if high < below => Some(format!("below {below}{unit}")),
+                _ => None,
+            };
+            if let Some(warning) = warning {
+                crossed = true;
+                eprintln!(
+                    "warning: {} on {}: high of {high:.precision$}{unit} is {warning}",
+                    report.location,
+                    report.date,
+                    precision = self.options.precision
+                );
+            }
+        }
+
+        if crossed {
+            return Err(ThresholdCrossed.into());
+        }
+
+        Ok(())
+    }
+
+    /// Run the `get` flow every `interval` until `clock` says to stop, redrawing the report.
+    ///
+    /// A failed refresh keeps the last good report on screen with an error note,
+    /// only a failure before the first report aborts watching.
+    pub fn run_watch(
+        &mut self,
+        address: String,
+        date: Option<String>,
+        provider: Option<ProviderCli>,
+        interval: Duration,
+        clock: &mut impl WatchClock,
+    ) -> Result<()> {
+        debug!(
+            "Running get handler with address: {}, date: {:?}, provider: {:?}, every {:?}",
+            redact::address(&address),
+            date,
+            provider,
+            interval
+        );
+
+        // Last report shown and when it was fetched.
+        let mut last_good: Option<(String, NaiveDateTime)> = None;
+        loop {
+            match self.fetch(address.clone(), date.clone(), provider) {
+                Ok(output) => {
+                    // A report served from the cache is as old as when it was fetched.
+                    let updated_at = match self.service.cached_at() {
+                        Some(fetched_at) => fetched_at.with_timezone(&Local).naive_local(),
+                        None => clock.now(),
+                    };
+                    self.redraw(&output, updated_at, None, interval)?;
+                    if last_good.is_none() {
+                        self.save_alias(&address)?;
+                    }
+                    last_good = Some((output, updated_at));
+                }
+                Err(err) => match &last_good {
+                    Some((output, updated_at)) => {
+                        warn!(
+                            "Failed to refresh weather for address {}: {err}",
+                            redact::address(&address)
+                        );
+                        let (output, updated_at) = (output.clone(), *updated_at);
+                        self.redraw(&output, updated_at, Some(&err), interval)?;
+                    }
+                    None => return Err(err.into()),
+                },
+            }
+
+            if !clock.wait(interval) {
+                debug!("Stopped watching address {}", redact::address(&address));
+                return Ok(());
+            }
+        }
+    }
+
+    /// Clear the screen and write `output` fetched at `updated_at`,
+    /// noting a failed refresh if there was one.
+    fn redraw(
+        &mut self,
+        output: &str,
+        updated_at: NaiveDateTime,
+        error: Option<&WeatherError>,
+        interval: Duration,
+    ) -> Result<()> {
+        write!(self.writer, "{CLEAR_SCREEN}").context("failed to clear screen")?;
+        self.write_output(output)?;
+        if let Some(err) = error {
+            writeln!(self.writer, "\n(update failed: {err}, showing last report)")
+                .context("failed to write weather report")?;
+        }
+        writeln!(
+            self.writer,
+            "\nUpdated at {}, refreshing every {}s, press Ctrl-C to stop.",
+            updated_at.format("%H:%M:%S"),
+            interval.as_secs()
+        )
+        .context("failed to write weather report")?;
+
+        self.writer.flush().context("failed to flush output")
+    }
+
+    /// Save `address` under the alias set by `with_save_as`, if any.
+    fn save_alias(&mut self, address: &str) -> Result<()> {
+        if let Some(alias) = &self.save_as {
+            self.service.save_location_alias(alias, address)?;
+            // Keep stdout clean for piped reports.
+            eprintln!("Location saved as `{alias}`.");
+        }
+
+        Ok(())
+    }
+
+    /// Run the `get` flow for every newline-separated address read from `reader`.
+    ///
+    /// Reports are written as soon as each lookup completes. A failed address is
+    /// reported to stderr and does not abort the rest of the batch.
+    pub fn run_batch<R: BufRead>(
+        &mut self,
+        reader: R,
+        provider: Option<ProviderCli>,
+    ) -> Result<()> {
+        debug!(
+            "Running get handler in batch mode, provider: {:?}",
+            provider
+        );
+
+        let mut total = 0;
+        let mut failed = 0;
+        let array = self.json_array();
+
+        for line in reader.lines() {
+            let line = line.context("failed to read addresses")?;
+            let address = line.trim();
+            if address.is_empty() {
+                continue;
+            }
+            total += 1;
+
+            match self.fetch(address.to_string(), None, provider) {
+                Ok(output) if array => {
+                    self.write_element(total - failed - 1, &output)?;
+                    self.writer.flush().context("failed to flush output")?;
+                }
+                Ok(output) => {
+                    self.write_output(&output)?;
+                    self.writer.flush().context("failed to flush output")?;
+                }
+                Err(err) => {
+                    failed += 1;
+                    warn!(
+                        "Failed to get weather for address {}: {err}",
+                        redact::address(address)
+                    );
+                    eprintln!("{address}: {err}");
+                }
+            }
+        }
+        if array {
+            self.end_array(total - failed)?;
+        }
+        debug!("Batch finished: {failed} of {total} addresses failed");
+
+        if failed > 0 {
+            return Err(anyhow!("{failed} of {total} addresses failed"));
+        }
+
+        Ok(())
+    }
+
+    /// Fetch weather and render it, or the raw provider response with `raw` set.
+    fn fetch(
+        &mut self,
+        address: String,
+        date: Option<String>,
+        provider: Option<ProviderCli>,
+    ) -> Result<String, WeatherError> {
+        if self.raw {
+            let body = self
+                .service
+                .get_weather_raw(address, date, provider.map(Into::into))?;
+            return Ok(body.to_string());
+        }
+
+        let report = self.fetch_report(address, date, provider)?;
+
+        Ok(self.render(&report))
+    }
+
+    /// Fetch the mapped report.
+    fn fetch_report(
+        &mut self,
+        address: String,
+        date: Option<String>,
+        provider: Option<ProviderCli>,
+    ) -> Result<WeatherReport, WeatherError> {
         let report = self
             .service
             .get_weather(address, date, provider.map(Into::into))?;
-        debug!("Weather report: {:?}", report);
+        trace!("Weather report: {:?}", report);
+
+        Ok(report)
+    }
 
-        self.render_report(report);
+    /// Render report in the configured format or template, or just the selected field.
+    fn render(&self, report: &WeatherReport) -> String {
+        if let Some(template) = &self.template {
+            return template.render(report, self.options.precision, self.options.units);
+        }
 
-        Ok(())
+        match &self.field {
+            Some(field) => render_field(report, field),
+            None if self.both_units && matches!(self.format, Format::Json | Format::JsonPretty) => {
+                render_json_both_units(report, self.format == Format::JsonPretty)
+            }
+            None => render(report, self.format, &self.options),
+        }
+    }
+
+    /// Whether reports of a batch or range are written as a single JSON array.
+    fn json_array(&self) -> bool {
+        self.field.is_none()
+            && self.template.is_none()
+            && matches!(self.format, Format::Json | Format::JsonPretty)
+    }
+
+    /// Write `output` as element `index` of a JSON array, opening the array before the first.
+    fn write_element(&mut self, index: usize, output: &str) -> Result<()> {
+        let separator = if index == 0 { "[" } else { "," };
+        let written = if self.format == Format::JsonPretty {
+            let indented: Vec<_> = output.lines().map(|line| format!("  {line}")).collect();
+            write!(self.writer, "{separator}\n{}", indented.join("\n"))
+        } else {
+            write!(self.writer, "{separator}{output}")
+        };
+
+        written.context("failed to write weather report")
+    }
+
+    /// Close a JSON array of `len` elements written with `write_element`.
+    fn end_array(&mut self, len: usize) -> Result<()> {
+        let end = match (len, self.format) {
+            (0, _) => "[]",
+            (_, Format::JsonPretty) => "\n]",
+            _ => "]",
+        };
+
+        writeln!(self.writer, "{end}").context("failed to write weather report")
+    }
+
+    fn write_output(&mut self, output: &str) -> Result<()> {
+        writeln!(self.writer, "{output}").context("failed to write weather report")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Days, Local, TimeZone};
+    use rstest::rstest;
+    use serde_json::Value;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use wezzapp_core::air_quality::{AirQuality, AqiCategory};
+    use wezzapp_core::alert::WeatherAlert;
+    use wezzapp_core::apis::report_cache::ReportCache;
+    use wezzapp_core::credentials::{Credentials, DEFAULT_PROFILE};
+    use wezzapp_core::provider::Provider;
+    use wezzapp_core::testing::{self, FakeClient, FakeFactory, InMemoryStore};
+
+    /// Client returning `testing::report()` for any address but "Nowhere", which isn't found.
+    fn client() -> FakeClient {
+        FakeClient::default().with_response(|address, _days| {
+            if address == "Nowhere" {
+                return Err(WeatherError::AddressNotFound);
+            }
+
+            Ok(WeatherReport {
+                location: address.to_string(),
+                ..testing::report()
+            })
+        })
+    }
+
+    /// Client failing all fetches after the first `ok_fetches`.
+    fn failing_after(ok_fetches: u32) -> FakeClient {
+        let fetches = AtomicU32::new(0);
+
+        FakeClient::default().with_response(move |address, _days| {
+            if fetches.fetch_add(1, Ordering::SeqCst) >= ok_fetches {
+                return Err(WeatherError::RateLimited);
+            }
+
+            Ok(WeatherReport {
+                location: address.to_string(),
+                ..testing::report()
+            })
+        })
+    }
+
+    /// Clock allowing a fixed number of waits, recording the requested intervals.
+    #[derive(Default)]
+    struct FakeClock {
+        waits_left: u32,
+        waited: Vec<Duration>,
+        /// Starts at the epoch, advanced by every wait.
+        now: NaiveDateTime,
+    }
+
+    impl WatchClock for FakeClock {
+        fn wait(&mut self, interval: Duration) -> bool {
+            if self.waits_left == 0 {
+                return false;
+            }
+            self.waits_left -= 1;
+            self.waited.push(interval);
+            self.now += interval;
+            true
+        }
+
+        fn now(&self) -> NaiveDateTime {
+            self.now
+        }
+    }
+
+    fn configured_service() -> WeatherService<InMemoryStore, FakeFactory> {
+        configured_service_with(FakeFactory::new(client()))
     }
 
-    /// Renders weather report
-    /// Can be moved to separate render layer if needed
-    fn render_report(&mut self, report: WeatherReport) {
-        debug!("Rendering report: {:?}", report);
-        println!("{:?}", report);
+    fn configured_service_with<F: ProviderClientFactory>(
+        factory: F,
+    ) -> WeatherService<InMemoryStore, F> {
+        WeatherService::new(configured_store(), factory)
+    }
+
+    fn configured_store() -> InMemoryStore {
+        InMemoryStore {
+            defaults: HashMap::from([(DEFAULT_PROFILE.to_string(), Provider::WeatherApi)]),
+            providers: HashMap::from([(
+                (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+                Credentials::WeatherApi {
+                    api_key: "TEST_KEY".to_string(),
+                },
+            )]),
+            ..Default::default()
+        }
+    }
+
+    fn watch(ok_fetches: u32, waits: u32) -> (Result<()>, String, FakeClient, FakeClock) {
+        let client = failing_after(ok_fetches);
+        let factory = FakeFactory::new(client.clone());
+        let mut handler = GetHandler::with_writer(configured_service_with(factory), Vec::new())
+            .with_format(Format::Compact);
+        let mut clock = FakeClock {
+            waits_left: waits,
+            ..Default::default()
+        };
+
+        let result = handler.run_watch(
+            "Kyiv".to_string(),
+            None,
+            None,
+            Duration::from_secs(300),
+            &mut clock,
+        );
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        (result, output, client, clock)
+    }
+
+    #[test]
+    fn run_watch_fetches_once_per_interval_until_stopped() {
+        let (result, output, client, clock) = watch(u32::MAX, 2);
+
+        result.expect("watch should succeed");
+        assert_eq!(client.forecasts().len(), 3);
+        assert_eq!(clock.waited, vec![Duration::from_secs(300); 2]);
+        assert_eq!(output.matches(CLEAR_SCREEN).count(), 3, "{output:?}");
+        assert!(
+            output.ends_with(
+                "Kyiv 2024-11-29 ☀ 12°/3°C\n\n\
+                 Updated at 00:10:00, refreshing every 300s, press Ctrl-C to stop.\n"
+            ),
+            "{output:?}"
+        );
+    }
+
+    #[test]
+    fn run_watch_keeps_last_report_on_failed_refresh() {
+        let (result, output, client, _) = watch(1, 1);
+
+        result.expect("watch should keep going after first report");
+        assert_eq!(client.forecasts().len(), 2);
+        let last_frame = output.rsplit(CLEAR_SCREEN).next().unwrap();
+        assert!(last_frame.contains("Kyiv 2024-11-29"), "{last_frame:?}");
+        assert!(
+            last_frame.contains("(update failed: provider rate limit exceeded"),
+            "{last_frame:?}"
+        );
+        assert!(
+            last_frame.contains("Updated at 00:00:00,"),
+            "{last_frame:?}"
+        );
+    }
+
+    /// Report cache holding one report for every key, fetched at `fetched_at`.
+    #[derive(Debug)]
+    struct FixedCache {
+        fetched_at: chrono::DateTime<chrono::Utc>,
+    }
+
+    impl ReportCache for FixedCache {
+        fn get(&self, _key: &str) -> Option<(WeatherReport, chrono::DateTime<chrono::Utc>)> {
+            Some((testing::report(), self.fetched_at))
+        }
+
+        fn put(&self, _key: &str, _report: &WeatherReport) {}
+    }
+
+    #[test]
+    fn run_watch_shows_when_cached_report_was_fetched() {
+        let client = client();
+        let factory = FakeFactory::new(client.clone());
+        let fetched_at = Local.with_ymd_and_hms(2024, 11, 29, 9, 30, 0).unwrap();
+        let service = WeatherService::builder(configured_store(), factory)
+            .with_report_cache(Arc::new(FixedCache {
+                fetched_at: fetched_at.to_utc(),
+            }))
+            .with_fresh_for(Duration::MAX)
+            .build();
+        let mut handler = GetHandler::with_writer(service, Vec::new()).with_format(Format::Compact);
+
+        handler
+            .run_watch(
+                "Kyiv".to_string(),
+                None,
+                None,
+                Duration::from_secs(300),
+                &mut FakeClock::default(),
+            )
+            .expect("watch should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(client.forecasts().len(), 0);
+        assert!(output.contains("Updated at 09:30:00,"), "{output:?}");
+    }
+
+    #[test]
+    fn run_watch_fails_without_first_report() {
+        let (result, output, client, clock) = watch(0, 5);
+
+        let err = result.expect_err("watch should fail");
+        assert!(
+            matches!(err.downcast_ref(), Some(WeatherError::RateLimited)),
+            "{err:?}"
+        );
+        assert_eq!(client.forecasts().len(), 1);
+        assert!(clock.waited.is_empty());
+        assert!(output.is_empty(), "{output:?}");
+    }
+
+    #[test]
+    fn run_writes_report_to_injected_writer() {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new());
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert!(
+            output.contains("Kyiv, Ukraine"),
+            "unexpected output: {output}"
+        );
+        assert!(output.contains("Sunny"), "unexpected output: {output}");
+        assert!(output.ends_with('\n'), "output should end with newline");
+    }
+
+    #[rstest]
+    #[case::flag(
+        Some(ProviderCli::WeatherApi),
+        "provider: weatherapi (source: --provider flag)"
+    )]
+    #[case::stored_default(None, "provider: weatherapi (source: stored default)")]
+    fn explain_describes_provider_resolution(
+        #[case] provider: Option<ProviderCli>,
+        #[case] expected: &str,
+    ) {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new());
+        assert_eq!(handler.explain(), None);
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, provider)
+            .expect("get should succeed");
+
+        let explanation = handler
+            .explain()
+            .expect("a provider should have been picked");
+
+        assert_eq!(explanation, expected);
+    }
+
+    /// Text output of `get` for `report`, uncolored.
+    fn text_snapshot(report: WeatherReport) -> String {
+        let mut handler = GetHandler::with_writer(
+            configured_service_with(FakeFactory::new(FakeClient::default().with_report(report))),
+            Vec::new(),
+        );
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+
+        String::from_utf8(handler.writer).expect("output should be utf-8")
+    }
+
+    fn minimal_report() -> WeatherReport {
+        testing::report()
+    }
+
+    #[test]
+    fn text_snapshot_without_optional_fields() {
+        assert_eq!(
+            text_snapshot(minimal_report()),
+            "Location: Kyiv, Ukraine\n\
+             Date: 2024-11-29\n\
+             Provider: WeatherApi\n\
+             Conditions: Sunny\n\
+             Temperature: max 12°C, min 3°C\n"
+        );
+    }
+
+    #[test]
+    fn text_snapshot_with_all_optional_fields() {
+        let report = WeatherReport {
+            avg_temperature: Some(7.4),
+            feels_like: Some(1.6),
+            uv_index: Some(4.0),
+            wind_kph: Some(18.4),
+            wind_direction: Some("NW".to_string()),
+            humidity: Some(72.0),
+            sunrise: Some("07:31".parse().unwrap()),
+            sunset: Some("16:02".parse().unwrap()),
+            air_quality: Some(AirQuality {
+                index: Some(42.0),
+                category: AqiCategory::Good,
+                pm2_5: Some(9.5),
+                pm10: None,
+            }),
+            alerts: Some(vec![WeatherAlert {
+                headline: "Flood Warning".to_string(),
+                severity: Some("Moderate".to_string()),
+                areas: vec!["Kyiv".to_string()],
+            }]),
+            ..minimal_report()
+        };
+
+        assert_eq!(
+            text_snapshot(report),
+            "Location: Kyiv, Ukraine\n\
+             Date: 2024-11-29\n\
+             Provider: WeatherApi\n\
+             Conditions: Sunny\n\
+             Temperature: max 12°C, min 3°C, avg 7°C\n\
+             Feels like: 2°C\n\
+             UV index: 4 (moderate)\n\
+             Wind: 18 kph NW\n\
+             Humidity: 72%\n\
+             Sunrise: 07:31\n\
+             Sunset: 16:02\n\
+             AQI: 42 (Good), PM2.5 9.5 μg/m³\n\
+             Alert: Flood Warning (Moderate)\n  \
+             Areas: Kyiv\n"
+        );
+    }
+
+    #[test]
+    fn run_current_writes_observed_conditions() {
+        let mut handler =
+            GetHandler::with_writer(configured_service(), Vec::new()).with_format(Format::Compact);
+
+        handler
+            .run_current("Kyiv, Ukraine".to_string(), None)
+            .expect("get --now should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(output, "Kyiv now ☀ 7°C\n");
+    }
+
+    #[test]
+    fn run_converts_temperatures_to_display_units() {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new())
+            .with_format(Format::Compact)
+            .with_units(TemperatureUnit::Fahrenheit);
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(output, "Kyiv 2024-11-29 ☀ 54°/37°F\n");
+    }
+
+    #[test]
+    fn run_renders_template() {
+        let template = Template::parse("{location} {date}: {max}/{min}{unit}").unwrap();
+        let mut handler =
+            GetHandler::with_writer(configured_service(), Vec::new()).with_template(Some(template));
+
+        handler
+            .run("Kyiv".to_string(), None, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(output, "Kyiv 2024-11-29: 12/3°C\n");
+    }
+
+    #[test]
+    fn run_writes_compact_format() {
+        let mut handler =
+            GetHandler::with_writer(configured_service(), Vec::new()).with_format(Format::Compact);
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(output, "Kyiv 2024-11-29 ☀ 12°/3°C\n");
+    }
+
+    #[test]
+    fn run_writes_json_to_output_file() {
+        let tmpdir = tempfile::tempdir().expect("create temp dir");
+        let path = tmpdir.path().join("forecast.json");
+        let file = std::fs::File::create(&path).expect("create output file");
+        let mut handler =
+            GetHandler::with_writer(configured_service(), file).with_format(Format::Json);
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+        drop(handler);
+
+        let output = std::fs::read_to_string(&path).expect("read output file");
+        let json: serde_json::Value = serde_json::from_str(&output).expect("output should be json");
+        assert_eq!(json["location"], "Kyiv, Ukraine");
+        assert_eq!(json["provider"], "weatherapi");
+        assert_eq!(json["condition"], "clear");
+        assert_eq!(json["max_temperature"], 12.0);
+        assert!(json["air_quality"].is_null());
+    }
+
+    #[test]
+    fn run_raw_writes_provider_json() {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new()).with_raw(true);
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(
+            output,
+            "{\"provider\":\"weatherapi\",\"location\":{\"name\":\"Kyiv, Ukraine\"}}\n"
+        );
+    }
+
+    #[test]
+    fn run_saves_alias_and_resolves_it_later() {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new())
+            .with_format(Format::Compact)
+            .with_save_as(Some("home".to_string()));
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+
+        let mut handler =
+            GetHandler::with_writer(handler.service, Vec::new()).with_format(Format::Compact);
+        handler
+            .run("home".to_string(), None, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(output, "Kyiv 2024-11-29 ☀ 12°/3°C\n");
+    }
+
+    #[test]
+    fn run_range_writes_report_per_day() {
+        let from = (Local::now().date_naive() + Days::new(1)).to_string();
+        let to = (Local::now().date_naive() + Days::new(3)).to_string();
+        let mut handler =
+            GetHandler::with_writer(configured_service(), Vec::new()).with_format(Format::Compact);
+
+        handler
+            .run_range("Kyiv".to_string(), from, to, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(output.lines().count(), 3, "{output}");
+    }
+
+    #[test]
+    fn run_range_writes_json_array() {
+        let from = (Local::now().date_naive() + Days::new(1)).to_string();
+        let to = (Local::now().date_naive() + Days::new(3)).to_string();
+        let mut handler =
+            GetHandler::with_writer(configured_service(), Vec::new()).with_format(Format::Json);
+
+        handler
+            .run_range("Kyiv".to_string(), from, to, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        let json: Value = serde_json::from_str(&output).expect("output should be one JSON value");
+        assert_eq!(json.as_array().map(Vec::len), Some(3), "{output}");
+    }
+
+    #[test]
+    fn run_with_field_writes_bare_value() {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new())
+            .with_field(Some("max_temperature".to_string()));
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(output, "12.0\n");
+    }
+
+    #[test]
+    fn failed_lookup_saves_no_alias() {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new())
+            .with_save_as(Some("nowhere".to_string()));
+
+        handler
+            .run("Nowhere".to_string(), None, None)
+            .expect_err("get should fail");
+
+        // A saved alias would resolve to the failing `Nowhere` again.
+        let mut handler = GetHandler::with_writer(handler.service, Vec::new());
+        handler
+            .run("nowhere".to_string(), None, None)
+            .expect("alias should not exist");
+    }
+
+    #[test]
+    fn run_without_color_writes_no_color_codes() {
+        let mut handler =
+            GetHandler::with_writer(configured_service(), Vec::new()).with_color(false);
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert!(
+            !output.contains('\x1b'),
+            "unexpected color codes: {output:?}"
+        );
+    }
+
+    #[test]
+    fn run_with_color_writes_color_codes() {
+        let mut handler =
+            GetHandler::with_writer(configured_service(), Vec::new()).with_color(true);
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect("get should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert!(output.contains('\x1b'), "expected color codes: {output:?}");
+    }
+
+    #[rstest]
+    #[case::above_crossed(Some(10.0), None, true)]
+    #[case::above_not_crossed(Some(12.0), None, false)]
+    #[case::below_crossed(None, Some(15.0), true)]
+    #[case::below_not_crossed(None, Some(12.0), false)]
+    fn run_checks_forecast_high_against_thresholds(
+        #[case] above: Option<f64>,
+        #[case] below: Option<f64>,
+        #[case] crossed: bool,
+    ) {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new())
+            .with_format(Format::Compact)
+            .with_alert_thresholds(above, below);
+
+        let result = handler.run("Kyiv, Ukraine".to_string(), None, None);
+
+        match result {
+            Err(err) => assert!(crossed && err.is::<ThresholdCrossed>(), "{err:?}"),
+            Ok(()) => assert!(!crossed, "threshold should be crossed"),
+        }
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(
+            output, "Kyiv 2024-11-29 ☀ 12°/3°C\n",
+            "report is written either way"
+        );
+    }
+
+    #[rstest]
+    #[case::celsius(TemperatureUnit::Celsius, false)]
+    #[case::fahrenheit(TemperatureUnit::Fahrenheit, true)]
+    fn thresholds_are_in_display_unit(#[case] units: TemperatureUnit, #[case] crossed: bool) {
+        // 12°C is 53.6°F.
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new())
+            .with_units(units)
+            .with_alert_thresholds(Some(50.0), None);
+
+        let result = handler.run("Kyiv, Ukraine".to_string(), None, None);
+
+        assert_eq!(result.is_err(), crossed, "{result:?}");
+    }
+
+    #[test]
+    fn run_writes_nothing_on_error() {
+        let service = WeatherService::new(InMemoryStore::default(), FakeFactory::default());
+        let mut handler = GetHandler::with_writer(service, Vec::new());
+
+        handler
+            .run("Kyiv, Ukraine".to_string(), None, None)
+            .expect_err("get should fail without configured provider");
+
+        assert!(handler.writer.is_empty(), "no output expected on error");
+    }
+
+    #[test]
+    fn run_batch_writes_report_per_address() {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new());
+
+        handler
+            .run_batch("Kyiv, Ukraine\n\n  Lviv, Ukraine  \n".as_bytes(), None)
+            .expect("batch should succeed");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert_eq!(
+            output.matches("Location: ").count(),
+            2,
+            "unexpected output: {output}"
+        );
+        assert!(
+            output.contains("Location: Kyiv, Ukraine\n"),
+            "unexpected output: {output}"
+        );
+        assert!(
+            output.contains("Location: Lviv, Ukraine\n"),
+            "address should be trimmed: {output}"
+        );
+    }
+
+    #[rstest]
+    #[case::json(Format::Json)]
+    #[case::json_pretty(Format::JsonPretty)]
+    fn run_batch_writes_json_array(#[case] format: Format) {
+        let mut handler =
+            GetHandler::with_writer(configured_service(), Vec::new()).with_format(format);
+
+        handler
+            .run_batch("Kyiv, Ukraine\nNowhere\nLviv, Ukraine\n".as_bytes(), None)
+            .expect_err("batch should report failure");
+
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        let json: Value = serde_json::from_str(&output).expect("output should be one JSON value");
+        let locations: Vec<_> = json
+            .as_array()
+            .expect("output should be an array")
+            .iter()
+            .map(|report| report["location"].as_str().unwrap())
+            .collect();
+        assert_eq!(locations, ["Kyiv, Ukraine", "Lviv, Ukraine"]);
+    }
+
+    #[test]
+    fn run_batch_of_no_addresses_writes_empty_json_array() {
+        let mut handler =
+            GetHandler::with_writer(configured_service(), Vec::new()).with_format(Format::Json);
+
+        handler.run_batch("\n".as_bytes(), None).unwrap();
+
+        assert_eq!(String::from_utf8(handler.writer).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn run_batch_isolates_failed_addresses() {
+        let mut handler = GetHandler::with_writer(configured_service(), Vec::new());
+
+        let err = handler
+            .run_batch("Kyiv, Ukraine\nNowhere\nLviv, Ukraine\n".as_bytes(), None)
+            .expect_err("batch should report failure");
+
+        assert!(
+            err.to_string().contains("1 of 3"),
+            "unexpected error: {err}"
+        );
+        let output = String::from_utf8(handler.writer).expect("output should be utf-8");
+        assert!(
+            output.contains("Kyiv, Ukraine"),
+            "unexpected output: {output}"
+        );
+        assert!(
+            output.contains("Lviv, Ukraine"),
+            "unexpected output: {output}"
+        );
     }
 }
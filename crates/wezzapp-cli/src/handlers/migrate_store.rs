@@ -0,0 +1,236 @@
+use crate::store::ConfigStore;
+use anyhow::{Context, Result};
+use tracing::debug;
+use wezzapp_core::provider::Provider;
+
+/// `migrate-store` command handler.
+///
+/// Copies the whole config: language, saved locations, max forecast days overrides,
+/// icons, call counters and every profile's credentials and default provider.
+pub struct MigrateStoreHandler<S, D>
+where
+    S: ConfigStore,
+    D: ConfigStore,
+{
+    source: S,
+    destination: D,
+}
+
+/// What was copied by `MigrateStoreHandler::run`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MigrationSummary {
+    pub profiles: usize,
+    pub credentials: usize,
+    pub locations: usize,
+}
+
+impl<S, D> MigrateStoreHandler<S, D>
+where
+    S: ConfigStore,
+    D: ConfigStore,
+{
+    pub fn new(source: S, destination: D) -> Self {
+        Self {
+            source,
+            destination,
+        }
+    }
+
+    /// Copy everything from source to destination, overwriting what's already there.
+    pub fn run(&mut self) -> Result<MigrationSummary> {
+        let mut summary = MigrationSummary::default();
+
+        if let Some(language) = self.source.get_language()? {
+            self.destination
+                .set_language(&language)
+                .context("failed to migrate language")?;
+        }
+
+        for (alias, address) in self.source.list_location_aliases()? {
+            self.destination
+                .set_location_alias(&alias, &address)
+                .with_context(|| format!("failed to migrate saved location `{alias}`"))?;
+            summary.locations += 1;
+        }
+
+        for &provider in Provider::ALL {
+            if let Some(days) = self.source.get_max_forecast_days(provider)? {
+                self.destination
+                    .set_max_forecast_days(provider, days)
+                    .with_context(|| {
+                        format!("failed to migrate max forecast days of {provider:?}")
+                    })?;
+            }
+            if let Some((period, calls)) = self.source.get_usage(provider)? {
+                self.destination
+                    .set_usage(provider, &period, calls)
+                    .with_context(|| format!("failed to migrate {provider:?} call counter"))?;
+            }
+        }
+
+        let icons = self.source.get_icons()?;
+        if !icons.is_empty() {
+            self.destination
+                .set_icons(&icons)
+                .context("failed to migrate icons")?;
+        }
+
+        for profile in self.source.list_profiles()? {
+            debug!("Migrating profile {profile:?}");
+            for &provider in Provider::ALL {
+                let Some(credentials) = self.source.get_credentials(&profile, provider)? else {
+                    continue;
+                };
+                self.destination
+                    .set_credentials(&profile, provider, &credentials)
+                    .with_context(|| {
+                        format!("failed to migrate {provider:?} credentials of profile `{profile}`")
+                    })?;
+                summary.credentials += 1;
+            }
+
+            if let Some(default) = self.source.get_default_provider(&profile)? {
+                self.destination
+                    .set_default_provider(&profile, default)
+                    .with_context(|| {
+                        format!("failed to migrate default provider of profile `{profile}`")
+                    })?;
+            }
+            summary.profiles += 1;
+        }
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::TomlFileCredentialsStore;
+    use std::collections::HashMap;
+    use std::fs;
+    use wezzapp_core::condition::WeatherCondition;
+    use wezzapp_core::credentials::{Credentials, DEFAULT_PROFILE};
+    use wezzapp_core::testing::InMemoryStore;
+
+    fn api_key(key: &str) -> Credentials {
+        Credentials::WeatherApi {
+            api_key: key.into(),
+        }
+    }
+
+    #[test]
+    fn copies_every_profile_and_setting() {
+        let mut source = InMemoryStore {
+            defaults: HashMap::from([
+                (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+                ("work".to_string(), Provider::AccuWeather),
+            ]),
+            providers: HashMap::from([
+                (
+                    (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+                    api_key("A"),
+                ),
+                (
+                    (DEFAULT_PROFILE.to_string(), Provider::TomorrowIo),
+                    Credentials::TomorrowIo {
+                        api_key: "B".into(),
+                    },
+                ),
+                (
+                    ("work".to_string(), Provider::AccuWeather),
+                    Credentials::AccuWeather {
+                        api_key: "C".into(),
+                        base_url: None,
+                    },
+                ),
+            ]),
+            language: Some("uk".into()),
+            locations: HashMap::from([("home".to_string(), "Kyiv, Ukraine".to_string())]),
+            max_forecast_days: HashMap::from([(Provider::AccuWeather, 10)]),
+            usage: HashMap::from([(Provider::AccuWeather, ("2024-11".to_string(), 7))]),
+        };
+        let mut destination = InMemoryStore::default();
+
+        let summary = MigrateStoreHandler::new(&mut source, &mut destination)
+            .run()
+            .unwrap();
+
+        assert_eq!(
+            summary,
+            MigrationSummary {
+                profiles: 2,
+                credentials: 3,
+                locations: 1,
+            }
+        );
+        assert!(destination == source);
+    }
+
+    #[test]
+    fn overwrites_existing_destination_entries_and_keeps_others() {
+        let mut source = InMemoryStore {
+            providers: HashMap::from([(
+                (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+                api_key("NEW"),
+            )]),
+            ..Default::default()
+        };
+        let mut destination = InMemoryStore {
+            defaults: HashMap::from([(DEFAULT_PROFILE.to_string(), Provider::WeatherApi)]),
+            providers: HashMap::from([(
+                (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+                api_key("OLD"),
+            )]),
+            language: Some("en".into()),
+            ..Default::default()
+        };
+
+        MigrateStoreHandler::new(&mut source, &mut destination)
+            .run()
+            .unwrap();
+
+        assert!(
+            destination.providers[&(DEFAULT_PROFILE.to_string(), Provider::WeatherApi)]
+                == api_key("NEW")
+        );
+        assert_eq!(
+            destination.defaults.get(DEFAULT_PROFILE),
+            Some(&Provider::WeatherApi)
+        );
+        assert_eq!(destination.language.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn empty_source_migrates_nothing() {
+        let mut source = InMemoryStore::default();
+        let mut destination = InMemoryStore::default();
+
+        let summary = MigrateStoreHandler::new(&mut source, &mut destination)
+            .run()
+            .unwrap();
+
+        assert_eq!(summary, MigrationSummary::default());
+        assert!(destination == InMemoryStore::default());
+    }
+
+    #[test]
+    fn copies_icons_between_config_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_path = dir.path().join("source.toml");
+        let destination_path = dir.path().join("destination.toml");
+        fs::write(&source_path, "[icons]\nrain = \"☔\"\n").unwrap();
+        let source = TomlFileCredentialsStore::new_with_path(&source_path, false).unwrap();
+        let destination =
+            TomlFileCredentialsStore::new_with_path(&destination_path, false).unwrap();
+
+        MigrateStoreHandler::new(source, destination).run().unwrap();
+
+        let destination =
+            TomlFileCredentialsStore::new_with_path(&destination_path, false).unwrap();
+        assert_eq!(
+            destination.get_icons().unwrap(),
+            HashMap::from([(WeatherCondition::Rain, "☔".to_string())])
+        );
+    }
+}
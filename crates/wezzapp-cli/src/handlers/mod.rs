@@ -1,2 +1,9 @@
+pub mod compare;
+pub mod config;
 pub mod configure;
 pub mod get;
+pub mod migrate_store;
+pub mod providers;
+pub mod set_default;
+pub mod status;
+pub mod tui;
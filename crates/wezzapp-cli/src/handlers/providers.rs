@@ -0,0 +1,77 @@
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::io::Write;
+use wezzapp_core::provider::Provider;
+
+/// `providers` command handler.
+///
+/// Table goes to `writer`, so tests can inject a buffer.
+pub struct ProvidersHandler<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> ProvidersHandler<W> {
+    pub fn with_writer(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Write every known provider with what it supports.
+    pub fn run(&mut self) -> Result<()> {
+        write!(self.writer, "{}", render_providers(Provider::ALL))
+            .context("failed to write providers")
+    }
+}
+
+/// Per-provider capabilities table.
+fn render_providers(providers: &[Provider]) -> String {
+    let mut table = format!(
+        "{:<12} {:<14} {:<8} {:<8} {:<7} {:<12} HISTORY\n",
+        "PROVIDER", "FORECAST DAYS", "API KEY", "CURRENT", "ALERTS", "AIR QUALITY"
+    );
+
+    for provider in providers {
+        let capabilities = provider.capabilities();
+        let _ = writeln!(
+            table,
+            "{:<12} {:<14} {:<8} {:<8} {:<7} {:<12} {}",
+            provider.as_str(),
+            capabilities.max_forecast_days,
+            yes_no(capabilities.needs_api_key),
+            yes_no(capabilities.current),
+            yes_no(capabilities.alerts),
+            yes_no(capabilities.air_quality),
+            yes_no(capabilities.history),
+        );
+    }
+
+    table
+}
+
+fn yes_no(supported: bool) -> &'static str {
+    if supported { "yes" } else { "no" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_capabilities_table() {
+        assert_eq!(
+            render_providers(&[Provider::WeatherApi, Provider::TomorrowIo]),
+            "PROVIDER     FORECAST DAYS  API KEY  CURRENT  ALERTS  AIR QUALITY  HISTORY\n\
+             weatherapi   14             yes      yes      yes     yes          no\n\
+             tomorrowio   6              yes      yes      no      no           no\n"
+        );
+    }
+
+    #[test]
+    fn run_lists_every_provider() {
+        let mut handler = ProvidersHandler::with_writer(Vec::new());
+
+        handler.run().unwrap();
+
+        let output = String::from_utf8(handler.writer).unwrap();
+        assert_eq!(output.lines().count(), Provider::ALL.len() + 1, "{output}");
+    }
+}
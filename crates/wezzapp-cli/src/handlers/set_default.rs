@@ -0,0 +1,128 @@
+use crate::cli::ProviderCli;
+use anyhow::{Context, Result};
+use tracing::debug;
+use wezzapp_core::credentials::{CredentialsStore, DEFAULT_PROFILE};
+use wezzapp_core::error::WeatherError;
+use wezzapp_core::provider::Provider;
+
+/// `set-default` command handler.
+pub struct SetDefaultHandler<S>
+where
+    S: CredentialsStore,
+{
+    store: S,
+    /// Credentials profile to set the default of.
+    profile: String,
+}
+
+impl<S> SetDefaultHandler<S>
+where
+    S: CredentialsStore,
+{
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            profile: DEFAULT_PROFILE.to_string(),
+        }
+    }
+
+    /// Set the default of the given profile.
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Make `provider_cli` the default, failing if it has no stored credentials.
+    pub fn run(&mut self, provider_cli: ProviderCli) -> Result<()> {
+        let provider = Provider::from(provider_cli);
+        debug!(
+            "Setting default provider of profile `{}`: {provider:?}",
+            self.profile
+        );
+
+        if self
+            .store
+            .get_credentials(&self.profile, provider)?
+            .is_none()
+        {
+            return Err(WeatherError::MissingCredentials(provider).into());
+        }
+
+        self.store
+            .set_default_provider(&self.profile, provider)
+            .context("failed to set default provider")?;
+        println!("Provider `{provider_cli}` was set as default.");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use wezzapp_core::credentials::Credentials;
+    use wezzapp_core::testing::InMemoryStore;
+
+    /// WeatherAPI as default, but only AccuWeather credentials stored.
+    fn store() -> InMemoryStore {
+        InMemoryStore {
+            defaults: HashMap::from([(DEFAULT_PROFILE.to_string(), Provider::WeatherApi)]),
+            providers: HashMap::from([(
+                (DEFAULT_PROFILE.to_string(), Provider::AccuWeather),
+                Credentials::AccuWeather {
+                    api_key: "KEY".into(),
+                    base_url: None,
+                },
+            )]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sets_configured_provider_as_default() {
+        let mut store = store();
+
+        SetDefaultHandler::new(&mut store)
+            .run(ProviderCli::AccuWeather)
+            .unwrap();
+
+        assert_eq!(
+            store.defaults.get(DEFAULT_PROFILE),
+            Some(&Provider::AccuWeather)
+        );
+    }
+
+    #[test]
+    fn provider_without_credentials_is_rejected() {
+        let mut store = store();
+
+        let err = SetDefaultHandler::new(&mut store)
+            .run(ProviderCli::TomorrowIo)
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                err.downcast_ref::<WeatherError>(),
+                Some(WeatherError::MissingCredentials(Provider::TomorrowIo))
+            ),
+            "unexpected error: {err:?}"
+        );
+        assert_eq!(
+            store.defaults.get(DEFAULT_PROFILE),
+            Some(&Provider::WeatherApi)
+        );
+    }
+
+    #[test]
+    fn credentials_of_another_profile_do_not_count() {
+        let mut store = store();
+
+        let result = SetDefaultHandler::new(&mut store)
+            .with_profile("work")
+            .run(ProviderCli::AccuWeather);
+
+        assert!(result.is_err());
+        assert!(!store.defaults.contains_key("work"));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::i18n::Locale;
+use anyhow::{Context, Result, anyhow};
+use std::error::Error;
+use std::fmt::Write as _;
+use std::io::Write;
+use tracing::debug;
+use wezzapp_core::apis::ProviderClientFactory;
+use wezzapp_core::credentials::ServiceStore;
+use wezzapp_core::error::WeatherError;
+use wezzapp_core::weather_service::{ProviderStatus, WeatherService};
+
+/// `status` command handler.
+///
+/// Table goes to `writer`, so tests can inject a buffer.
+pub struct StatusHandler<S, F, W>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+    W: Write,
+{
+    service: WeatherService<S, F>,
+    writer: W,
+}
+
+impl<S, F, W> StatusHandler<S, F, W>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+    W: Write,
+{
+    pub fn with_writer(service: WeatherService<S, F>, writer: W) -> Self {
+        Self { service, writer }
+    }
+
+    /// Check every provider and write a table, failing if any of them isn't OK.
+    pub fn run(&mut self) -> Result<()> {
+        let statuses = self.service.status()?;
+        debug!("Provider statuses: {statuses:?}");
+
+        write!(self.writer, "{}", render_status(&statuses)).context("failed to write status")?;
+
+        let failed = statuses.iter().filter(|status| !status.is_ok()).count();
+        if failed > 0 {
+            return Err(anyhow!("{failed} of {} providers failed", statuses.len()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Per-provider table, followed by the reason of every failed check.
+fn render_status(statuses: &[ProviderStatus]) -> String {
+    let mut table = format!(
+        "{:<12} {:<12} {:<10} {:<9} STATUS\n",
+        "PROVIDER", "CREDENTIALS", "REACHABLE", "VERIFIED"
+    );
+    let mut errors = String::new();
+
+    for status in statuses {
+        let name = status.provider.as_str();
+        let verified = match &status.verified {
+            Some(verified) => yes_no(verified),
+            None => "-",
+        };
+        let _ = writeln!(
+            table,
+            "{name:<12} {:<12} {:<10} {verified:<9} {}",
+            if status.has_credentials { "yes" } else { "no" },
+            yes_no(&status.reachable),
+            if status.is_ok() { "OK" } else { "FAIL" },
+        );
+
+        for result in [Some(&status.reachable), status.verified.as_ref()] {
+            if let Some(Err(err)) = result {
+                let _ = write!(errors, "{name}: {}", error_chain(err));
+                match Locale::En.weather_error_hint(err) {
+                    Some(hint) => {
+                        let _ = writeln!(errors, ". {hint}");
+                    }
+                    None => errors.push('\n'),
+                }
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        table.push('\n');
+        table.push_str(&errors);
+    }
+
+    table
+}
+
+fn yes_no(result: &Result<(), WeatherError>) -> &'static str {
+    if result.is_ok() { "yes" } else { "no" }
+}
+
+/// Error with all its sources, e.g. "HTTP request failed: connection refused".
+fn error_chain(err: &WeatherError) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        let _ = write!(message, ": {err}");
+        source = err.source();
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wezzapp_core::provider::Provider;
+
+    #[test]
+    fn renders_table_with_failure_reasons() {
+        let statuses = [
+            ProviderStatus {
+                provider: Provider::WeatherApi,
+                has_credentials: true,
+                reachable: Ok(()),
+                verified: Some(Ok(())),
+            },
+            ProviderStatus {
+                provider: Provider::AccuWeather,
+                has_credentials: true,
+                reachable: Ok(()),
+                verified: Some(Err(WeatherError::InvalidApiKey(Provider::AccuWeather))),
+            },
+            ProviderStatus {
+                provider: Provider::TomorrowIo,
+                has_credentials: false,
+                reachable: Ok(()),
+                verified: None,
+            },
+        ];
+
+        assert_eq!(
+            render_status(&statuses),
+            "PROVIDER     CREDENTIALS  REACHABLE  VERIFIED  STATUS\n\
+             weatherapi   yes          yes        yes       OK\n\
+             accuweather  yes          yes        no        FAIL\n\
+             tomorrowio   no           yes        -         OK\n\
+             \n\
+             accuweather: API key of `AccuWeather` was rejected. \
+             Re-run `wezzapp configure accuweather` with a valid key.\n"
+        );
+    }
+
+    #[test]
+    fn all_ok_renders_no_failure_reasons() {
+        let statuses = [ProviderStatus {
+            provider: Provider::WeatherApi,
+            has_credentials: false,
+            reachable: Ok(()),
+            verified: None,
+        }];
+
+        assert_eq!(
+            render_status(&statuses),
+            "PROVIDER     CREDENTIALS  REACHABLE  VERIFIED  STATUS\n\
+             weatherapi   no           yes        -         OK\n"
+        );
+    }
+}
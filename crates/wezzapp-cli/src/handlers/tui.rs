@@ -0,0 +1,492 @@
+use crate::i18n::Locale;
+use crate::render::{Format, RenderOptions, TemperatureUnit, render};
+use crate::stderr;
+use anyhow::{Context, Result};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{
+    self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode,
+    enable_raw_mode,
+};
+use crossterm::{execute, queue};
+use std::io::{self, Write};
+use tracing::{debug, trace};
+use wezzapp_core::apis::{ProviderClientFactory, WeatherReport};
+use wezzapp_core::credentials::ServiceStore;
+use wezzapp_core::provider::Provider;
+use wezzapp_core::weather_service::WeatherService;
+
+/// Days fetched per location, today included; every provider's free plan covers them.
+const FORECAST_DAYS: u32 = 5;
+
+/// Key bindings shown above the input line.
+const HELP: &str = "Enter: fetch  ↑/↓: provider  ←/→: day  Esc: quit";
+
+/// Key presses the TUI reacts to, independent of the terminal backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Char(char),
+    Backspace,
+    Enter,
+    PreviousProvider,
+    NextProvider,
+    PreviousDay,
+    NextDay,
+    Quit,
+}
+
+impl Input {
+    /// Map a terminal key press, `None` for keys without a binding.
+    fn from_key(key: KeyEvent) -> Option<Self> {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Self::Quit),
+            KeyCode::Char(c) => Some(Self::Char(c)),
+            KeyCode::Backspace => Some(Self::Backspace),
+            KeyCode::Enter => Some(Self::Enter),
+            KeyCode::Up => Some(Self::PreviousProvider),
+            KeyCode::Down => Some(Self::NextProvider),
+            KeyCode::Left => Some(Self::PreviousDay),
+            KeyCode::Right => Some(Self::NextDay),
+            KeyCode::Esc => Some(Self::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Forecast to fetch, requested by pressing Enter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchRequest {
+    pub address: String,
+    /// `None` if no provider is configured, leaving the error to `WeatherService`.
+    pub provider: Option<Provider>,
+}
+
+/// What the event loop should do after `TuiState::update`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Effect {
+    Redraw,
+    Fetch(FetchRequest),
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Idle,
+    Loading,
+    Failed(String),
+}
+
+/// Everything shown on screen, changed only by `update` and `finish_fetch`.
+#[derive(Debug)]
+pub struct TuiState {
+    input: String,
+    providers: Vec<Provider>,
+    provider: usize,
+    reports: Vec<WeatherReport>,
+    day: usize,
+    status: Status,
+}
+
+impl TuiState {
+    /// Start with an empty address and the first of `providers` selected.
+    pub fn new(providers: Vec<Provider>) -> Self {
+        Self {
+            input: String::new(),
+            providers,
+            provider: 0,
+            reports: Vec::new(),
+            day: 0,
+            status: Status::Idle,
+        }
+    }
+
+    pub fn update(&mut self, input: Input) -> Effect {
+        match input {
+            Input::Char(c) => self.input.push(c),
+            Input::Backspace => {
+                self.input.pop();
+            }
+            Input::Enter => {
+                let address = self.input.trim();
+                if address.is_empty() || self.status == Status::Loading {
+                    return Effect::Redraw;
+                }
+                self.status = Status::Loading;
+                return Effect::Fetch(FetchRequest {
+                    address: address.to_string(),
+                    provider: self.providers.get(self.provider).copied(),
+                });
+            }
+            Input::PreviousProvider if !self.providers.is_empty() => {
+                self.provider = (self.provider + self.providers.len() - 1) % self.providers.len();
+            }
+            Input::NextProvider if !self.providers.is_empty() => {
+                self.provider = (self.provider + 1) % self.providers.len();
+            }
+            Input::PreviousProvider | Input::NextProvider => {}
+            Input::PreviousDay => self.day = self.day.saturating_sub(1),
+            Input::NextDay => {
+                if self.day + 1 < self.reports.len() {
+                    self.day += 1;
+                }
+            }
+            Input::Quit => return Effect::Quit,
+        }
+
+        Effect::Redraw
+    }
+
+    /// Show fetched reports from the first day, or why they couldn't be fetched.
+    pub fn finish_fetch(&mut self, result: Result<Vec<WeatherReport>>) {
+        self.day = 0;
+        match result {
+            Ok(reports) => {
+                self.reports = reports;
+                self.status = Status::Idle;
+            }
+            Err(err) => {
+                self.reports.clear();
+                self.status = Status::Failed(format!("{err:#}"));
+            }
+        }
+    }
+
+    /// Screen lines, cut to `width` characters.
+    pub fn view(
+        &self,
+        width: usize,
+        precision: usize,
+        units: TemperatureUnit,
+        locale: Locale,
+    ) -> Vec<String> {
+        let provider = match self.providers.get(self.provider) {
+            Some(provider) => format!(
+                "{} ({}/{})",
+                provider.as_str(),
+                self.provider + 1,
+                self.providers.len()
+            ),
+            None => locale.tr("none configured").to_string(),
+        };
+        let mut lines = vec![
+            locale.tr(HELP).to_string(),
+            String::new(),
+            format!("{}: {}", locale.tr("Location"), self.input),
+            format!("{}: {provider}", locale.tr("Provider")),
+            String::new(),
+        ];
+
+        match (&self.status, self.reports.get(self.day)) {
+            (Status::Loading, _) => lines.push(locale.tr("Loading…").to_string()),
+            (Status::Failed(err), _) => lines.push(format!("{}: {err}", locale.tr("Error"))),
+            (Status::Idle, Some(report)) => {
+                lines.push(format!(
+                    "{} {}/{}",
+                    locale.tr("Day"),
+                    self.day + 1,
+                    self.reports.len()
+                ));
+                let options = RenderOptions {
+                    precision,
+                    units,
+                    locale,
+                    ..RenderOptions::default()
+                };
+                let text = render(report, Format::Text, &options);
+                lines.extend(text.lines().map(str::to_string));
+            }
+            (Status::Idle, None) => {
+                lines.push(locale.tr("Type a location and press Enter.").to_string());
+            }
+        }
+
+        lines
+            .into_iter()
+            .map(|line| line.chars().take(width).collect())
+            .collect()
+    }
+}
+
+/// `tui` command handler.
+///
+/// Drives `TuiState` with terminal key presses and fetches forecasts with `WeatherService`.
+pub struct TuiHandler<S, F>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+{
+    service: WeatherService<S, F>,
+    precision: usize,
+    units: TemperatureUnit,
+    locale: Locale,
+}
+
+impl<S, F> TuiHandler<S, F>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+{
+    pub fn new(service: WeatherService<S, F>) -> Self {
+        Self {
+            service,
+            precision: 0,
+            units: TemperatureUnit::default(),
+            locale: Locale::default(),
+        }
+    }
+
+    /// Round temperatures to `precision` decimals.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Show temperatures in `units`, converting from °C.
+    pub fn with_units(mut self, units: TemperatureUnit) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Show report labels in `locale`.
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Run until Esc or Ctrl-C, restoring the terminal on the way out.
+    pub fn run(&mut self) -> Result<()> {
+        let providers = self.service.configured_providers()?;
+        debug!("Configured providers: {providers:?}");
+        let mut state = TuiState::new(providers);
+
+        let _terminal = TerminalGuard::enter()?;
+        let mut stdout = io::stdout();
+        loop {
+            self.draw(&mut stdout, &state)?;
+
+            // Resizes and unbound keys just redraw at the new size.
+            let input = match event::read().context("failed to read terminal event")? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => Input::from_key(key),
+                _ => None,
+            };
+            let Some(input) = input else {
+                continue;
+            };
+
+            match state.update(input) {
+                Effect::Redraw => {}
+                Effect::Fetch(request) => {
+                    self.draw(&mut stdout, &state)?;
+                    let result = self.fetch(request);
+                    state.finish_fetch(result);
+                }
+                Effect::Quit => return Ok(()),
+            }
+        }
+    }
+
+    /// Fetch `FORECAST_DAYS` days starting today.
+    fn fetch(&mut self, request: FetchRequest) -> Result<Vec<WeatherReport>> {
+        trace!("Fetching {request:?}");
+
+        Ok(self.service.get_weather_range_days_ahead(
+            request.address,
+            0..=FORECAST_DAYS - 1,
+            request.provider,
+        )?)
+    }
+
+    fn draw(&self, stdout: &mut impl Write, state: &TuiState) -> Result<()> {
+        let (width, height) = terminal::size().context("failed to get terminal size")?;
+
+        queue!(stdout, Clear(ClearType::All))?;
+        let lines = state.view(width.into(), self.precision, self.units, self.locale);
+        for (row, line) in (0..height).zip(lines) {
+            queue!(stdout, MoveTo(0, row), Print(line))?;
+        }
+        stdout.flush().context("failed to draw")
+    }
+}
+
+/// Raw mode on the alternate screen while alive, so the shell is left as it was.
+///
+/// Logs and warnings are held back meanwhile, they'd be drawn over the screen.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self> {
+        stderr::hold();
+        enable_raw_mode().context("failed to enable raw terminal mode")?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)
+            .context("failed to switch to alternate screen")?;
+
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+        stderr::release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use wezzapp_core::testing;
+
+    fn report(date: &str) -> WeatherReport {
+        WeatherReport {
+            date: date.into(),
+            ..testing::report()
+        }
+    }
+
+    fn type_text(state: &mut TuiState, text: &str) {
+        for c in text.chars() {
+            state.update(Input::Char(c));
+        }
+    }
+
+    #[test]
+    fn enter_requests_fetch_of_typed_address_with_selected_provider() {
+        let mut state = TuiState::new(vec![Provider::WeatherApi, Provider::AccuWeather]);
+        type_text(&mut state, " Kyivv");
+        state.update(Input::Backspace);
+        state.update(Input::NextProvider);
+
+        assert_eq!(
+            state.update(Input::Enter),
+            Effect::Fetch(FetchRequest {
+                address: "Kyiv".into(),
+                provider: Some(Provider::AccuWeather),
+            })
+        );
+        assert_eq!(state.status, Status::Loading);
+    }
+
+    #[test]
+    fn enter_without_address_or_while_loading_does_not_fetch() {
+        let mut state = TuiState::new(vec![Provider::WeatherApi]);
+        type_text(&mut state, "  ");
+        assert_eq!(state.update(Input::Enter), Effect::Redraw);
+
+        type_text(&mut state, "Kyiv");
+        assert!(matches!(state.update(Input::Enter), Effect::Fetch(_)));
+        assert_eq!(state.update(Input::Enter), Effect::Redraw);
+    }
+
+    #[test]
+    fn provider_selection_wraps_around() {
+        let mut state = TuiState::new(vec![Provider::WeatherApi, Provider::AccuWeather]);
+
+        state.update(Input::PreviousProvider);
+        assert_eq!(state.provider, 1);
+        state.update(Input::NextProvider);
+        assert_eq!(state.provider, 0);
+    }
+
+    #[test]
+    fn no_configured_provider_fetches_with_default() {
+        let mut state = TuiState::new(Vec::new());
+        state.update(Input::NextProvider);
+        type_text(&mut state, "Kyiv");
+
+        assert_eq!(
+            state.update(Input::Enter),
+            Effect::Fetch(FetchRequest {
+                address: "Kyiv".into(),
+                provider: None,
+            })
+        );
+    }
+
+    #[test]
+    fn arrows_browse_fetched_days_within_bounds() {
+        let mut state = TuiState::new(vec![Provider::WeatherApi]);
+        state.finish_fetch(Ok(vec![report("2024-11-29"), report("2024-11-30")]));
+
+        state.update(Input::PreviousDay);
+        assert_eq!(state.day, 0);
+        state.update(Input::NextDay);
+        state.update(Input::NextDay);
+        assert_eq!(state.day, 1);
+    }
+
+    #[test]
+    fn escape_and_ctrl_c_quit() {
+        let mut state = TuiState::new(Vec::new());
+
+        assert_eq!(state.update(Input::Quit), Effect::Quit);
+        assert_eq!(
+            Input::from_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Input::Quit)
+        );
+        assert_eq!(
+            Input::from_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)),
+            Some(Input::Quit)
+        );
+    }
+
+    #[test]
+    fn view_shows_loading_error_and_selected_day() {
+        let mut state = TuiState::new(vec![Provider::WeatherApi]);
+        type_text(&mut state, "Kyiv");
+        state.update(Input::Enter);
+        assert!(
+            state
+                .view(80, 0, TemperatureUnit::Celsius, Locale::En)
+                .contains(&"Loading…".to_string())
+        );
+
+        state.finish_fetch(Err(anyhow!("address not found")));
+        assert!(
+            state
+                .view(80, 0, TemperatureUnit::Celsius, Locale::En)
+                .contains(&"Error: address not found".to_string())
+        );
+
+        state.finish_fetch(Ok(vec![report("2024-11-29"), report("2024-11-30")]));
+        state.update(Input::NextDay);
+        let view = state.view(80, 0, TemperatureUnit::Celsius, Locale::En);
+        assert_eq!(view[3], "Provider: weatherapi (1/1)");
+        assert!(view.contains(&"Day 2/2".to_string()), "{view:?}");
+        assert!(view.contains(&"Date: 2024-11-30".to_string()), "{view:?}");
+    }
+
+    #[test]
+    fn view_is_localized() {
+        let mut state = TuiState::new(Vec::new());
+        assert_eq!(
+            state.view(80, 0, TemperatureUnit::Celsius, Locale::Uk),
+            vec![
+                "Enter: прогноз  ↑/↓: провайдер  ←/→: день  Esc: вихід",
+                "",
+                "Місце: ",
+                "Провайдер: не налаштовано",
+                "",
+                "Введіть місце і натисніть Enter.",
+            ]
+        );
+
+        state.finish_fetch(Ok(vec![report("2024-11-29")]));
+        let view = state.view(80, 0, TemperatureUnit::Celsius, Locale::Uk);
+        assert!(view.contains(&"День 1/1".to_string()), "{view:?}");
+    }
+
+    #[test]
+    fn view_cuts_lines_to_width() {
+        let state = TuiState::new(Vec::new());
+
+        assert!(
+            state
+                .view(10, 0, TemperatureUnit::Celsius, Locale::En)
+                .iter()
+                .all(|line| line.chars().count() <= 10)
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use std::error::Error;
+use std::fmt::Write as _;
+use wezzapp_core::error::WeatherError;
+
+/// Language of CLI labels and messages.
+///
+/// Provider condition text is localized by providers themselves, see `--lang`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Locale {
+    #[default]
+    En,
+    Uk,
+}
+
+/// Ukrainian translations, keyed by the English text like gettext's msgid.
+const UK: &[(&str, &str)] = &[
+    ("Error", "Помилка"),
+    ("Caused by", "Причина"),
+    ("Configuration cancelled.", "Налаштування скасовано."),
+    ("Cancelled.", "Скасовано."),
+    (
+        "Run `wezzapp configure weatherapi` to get started.",
+        "Виконайте `wezzapp configure weatherapi`, щоб почати.",
+    ),
+    (
+        "Re-run `wezzapp configure {provider}` with a valid key.",
+        "Повторно виконайте `wezzapp configure {provider}` з дійсним ключем.",
+    ),
+    (
+        "Run without `--offline` to fetch it.",
+        "Запустіть без `--offline`, щоб отримати його.",
+    ),
+    (
+        "Try a closer date or a shorter `--from`/`--to` range.",
+        "Спробуйте ближчу дату або коротший проміжок `--from`/`--to`.",
+    ),
+    ("Location", "Місце"),
+    ("Date", "Дата"),
+    ("Observed", "Спостереження"),
+    ("Provider", "Провайдер"),
+    ("Conditions", "Умови"),
+    ("Temperature", "Температура"),
+    ("max", "макс"),
+    ("min", "мін"),
+    ("avg", "сер"),
+    ("Feels like", "Відчувається як"),
+    ("UV index", "УФ-індекс"),
+    ("Wind", "Вітер"),
+    ("Humidity", "Вологість"),
+    ("Sunrise", "Схід сонця"),
+    ("Sunset", "Захід сонця"),
+    ("AQI", "Індекс якості повітря"),
+    ("No active alerts.", "Активних попереджень немає."),
+    (
+        "Enter: fetch  ↑/↓: provider  ←/→: day  Esc: quit",
+        "Enter: прогноз  ↑/↓: провайдер  ←/→: день  Esc: вихід",
+    ),
+    ("none configured", "не налаштовано"),
+    ("Loading…", "Завантаження…"),
+    ("Day", "День"),
+    (
+        "Type a location and press Enter.",
+        "Введіть місце і натисніть Enter.",
+    ),
+];
+
+impl Locale {
+    /// Locale of a language code like "uk", or of a `LANG` value like "uk_UA.UTF-8".
+    ///
+    /// English for unsupported languages.
+    pub fn from_code(code: &str) -> Self {
+        let language = code.split(['_', '-', '.']).next().unwrap_or_default();
+
+        match language.to_lowercase().as_str() {
+            "uk" => Self::Uk,
+            _ => Self::En,
+        }
+    }
+
+    /// Locale of `lang`, e.g. `--lang`, falling back to the `LANG` env var.
+    pub fn resolve(lang: Option<&str>) -> Self {
+        match lang {
+            Some(lang) => Self::from_code(lang),
+            None => std::env::var("LANG")
+                .map(|lang| Self::from_code(&lang))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Translation of English `text`, or `text` itself if there's none.
+    pub fn tr(self, text: &str) -> &str {
+        let table = match self {
+            Self::En => return text,
+            Self::Uk => UK,
+        };
+
+        table
+            .iter()
+            .find(|(english, _)| *english == text)
+            .map_or(text, |(_, translated)| translated)
+    }
+
+    /// `err` with its causes, like anyhow's `{:?}` without the backtrace, with provider errors
+    /// translated, followed by what to do about it when that's obvious.
+    pub fn error_message(self, err: &anyhow::Error) -> String {
+        let mut message = self.causes_message(err);
+        if let Some(hint) = self.hint(err) {
+            let _ = write!(message, "\n\n{hint}");
+        }
+
+        message
+    }
+
+    /// Next step for the user after `err`, e.g. configuring a provider when there's none.
+    fn hint(self, err: &anyhow::Error) -> Option<String> {
+        err.chain()
+            .filter_map(|cause| cause.downcast_ref::<WeatherError>())
+            .find_map(|err| self.weather_error_hint(err))
+    }
+
+    /// Next step for the user after a provider error, worded for the CLI as core error
+    /// messages don't know about its commands and flags.
+    pub fn weather_error_hint(self, err: &WeatherError) -> Option<String> {
+        let hint = match err {
+            WeatherError::NoProvidersConfigured => {
+                self.tr("Run `wezzapp configure weatherapi` to get started.")
+            }
+            WeatherError::InvalidApiKey(provider) => {
+                return Some(
+                    self.tr("Re-run `wezzapp configure {provider}` with a valid key.")
+                        .replace("{provider}", provider.as_str()),
+                );
+            }
+            WeatherError::Offline => self.tr("Run without `--offline` to fetch it."),
+            WeatherError::ForecastTooShort { .. } => {
+                self.tr("Try a closer date or a shorter `--from`/`--to` range.")
+            }
+            _ => return None,
+        };
+
+        Some(hint.to_string())
+    }
+
+    fn causes_message(self, err: &anyhow::Error) -> String {
+        let mut causes = err.chain().map(|cause| self.cause_message(cause));
+        let mut message = causes.next().unwrap_or_default();
+
+        let causes: Vec<_> = causes.collect();
+        match causes.as_slice() {
+            [] => {}
+            [cause] => {
+                let _ = write!(message, "\n\n{}:\n    {cause}", self.tr("Caused by"));
+            }
+            causes => {
+                let _ = write!(message, "\n\n{}:", self.tr("Caused by"));
+                for (i, cause) in causes.iter().enumerate() {
+                    let _ = write!(message, "\n    {i}: {cause}");
+                }
+            }
+        }
+
+        message
+    }
+
+    fn cause_message(self, cause: &(dyn Error + 'static)) -> String {
+        match cause.downcast_ref::<WeatherError>() {
+            Some(err) => self.weather_error_message(err),
+            None => cause.to_string(),
+        }
+    }
+
+    /// `err` in this locale, falling back to its English message for untranslated variants.
+    fn weather_error_message(self, err: &WeatherError) -> String {
+        if self == Self::En {
+            return err.to_string();
+        }
+
+        match err {
+            WeatherError::AddressNotFound => {
+                "адресу не знайдено, будь ласка, вкажіть точнішу адресу, напр.: Київ, Україна"
+                    .into()
+            }
+            WeatherError::EmptyAddress => {
+                "адреса порожня, будь ласка, вкажіть місце, напр.: Київ, Україна".into()
+            }
+            WeatherError::AddressTooLong { len, max } => {
+                format!("адреса задовга ({len} символів), дозволено щонайбільше {max}")
+            }
+            WeatherError::RateLimited => {
+                "перевищено ліміт запитів до провайдера, будь ласка, спробуйте пізніше".into()
+            }
+            WeatherError::InvalidApiKey(provider) => {
+                format!("API-ключ `{provider:?}` відхилено")
+            }
+            WeatherError::InvalidDate(_) => {
+                "неправильний формат дати (очікується РРРР-ММ-ДД)".into()
+            }
+            WeatherError::DateInPast => "дата в минулому".into(),
+            WeatherError::DateTooFarAhead { max } => format!(
+                "дата більш ніж на {max} днів уперед, провайдери прогнозують лише на кілька днів"
+            ),
+            WeatherError::InvalidDateRange { from, to } => {
+                format!("кінцева дата `{to}` раніше за початкову `{from}`")
+            }
+            WeatherError::MissingCredentials(provider) => format!(
+                "не знайдено облікових даних провайдера `{provider:?}`, \
+                 будь ласка, спершу налаштуйте його"
+            ),
+            WeatherError::NoProvidersConfigured => "провайдерів не налаштовано".into(),
+            WeatherError::NoDefaultProvider => "провайдера не вказано і типового не встановлено, \
+                 будь ласка, налаштуйте провайдера та/або встановіть типового"
+                .into(),
+            WeatherError::ProviderUnavailable(provider) => {
+                format!("`{provider:?}` зараз недоступний, будь ласка, спробуйте пізніше")
+            }
+            WeatherError::Offline => "немає збереженого звіту для роботи офлайн".into(),
+            _ => err.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Context, anyhow};
+    use rstest::rstest;
+    use wezzapp_core::provider::Provider;
+
+    #[rstest]
+    #[case("uk", Locale::Uk)]
+    #[case("uk_UA.UTF-8", Locale::Uk)]
+    #[case("UK", Locale::Uk)]
+    #[case("en_US.UTF-8", Locale::En)]
+    #[case("de", Locale::En)]
+    #[case("", Locale::En)]
+    fn parses_language_codes(#[case] code: &str, #[case] expected: Locale) {
+        assert_eq!(Locale::from_code(code), expected);
+    }
+
+    #[test]
+    fn translates_known_text_and_keeps_unknown() {
+        assert_eq!(Locale::Uk.tr("Humidity"), "Вологість");
+        assert_eq!(Locale::Uk.tr("Something new"), "Something new");
+        assert_eq!(Locale::En.tr("Humidity"), "Humidity");
+    }
+
+    #[test]
+    fn translates_provider_errors_keeping_context() {
+        let err = Err::<(), _>(WeatherError::DateInPast)
+            .context("failed to get weather for `Kyiv`")
+            .unwrap_err();
+
+        assert_eq!(
+            Locale::Uk.error_message(&err),
+            "failed to get weather for `Kyiv`\n\nПричина:\n    дата в минулому"
+        );
+    }
+
+    #[test]
+    fn translates_error_fields() {
+        let err = anyhow::Error::from(WeatherError::DateTooFarAhead { max: 30 });
+
+        assert_eq!(
+            Locale::Uk.error_message(&err),
+            "дата більш ніж на 30 днів уперед, провайдери прогнозують лише на кілька днів"
+        );
+    }
+
+    #[rstest]
+    #[case(
+        Locale::En,
+        "no providers configured\n\nRun `wezzapp configure weatherapi` to get started."
+    )]
+    #[case(
+        Locale::Uk,
+        "провайдерів не налаштовано\n\nВиконайте `wezzapp configure weatherapi`, щоб почати."
+    )]
+    fn suggests_configuring_when_no_providers_are(#[case] locale: Locale, #[case] expected: &str) {
+        let err = anyhow::Error::from(WeatherError::NoProvidersConfigured);
+
+        assert_eq!(locale.error_message(&err), expected);
+    }
+
+    #[rstest]
+    #[case(
+        Locale::En,
+        "no cached report available offline\n\nRun without `--offline` to fetch it."
+    )]
+    #[case(
+        Locale::Uk,
+        "немає збереженого звіту для роботи офлайн\n\nЗапустіть без `--offline`, щоб отримати його."
+    )]
+    fn suggests_going_online_when_offline_report_is_missing(
+        #[case] locale: Locale,
+        #[case] expected: &str,
+    ) {
+        let err = anyhow::Error::from(WeatherError::Offline);
+
+        assert_eq!(locale.error_message(&err), expected);
+    }
+
+    #[rstest]
+    #[case(
+        Locale::En,
+        "API key of `AccuWeather` was rejected\n\n\
+         Re-run `wezzapp configure accuweather` with a valid key."
+    )]
+    #[case(
+        Locale::Uk,
+        "API-ключ `AccuWeather` відхилено\n\n\
+         Повторно виконайте `wezzapp configure accuweather` з дійсним ключем."
+    )]
+    fn suggests_reconfiguring_rejected_api_key(#[case] locale: Locale, #[case] expected: &str) {
+        let err = anyhow::Error::from(WeatherError::InvalidApiKey(Provider::AccuWeather));
+
+        assert_eq!(locale.error_message(&err), expected);
+    }
+
+    #[test]
+    fn suggests_shorter_range_when_forecast_is_too_short() {
+        let err = anyhow::Error::from(WeatherError::ForecastTooShort {
+            returned: 3,
+            requested: 5,
+            provider: Provider::AccuWeather,
+        });
+
+        assert_eq!(
+            Locale::En.error_message(&err),
+            "`AccuWeather` returned 3 days forecast (including today), requested 5\n\n\
+             Try a closer date or a shorter `--from`/`--to` range."
+        );
+    }
+
+    #[test]
+    fn other_errors_stay_untranslated() {
+        let err = anyhow!("date is in the past");
+
+        assert_eq!(Locale::Uk.error_message(&err), "date is in the past");
+    }
+}
@@ -1,63 +1,487 @@
-use crate::cli::Command;
-use crate::handlers::configure::ConfigureHandler;
-use crate::handlers::get::GetHandler;
-use crate::prompter::InquirePrompter;
-use crate::store::TomlFileCredentialsStore;
+use crate::cli::{Command, ConfigCommand, LogFormat, StoreBackend};
+use crate::handlers::compare::{CompareHandler, Side};
+use crate::handlers::config::ConfigDumpHandler;
+use crate::handlers::configure::{ConfigureHandler, read_api_key};
+use crate::handlers::get::{GetHandler, ThresholdCrossed};
+use crate::handlers::migrate_store::MigrateStoreHandler;
+use crate::handlers::providers::ProvidersHandler;
+use crate::handlers::set_default::SetDefaultHandler;
+use crate::handlers::status::StatusHandler;
+use crate::handlers::tui::TuiHandler;
+use crate::i18n::Locale;
+use crate::output::OutputFile;
+use crate::paths::Paths;
+use crate::prompter::{Cancelled, InquirePrompter, prompt_passphrase};
+use crate::quota::QuotaWarning;
+use crate::render::Icons;
+use crate::store::{ConfigStore, EncryptedFileCredentialsStore, TomlFileCredentialsStore};
+use crate::watch::InterruptibleClock;
+use anyhow::Context;
 use clap::Parser;
-use tracing::debug;
+use std::fs::File;
+use std::io::{self, BufReader, IsTerminal, Write};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, trace};
 use tracing_subscriber::{EnvFilter, fmt};
-use wezzapp_core::apis::HttpProviderClientFactory;
-use wezzapp_core::weather_service::WeatherService;
+use wezzapp_core::apis::debug_log::DebugLog;
+use wezzapp_core::apis::location_cache::FileLocationCache;
+use wezzapp_core::apis::report_cache::FileReportCache;
+use wezzapp_core::apis::{
+    DEFAULT_LANGUAGE, HttpProviderClientFactory, HttpProviderClientFactoryBuilder,
+};
+use wezzapp_core::weather_service::{WeatherService, WeatherServiceBuilder};
 
 mod cli;
+mod encryption;
+mod exit_code;
 mod handlers;
+mod i18n;
+mod output;
+mod paths;
 mod prompter;
+mod quota;
+mod render;
+mod stderr;
 mod store;
+mod template;
+mod watch;
 
-fn main() -> anyhow::Result<()> {
-    init_tracing();
+/// How long `get --watch` redraws a cached report before fetching a new one.
+const WATCH_FRESH_FOR: Duration = Duration::from_secs(5 * 60);
 
+fn main() -> ExitCode {
+    // Exits with `exit_code::USAGE` on invalid arguments.
     let args = cli::Cli::parse();
-    debug!("Parsed CLI args: {:?}", args);
+    if let Err(err) = args.validate() {
+        err.exit();
+    }
+    init_tracing(args.log_format);
+    trace!("Parsed CLI args: {:?}", args);
+    let mut locale = Locale::resolve(args.command.lang());
+    let configuring = matches!(args.command, Command::Configure { .. });
+
+    match run(args, &mut locale) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.chain().any(|cause| cause.is::<Cancelled>()) => {
+            if configuring {
+                eprintln!("{}", locale.tr("Configuration cancelled."));
+                return ExitCode::SUCCESS;
+            }
+            eprintln!("{}", locale.tr("Cancelled."));
+            ExitCode::from(exit_code::CANCELLED)
+        }
+        // Warnings were printed already, the exit code is all that's left to report.
+        Err(err) if err.is::<ThresholdCrossed>() => ExitCode::from(exit_code::THRESHOLD),
+        Err(err) => {
+            eprintln!("{}: {}", locale.tr("Error"), locale.error_message(&err));
+            ExitCode::from(exit_code::for_error(&err))
+        }
+    }
+}
+
+/// Run the command, switching `locale` of error messages to the stored language once the
+/// config is read, unless `--lang` is given.
+fn run(args: cli::Cli, locale: &mut Locale) -> anyhow::Result<()> {
+    let paths = Paths::new()?;
+    paths.migrate_legacy()?;
+
+    let lang_given = args.command.lang().is_some();
+    let mut open_config = |encrypted: bool| -> anyhow::Result<Box<dyn ConfigStore>> {
+        let store = open_store(&paths, encrypted, args.reset_config)?;
+        if !lang_given && let Some(language) = store.get_language()? {
+            *locale = Locale::from_code(&language);
+        }
+        Ok(store)
+    };
 
     match args.command {
-        Command::Configure { provider } => {
-            ConfigureHandler::new(TomlFileCredentialsStore::new()?, InquirePrompter::new())
+        Command::Configure {
+            provider,
+            lang,
+            profile,
+            no_default_write,
+            api_key,
+            api_key_file,
+            api_key_stdin,
+            base_url,
+        } => {
+            let api_key = match (api_key, api_key_file) {
+                (Some(api_key), _) => Some(api_key),
+                (None, Some(path)) => {
+                    Some(read_api_key(File::open(&path).with_context(|| {
+                        format!("failed to open {}", path.display())
+                    })?)?)
+                }
+                (None, None) if api_key_stdin => Some(read_api_key(io::stdin().lock())?),
+                (None, None) => None,
+            };
+
+            ConfigureHandler::new(open_config(args.encrypted)?, InquirePrompter::new())
+                .with_profile(profile)
+                .with_no_default_write(no_default_write)
+                .with_api_key(api_key)
+                .with_base_url(base_url)
+                .run(provider, lang)
+        }
+        Command::SetDefault { provider, profile } => {
+            SetDefaultHandler::new(open_config(args.encrypted)?)
+                .with_profile(profile)
                 .run(provider)
         }
         Command::Get {
             address,
             date,
+            from,
+            to,
+            file,
+            stdin,
             provider,
+            auto_provider,
+            tz_aware,
+            format,
+            lang,
+            profile,
+            color,
+            no_color,
+            aqi,
+            alerts,
+            precision,
+            display_units,
+            both_units,
+            alert_above,
+            alert_below,
+            save,
+            allow_stale,
+            refresh_location,
+            raw,
+            preflight,
+            explain,
+            field,
+            template,
+            timeout,
+            connect_timeout,
+            proxy,
+            debug_log,
+            watch,
+            now,
+            params,
         } => {
-            let store = TomlFileCredentialsStore::new()?;
+            let store = open_config(args.encrypted)?;
             debug!("Loaded credentials from store");
 
-            let factory = HttpProviderClientFactory::new();
+            let language = language(&*store, lang)?;
+            let locale = Locale::resolve(language.as_deref());
+            let icons = Icons::new(store.get_icons()?);
+
+            let quota_warning = Arc::new(QuotaWarning::default());
+            let mut factory =
+                factory_builder(&paths, language, refresh_location, quota_warning.clone())
+                    .with_air_quality(aqi)
+                    .with_alerts(alerts)
+                    .with_extra_params(params);
+            if let Some(secs) = timeout {
+                factory = factory.with_timeout(Duration::from_secs(secs));
+            }
+            if let Some(secs) = connect_timeout {
+                factory = factory.with_connect_timeout(Duration::from_secs(secs));
+            }
+            if let Some(proxy) = proxy {
+                factory = factory.with_proxy(proxy);
+            }
+            if let Some(path) = debug_log {
+                let log = DebugLog::create(&path)
+                    .with_context(|| format!("failed to create {}", path.display()))?;
+                factory = factory.with_debug_log(Arc::new(log));
+            }
+            let factory = factory.build()?;
             debug!("Initialized provider client factory: {:?}", factory);
 
-            let service = WeatherService::new(store, factory);
+            let mut service =
+                service_builder(&paths, store, factory, profile, quota_warning, args.offline)
+                    .with_allow_stale(allow_stale)
+                    .with_auto_provider(auto_provider)
+                    .with_tz_aware(tz_aware);
+            if watch.is_some() {
+                service = service.with_fresh_for(WATCH_FRESH_FOR);
+            }
+            let service = service.build();
             debug!("Initialized weather service");
 
-            let mut handler = GetHandler::new(service);
+            let (output, writer, is_terminal): (_, Box<dyn Write>, _) = match &args.output {
+                Some(path) => {
+                    let (output, file) = OutputFile::create(path)?;
+                    (Some(output), Box::new(file), false)
+                }
+                None => (None, Box::new(io::stdout()), io::stdout().is_terminal()),
+            };
+
+            let mut handler = GetHandler::with_writer(service, writer)
+                .with_format(format)
+                .with_precision(precision.into())
+                .with_units(display_units)
+                .with_both_units(both_units)
+                .with_icons(icons)
+                .with_locale(locale)
+                .with_raw(raw)
+                .with_field(field)
+                .with_template(template)
+                .with_save_as(save)
+                .with_alert_thresholds(alert_above, alert_below)
+                .with_color(!no_color && color.enabled(is_terminal));
             debug!("Initialized weather get handler");
 
-            handler.run(address, date, provider)
+            if preflight {
+                handler.preflight(provider)?;
+            }
+
+            let result = if let Some(path) = file {
+                let file = File::open(&path)
+                    .with_context(|| format!("failed to open {}", path.display()))?;
+                handler.run_batch(BufReader::new(file), provider)
+            } else if stdin {
+                handler.run_batch(io::stdin().lock(), provider)
+            } else if let Some(secs) = watch {
+                let address = address.context("address is required")?;
+                let mut clock = InterruptibleClock::new()?;
+                handler.run_watch(
+                    address,
+                    date,
+                    provider,
+                    Duration::from_secs(secs),
+                    &mut clock,
+                )
+            } else if now {
+                let address = address.context("address is required")?;
+                handler.run_current(address, provider)
+            } else if let (Some(from), Some(to)) = (from, to) {
+                let address = address.context("address is required")?;
+                handler.run_range(address, from, to, provider)
+            } else {
+                let address = address.context("address is required")?;
+                handler.run(address, date, provider)
+            };
+
+            // After fetching, since `--auto-provider` may fall back to another provider.
+            if explain && let Some(explanation) = handler.explain() {
+                eprintln!("{explanation}");
+            }
+
+            // A crossed threshold fails the run only after the report was written in full.
+            drop(handler);
+            let written = match &result {
+                Ok(()) => true,
+                Err(err) => err.is::<ThresholdCrossed>(),
+            };
+            if written && let Some(output) = output {
+                output.persist()?;
+            }
+            result
+        }
+        Command::Status { profile } => {
+            let store = open_config(args.encrypted)?;
+            let service = WeatherService::builder(store, HttpProviderClientFactory::new())
+                .with_profile(profile)
+                .with_offline(args.offline)
+                .build();
+
+            StatusHandler::with_writer(service, io::stdout()).run()
+        }
+        Command::Providers => ProvidersHandler::with_writer(io::stdout()).run(),
+        Command::Compare {
+            address,
+            other_address,
+            date,
+            compare_date,
+            provider,
+            lang,
+            profile,
+            display_units,
+            precision,
+        } => {
+            let store = open_config(args.encrypted)?;
+            let language = language(&*store, lang)?;
+            let quota_warning = Arc::new(QuotaWarning::default());
+            let factory =
+                factory_builder(&paths, language, false, quota_warning.clone()).build()?;
+            let service =
+                service_builder(&paths, store, factory, profile, quota_warning, args.offline)
+                    .build();
+
+            let left = Side::new(address.clone(), date.clone());
+            let right = match other_address {
+                Some(other_address) => Side::new(other_address, date),
+                None => Side::new(address, compare_date),
+            };
+            let (output, writer): (_, Box<dyn Write>) = match &args.output {
+                Some(path) => {
+                    let (output, file) = OutputFile::create(path)?;
+                    (Some(output), Box::new(file))
+                }
+                None => (None, Box::new(io::stdout())),
+            };
+
+            CompareHandler::with_writer(service, writer)
+                .with_precision(precision.into())
+                .with_units(display_units)
+                .run(left, right, provider)?;
+            if let Some(output) = output {
+                output.persist()?;
+            }
+            Ok(())
+        }
+        Command::Tui {
+            lang,
+            profile,
+            display_units,
+            precision,
+        } => {
+            let store = open_config(args.encrypted)?;
+            let language = language(&*store, lang)?;
+            let locale = Locale::resolve(language.as_deref());
+            let quota_warning = Arc::new(QuotaWarning::default());
+            let factory =
+                factory_builder(&paths, language, false, quota_warning.clone()).build()?;
+            let service =
+                service_builder(&paths, store, factory, profile, quota_warning, args.offline)
+                    .build();
+
+            TuiHandler::new(service)
+                .with_precision(precision.into())
+                .with_units(display_units)
+                .with_locale(locale)
+                .run()
+        }
+        Command::MigrateStore { from, to, purge } => {
+            anyhow::ensure!(from != to, "source and destination store are the same");
+
+            let source_file = match from {
+                StoreBackend::Toml => paths.credentials_file(),
+                StoreBackend::Encrypted => paths.encrypted_credentials_file(),
+            };
+            anyhow::ensure!(
+                source_file.exists(),
+                "nothing to migrate, {} doesn't exist",
+                source_file.display()
+            );
+
+            let source = open_config(from == StoreBackend::Encrypted)?;
+            let destination = open_config(to == StoreBackend::Encrypted)?;
+            let summary = MigrateStoreHandler::new(source, destination).run()?;
+            println!(
+                "Migrated {} credentials in {} profiles and {} saved locations.",
+                summary.credentials, summary.profiles, summary.locations
+            );
+
+            if purge {
+                std::fs::remove_file(&source_file)
+                    .with_context(|| format!("failed to delete {}", source_file.display()))?;
+                println!("Deleted {}.", source_file.display());
+            }
+            Ok(())
+        }
+        Command::Config {
+            command: ConfigCommand::Dump { format },
+        } => {
+            let path = if args.encrypted {
+                paths.encrypted_credentials_file()
+            } else {
+                paths.credentials_file()
+            };
+            let store = open_config(args.encrypted)?;
+
+            ConfigDumpHandler::new(store, path, io::stdout())
+                .with_format(format)
+                .run()
         }
     }
 }
 
+/// `--lang`, or else the stored language, `None` if neither is set.
+fn language(store: &dyn ConfigStore, lang: Option<String>) -> anyhow::Result<Option<String>> {
+    match lang {
+        Some(lang) => Ok(Some(lang)),
+        None => store.get_language(),
+    }
+}
+
+/// Provider client factory shared by commands fetching forecasts, in `language` or English,
+/// with the location cache and quota warnings.
+fn factory_builder(
+    paths: &Paths,
+    language: Option<String>,
+    refresh_location: bool,
+    quota_warning: Arc<QuotaWarning>,
+) -> HttpProviderClientFactoryBuilder {
+    let language = language.unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+    debug!("Using language: {language}");
+    let location_cache =
+        FileLocationCache::new(paths.locations_cache()).with_refresh(refresh_location);
+
+    HttpProviderClientFactory::builder()
+        .with_language(language)
+        .with_quota_observer(quota_warning)
+        .with_location_cache(Arc::new(location_cache))
+}
+
+/// Weather service shared by commands fetching forecasts, with the report cache and quota
+/// warnings.
+fn service_builder(
+    paths: &Paths,
+    store: Box<dyn ConfigStore>,
+    factory: HttpProviderClientFactory,
+    profile: String,
+    quota_warning: Arc<QuotaWarning>,
+    offline: bool,
+) -> WeatherServiceBuilder<Box<dyn ConfigStore>, HttpProviderClientFactory> {
+    let report_cache = FileReportCache::new(paths.reports_cache());
+
+    WeatherService::builder(store, factory)
+        .with_profile(profile)
+        .with_quota_observer(quota_warning)
+        .with_report_cache(Arc::new(report_cache))
+        .with_offline(offline)
+}
+
+/// Open the plain or, with `encrypted`, the passphrase-encrypted config store.
+fn open_store(
+    paths: &Paths,
+    encrypted: bool,
+    reset_config: bool,
+) -> anyhow::Result<Box<dyn ConfigStore>> {
+    if !encrypted {
+        return Ok(Box::new(TomlFileCredentialsStore::new(
+            paths,
+            reset_config,
+        )?));
+    }
+
+    // New files get the passphrase typed twice, so a typo doesn't lock the user out.
+    let is_new = !paths.encrypted_credentials_file().exists();
+    let passphrase = prompt_passphrase(is_new)?;
+
+    Ok(Box::new(EncryptedFileCredentialsStore::new(
+        paths,
+        passphrase,
+        reset_config,
+    )?))
+}
+
 /// Initialize global tracing subscriber.
 ///
 /// - Uses `RUST_LOG` if set (e.g. `RUST_LOG=wezzapp_cli=debug,wezzapp_core=trace`)
 /// - Otherwise defaults to `info` for our crates.
-fn init_tracing() {
+fn init_tracing(format: LogFormat) {
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new("wezzapp_cli=info,wezzapp_core=info"));
 
-    let _ = fmt()
+    let subscriber = fmt()
         .with_env_filter(env_filter)
         .with_target(false)
-        .compact()
-        .try_init();
+        .with_writer(|| stderr::Stderr);
+    let _ = match format {
+        LogFormat::Compact => subscriber.compact().try_init(),
+        LogFormat::Json => subscriber.json().try_init(),
+    };
 }
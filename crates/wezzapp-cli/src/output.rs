@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// File given with `--output`, written under a temporary name next to it and renamed over it
+/// only once the command succeeds, so a failed run leaves the previous file as it was.
+#[derive(Debug)]
+pub struct OutputFile {
+    tmp: PathBuf,
+    path: PathBuf,
+    persisted: bool,
+}
+
+impl OutputFile {
+    /// Create the temporary file for `path`, returned to be written to.
+    pub fn create(path: &Path) -> Result<(Self, File)> {
+        let name = path
+            .file_name()
+            .with_context(|| format!("{} is not a file path", path.display()))?;
+        let mut tmp_name = name.to_os_string();
+        tmp_name.push(".tmp");
+        let tmp = path.with_file_name(tmp_name);
+
+        let file =
+            File::create(&tmp).with_context(|| format!("failed to create {}", tmp.display()))?;
+        debug!("Writing output to {}", tmp.display());
+
+        let output = Self {
+            tmp,
+            path: path.to_path_buf(),
+            persisted: false,
+        };
+        Ok((output, file))
+    }
+
+    /// Replace the target with what was written, once the file returned by `create` is closed.
+    pub fn persist(mut self) -> Result<()> {
+        fs::rename(&self.tmp, &self.path).with_context(|| {
+            format!(
+                "failed to rename {} to {}",
+                self.tmp.display(),
+                self.path.display()
+            )
+        })?;
+        self.persisted = true;
+        debug!("Renamed output to {}", self.path.display());
+
+        Ok(())
+    }
+}
+
+impl Drop for OutputFile {
+    /// Remove the temporary file of a run that failed.
+    fn drop(&mut self) {
+        if self.persisted {
+            return;
+        }
+        let _ = fs::remove_file(&self.tmp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn persist_replaces_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("forecast.json");
+        fs::write(&path, "old").unwrap();
+
+        let (output, mut file) = OutputFile::create(&path).unwrap();
+        file.write_all(b"new").unwrap();
+        drop(file);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+
+        output.persist().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn dropping_without_persist_keeps_target_and_removes_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("forecast.json");
+        fs::write(&path, "old").unwrap();
+
+        let (output, mut file) = OutputFile::create(&path).unwrap();
+        file.write_all(b"partial").unwrap();
+        drop(file);
+        drop(output);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
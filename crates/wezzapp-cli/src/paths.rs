@@ -0,0 +1,221 @@
+use anyhow::{Context, Result};
+use directories::{ProjectDirs, UserDirs};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Env var overriding where config (and cache, in its `cache` subdirectory) is kept.
+pub const CONFIG_DIR_ENV: &str = "WEZZAPP_CONFIG_DIR";
+
+const CREDENTIALS_FILE: &str = "credentials.toml";
+const ENCRYPTED_CREDENTIALS_FILE: &str = "credentials.enc";
+
+/// Platform-appropriate locations of wezzapp's files.
+///
+/// Linux: `~/.config/wezzapp` and `~/.cache/wezzapp` (honoring `XDG_*` vars),
+/// macOS: `~/Library/Application Support/wezzapp` and `~/Library/Caches/wezzapp`,
+/// Windows: `%APPDATA%\wezzapp\config` and `%LOCALAPPDATA%\wezzapp\cache`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    config_dir: PathBuf,
+    cache_dir: PathBuf,
+    /// Pre-XDG `~/.wezzapp`, migrated from unless the config dir is overridden.
+    legacy_dir: Option<PathBuf>,
+}
+
+impl Paths {
+    /// Resolve paths from `WEZZAPP_CONFIG_DIR` or the platform defaults.
+    pub fn new() -> Result<Self> {
+        let config_override = env::var_os(CONFIG_DIR_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+        let project = ProjectDirs::from("", "", "wezzapp");
+        let home = UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+
+        Self::resolve(config_override, project.as_ref(), home)
+    }
+
+    fn resolve(
+        config_override: Option<PathBuf>,
+        project: Option<&ProjectDirs>,
+        home: Option<PathBuf>,
+    ) -> Result<Self> {
+        if let Some(dir) = config_override {
+            debug!("Using config dir from {CONFIG_DIR_ENV}: {}", dir.display());
+            return Ok(Self {
+                cache_dir: dir.join("cache"),
+                config_dir: dir,
+                legacy_dir: None,
+            });
+        }
+
+        let project = project.context(format!(
+            "failed to determine config directory, please, set {CONFIG_DIR_ENV}"
+        ))?;
+
+        Ok(Self {
+            config_dir: project.config_dir().to_path_buf(),
+            cache_dir: project.cache_dir().to_path_buf(),
+            legacy_dir: home.map(|home| home.join(".wezzapp")),
+        })
+    }
+
+    /// Config file with credentials, profiles and settings.
+    pub fn credentials_file(&self) -> PathBuf {
+        self.config_dir.join(CREDENTIALS_FILE)
+    }
+
+    /// Passphrase-encrypted config, used with `--encrypted`.
+    pub fn encrypted_credentials_file(&self) -> PathBuf {
+        self.config_dir.join(ENCRYPTED_CREDENTIALS_FILE)
+    }
+
+    /// Location lookup cache (AccuWeather keys, geocoded coordinates).
+    pub fn locations_cache(&self) -> PathBuf {
+        self.cache_dir.join("locations.json")
+    }
+
+    /// Last fetched reports, served with `--allow-stale`.
+    pub fn reports_cache(&self) -> PathBuf {
+        self.cache_dir.join("reports.json")
+    }
+
+    /// Move config from the pre-XDG `~/.wezzapp` if there's no config in the new place yet.
+    ///
+    /// Caches are left behind, they are rebuilt on demand.
+    pub fn migrate_legacy(&self) -> Result<()> {
+        let Some(legacy_dir) = &self.legacy_dir else {
+            return Ok(());
+        };
+        let legacy = legacy_dir.join(CREDENTIALS_FILE);
+        let current = self.credentials_file();
+        if !legacy.exists() || current.exists() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.config_dir).context(format!(
+            "failed to create directory {}",
+            self.config_dir.display()
+        ))?;
+        move_file(&legacy, &current).context(format!(
+            "failed to move config file {} to {}",
+            legacy.display(),
+            current.display()
+        ))?;
+        eprintln!(
+            "Moved config file {} to {}",
+            legacy.display(),
+            current.display()
+        );
+
+        Ok(())
+    }
+}
+
+/// Rename, falling back to copy and remove when `from` and `to` are on different filesystems.
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn legacy_paths(tmpdir: &TempDir) -> Paths {
+        Paths {
+            config_dir: tmpdir.path().join("config"),
+            cache_dir: tmpdir.path().join("cache"),
+            legacy_dir: Some(tmpdir.path().join(".wezzapp")),
+        }
+    }
+
+    #[test]
+    fn env_override_holds_config_and_cache() {
+        let project = ProjectDirs::from("", "", "wezzapp");
+
+        let paths = Paths::resolve(
+            Some(PathBuf::from("/tmp/wezzapp")),
+            project.as_ref(),
+            Some(PathBuf::from("/home/user")),
+        )
+        .unwrap();
+
+        assert_eq!(
+            paths.credentials_file(),
+            Path::new("/tmp/wezzapp/credentials.toml")
+        );
+        assert_eq!(
+            paths.reports_cache(),
+            Path::new("/tmp/wezzapp/cache/reports.json")
+        );
+        assert_eq!(paths.legacy_dir, None, "override shouldn't migrate");
+    }
+
+    #[test]
+    fn defaults_to_platform_dirs() {
+        let Some(project) = ProjectDirs::from("", "", "wezzapp") else {
+            return;
+        };
+
+        let paths =
+            Paths::resolve(None, Some(&project), Some(PathBuf::from("/home/user"))).unwrap();
+
+        assert_eq!(
+            paths.credentials_file(),
+            project.config_dir().join("credentials.toml")
+        );
+        assert_eq!(
+            paths.locations_cache(),
+            project.cache_dir().join("locations.json")
+        );
+        assert_eq!(paths.legacy_dir, Some(PathBuf::from("/home/user/.wezzapp")));
+    }
+
+    #[test]
+    fn missing_dirs_without_override_is_an_error() {
+        let err = Paths::resolve(None, None, None).unwrap_err();
+
+        assert!(err.to_string().contains(CONFIG_DIR_ENV), "{err}");
+    }
+
+    #[test]
+    fn migrates_legacy_config() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let paths = legacy_paths(&tmpdir);
+        fs::create_dir_all(tmpdir.path().join(".wezzapp")).unwrap();
+        fs::write(
+            tmpdir.path().join(".wezzapp/credentials.toml"),
+            "language = \"uk\"",
+        )
+        .unwrap();
+
+        paths.migrate_legacy().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(paths.credentials_file()).unwrap(),
+            "language = \"uk\""
+        );
+        assert!(!tmpdir.path().join(".wezzapp/credentials.toml").exists());
+    }
+
+    #[test]
+    fn migration_keeps_existing_config() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let paths = legacy_paths(&tmpdir);
+        fs::create_dir_all(tmpdir.path().join(".wezzapp")).unwrap();
+        fs::write(tmpdir.path().join(".wezzapp/credentials.toml"), "old").unwrap();
+        fs::create_dir_all(tmpdir.path().join("config")).unwrap();
+        fs::write(paths.credentials_file(), "new").unwrap();
+
+        paths.migrate_legacy().unwrap();
+
+        assert_eq!(fs::read_to_string(paths.credentials_file()).unwrap(), "new");
+        assert!(tmpdir.path().join(".wezzapp/credentials.toml").exists());
+    }
+}
@@ -1,5 +1,8 @@
 use anyhow::{Context, Result};
-use inquire::{Confirm, Text};
+use inquire::error::InquireResult;
+use inquire::{Confirm, InquireError, Password, Select, Text};
+use std::collections::HashMap;
+use std::fmt;
 use tracing::debug;
 use wezzapp_core::credentials::Credentials;
 use wezzapp_core::provider::Provider;
@@ -12,8 +15,55 @@ pub trait ConfigurePrompter {
     /// Ask user to confirm default provider change.
     fn confirm_set_default(&mut self, provider: Provider) -> Result<bool>;
 
-    /// Ask user for credentials for a given provider.
-    fn prompt_credentials(&mut self, provider: Provider) -> Result<Credentials>;
+    /// Ask user for credentials for a given provider, offering `optional` values already
+    /// known, e.g. the stored base URL, so they're kept unless changed.
+    fn prompt_credentials(
+        &mut self,
+        provider: Provider,
+        optional: &HashMap<&str, String>,
+    ) -> Result<Credentials>;
+
+    /// Ask user which provider to configure.
+    fn select_provider(&mut self) -> Result<Provider>;
+}
+
+/// User pressed Ctrl-C or Esc at a prompt, `main` exits without a stack trace: successfully
+/// from `configure`, with `exit_code::CANCELLED` otherwise.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Answer of an `inquire` prompt, failing with `Cancelled` on Ctrl-C or Esc
+/// and with `failure` as context on any other error.
+fn run_prompt<T, C>(answer: InquireResult<T>, failure: C) -> Result<T>
+where
+    C: fmt::Display + Send + Sync + 'static,
+{
+    match answer {
+        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+            debug!("Prompt cancelled");
+            Err(Cancelled.into())
+        }
+        answer => answer.context(failure),
+    }
+}
+
+/// Ask for the config passphrase, twice if `confirm` is set.
+pub fn prompt_passphrase(confirm: bool) -> Result<String> {
+    debug!("Prompting for config passphrase");
+    let mut prompt = Password::new("Config passphrase:");
+    if !confirm {
+        prompt = prompt.without_confirmation();
+    }
+
+    run_prompt(prompt.prompt(), "failed to read passphrase from stdin")
 }
 
 /// Real implementation using `inquire`.
@@ -30,42 +80,102 @@ impl ConfigurePrompter for InquirePrompter {
         debug!("Confirming overwrite");
         let answer = Confirm::new("Credentials already exist. Overwrite?")
             .with_default(true)
-            .prompt()
-            .context("failed to read confirmation from stdin")?;
+            .prompt();
 
-        Ok(answer)
+        run_prompt(answer, "failed to read confirmation from stdin")
     }
 
     fn confirm_set_default(&mut self, _provider: Provider) -> Result<bool> {
         debug!("Confirming default provider change");
         let answer = Confirm::new("Do you want to make this provider the default?")
             .with_default(true)
-            .prompt()
-            .context("failed to read confirmation from stdin")?;
+            .prompt();
 
-        Ok(answer)
+        run_prompt(answer, "failed to read confirmation from stdin")
     }
 
-    fn prompt_credentials(&mut self, provider: Provider) -> Result<Credentials> {
+    fn prompt_credentials(
+        &mut self,
+        provider: Provider,
+        optional: &HashMap<&str, String>,
+    ) -> Result<Credentials> {
         debug!("Prompting for credentials for provider {:?}", provider);
-        match provider {
-            Provider::WeatherApi => {
-                let api_key = Text::new("Enter WeatherAPI API key:")
-                    .with_help_message("Sign up at https://www.weatherapi.com/")
-                    .prompt()
-                    .context("failed to read WeatherAPI API key from stdin")?;
-
-                Ok(Credentials::WeatherApi { api_key })
-            }
+        let mut fields = HashMap::new();
+
+        for field in provider.required_credential_fields() {
+            let value = Text::new(&format!("Enter {}:", field.label))
+                .with_help_message(field.help)
+                .prompt();
+            let value = run_prompt(value, format!("failed to read {} from stdin", field.label))?;
+
+            fields.insert(field.name, value);
+        }
 
-            Provider::AccuWeather => {
-                let api_key = Text::new("Enter AccuWeather API key:")
-                    .with_help_message("Visit https://developer.accuweather.com/")
-                    .prompt()
-                    .context("failed to read AccuWeather API key from stdin")?;
+        for field in provider.optional_credential_fields() {
+            let current = optional.get(field.name).map_or("", String::as_str);
+            let value = Text::new(&format!("Enter {} (optional):", field.label))
+                .with_help_message(field.help)
+                .with_initial_value(current)
+                .prompt();
+            let value = run_prompt(value, format!("failed to read {} from stdin", field.label))?;
 
-                Ok(Credentials::AccuWeather { api_key })
+            let value = value.trim();
+            if !value.is_empty() {
+                fields.insert(field.name, value.to_string());
             }
         }
+
+        Credentials::from_fields(provider, &fields)
+            .with_context(|| format!("incomplete credentials for {provider:?}"))
+    }
+
+    fn select_provider(&mut self) -> Result<Provider> {
+        debug!("Prompting for provider");
+        let options = Provider::ALL
+            .iter()
+            .map(
+                |provider| match provider.required_credential_fields().first() {
+                    Some(field) => format!("{} ({})", provider.as_str(), field.help),
+                    None => provider.as_str().to_string(),
+                },
+            )
+            .collect();
+
+        let selected = Select::new("Which provider do you want to configure?", options)
+            .with_help_message("↑↓ to move, type to filter, enter to select")
+            .raw_prompt();
+        let selected = run_prompt(selected, "failed to read provider from stdin")?;
+
+        Ok(Provider::ALL[selected.index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ctrl_c_and_esc_cancel() {
+        for err in [
+            InquireError::OperationInterrupted,
+            InquireError::OperationCanceled,
+        ] {
+            let err = run_prompt::<bool, _>(Err(err), "failed").unwrap_err();
+
+            assert!(err.is::<Cancelled>(), "{err:?}");
+        }
+    }
+
+    #[test]
+    fn other_prompt_errors_keep_context() {
+        let err = run_prompt::<bool, _>(Err(InquireError::NotTTY), "failed to read").unwrap_err();
+
+        assert!(!err.is::<Cancelled>());
+        assert_eq!(err.to_string(), "failed to read");
+    }
+
+    #[test]
+    fn answer_is_passed_through() {
+        assert!(run_prompt(Ok(true), "failed").unwrap());
     }
 }
@@ -0,0 +1,75 @@
+use crate::stderr::Stderr;
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::Mutex;
+use wezzapp_core::apis::quota::{QuotaObserver, QuotaStatus};
+use wezzapp_core::provider::Provider;
+
+/// Warn when less than this share of the quota is left.
+const WARN_RATIO: f64 = 0.1;
+/// Warn threshold when provider doesn't report the total quota.
+const WARN_REMAINING: u32 = 5;
+
+/// Prints a warning to stderr once per provider when its quota runs low.
+#[derive(Debug, Default)]
+pub struct QuotaWarning {
+    warned: Mutex<HashSet<Provider>>,
+}
+
+impl QuotaObserver for QuotaWarning {
+    fn observe(&self, status: &QuotaStatus) {
+        let Some(message) = warning(status) else {
+            return;
+        };
+
+        let mut warned = self.warned.lock().unwrap_or_else(|e| e.into_inner());
+        if warned.insert(status.provider) {
+            let _ = writeln!(Stderr, "warning: {message}");
+        }
+    }
+}
+
+/// Warning message if `status` is below the threshold.
+fn warning(status: &QuotaStatus) -> Option<String> {
+    let low = match status.limit {
+        Some(limit) => f64::from(status.remaining) < f64::from(limit) * WARN_RATIO,
+        None => status.remaining <= WARN_REMAINING,
+    };
+    if !low {
+        return None;
+    }
+
+    let remaining = match status.limit {
+        Some(limit) => format!("{} of {limit}", status.remaining),
+        None => status.remaining.to_string(),
+    };
+    Some(format!(
+        "only {remaining} `{}` API calls left",
+        status.provider.as_str()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(99, Some(1000), Some("only 99 of 1000 `weatherapi` API calls left"))]
+    #[case(100, Some(1000), None)]
+    #[case(5, None, Some("only 5 `weatherapi` API calls left"))]
+    #[case(6, None, None)]
+    fn warns_below_threshold(
+        #[case] remaining: u32,
+        #[case] limit: Option<u32>,
+        #[case] expected: Option<&str>,
+    ) {
+        let status = QuotaStatus {
+            provider: Provider::WeatherApi,
+            remaining,
+            limit,
+        };
+
+        assert_eq!(warning(&status).as_deref(), expected);
+    }
+}
@@ -0,0 +1,1289 @@
+use crate::i18n::Locale;
+use clap::ValueEnum;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use wezzapp_core::air_quality::AirQuality;
+use wezzapp_core::alert::WeatherAlert;
+use wezzapp_core::apis::{CurrentConditions, WeatherReport};
+use wezzapp_core::condition::WeatherCondition;
+use wezzapp_core::temperature::to_fahrenheit;
+
+/// Output format for weather reports.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum Format {
+    /// Full human-readable report.
+    #[default]
+    Text,
+
+    /// Single line, e.g. for status bars: `Kyiv 2024-11-29 ☀ 12°/3°C`.
+    Compact,
+
+    /// Single-line JSON, for scripts, `jq` and logging. Several reports (a date range or batch)
+    /// are written as one array.
+    Json,
+
+    /// Indented multi-line JSON, for reading.
+    JsonPretty,
+}
+
+/// When to color text output.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum ColorChoice {
+    /// Color only when stdout is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+
+    /// Always color.
+    Always,
+
+    /// Never color.
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether text output should be colored, checking that output goes to a terminal
+    /// and `NO_COLOR` env var (https://no-color.org) in `Auto` mode.
+    pub fn enabled(self, is_terminal: bool) -> bool {
+        let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        self.resolve(is_terminal, no_color_env)
+    }
+
+    fn resolve(self, is_terminal: bool, no_color_env: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => is_terminal && !no_color_env,
+        }
+    }
+}
+
+/// Unit to display temperatures in, reports themselves always hold °C.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    /// Convert a °C report value to this unit.
+    pub fn convert(self, celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => to_fahrenheit(celsius),
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+}
+
+/// Temperatures at or below this are rendered blue.
+const COLD_THRESHOLD: f64 = 5.0;
+
+/// Temperatures at or above this are rendered red.
+const HOT_THRESHOLD: f64 = 25.0;
+
+/// Condition keywords mapped to emoji, most severe first.
+///
+/// The first keyword found in the (lowercased) description wins.
+const CONDITION_EMOJIS: &[(&str, &str)] = &[
+    ("thunder", "⛈"),
+    ("snow", "❄"),
+    ("sleet", "🌨"),
+    ("ice", "🌨"),
+    ("rain", "🌧"),
+    ("shower", "🌦"),
+    ("drizzle", "🌦"),
+    ("fog", "🌫"),
+    ("mist", "🌫"),
+    ("haz", "🌫"),
+    ("partly", "⛅"),
+    ("overcast", "☁"),
+    ("cloud", "☁"),
+    ("sun", "☀"),
+    ("clear", "☀"),
+];
+
+/// Condition icons of compact output: user overrides from `[icons]` config table,
+/// falling back to built-in emoji.
+#[derive(Debug, Default, Clone)]
+pub struct Icons {
+    overrides: HashMap<WeatherCondition, String>,
+}
+
+impl Icons {
+    pub fn new(overrides: HashMap<WeatherCondition, String>) -> Self {
+        Self { overrides }
+    }
+
+    /// Icon for `condition`, or the built-in emoji matching free-text `description`,
+    /// or of the normalized `condition` when the description has no known keyword
+    /// (e.g. in another language).
+    fn icon<'a>(&'a self, condition: WeatherCondition, description: &str) -> Option<&'a str> {
+        match self.overrides.get(&condition) {
+            Some(icon) => Some(icon),
+            None => condition_emoji(description).or_else(|| normalized_emoji(condition)),
+        }
+    }
+}
+
+/// `WeatherReport` fields selectable with `--field`, as named in JSON output.
+pub const REPORT_FIELDS: &[&str] = &[
+    "provider",
+    "date",
+    "location",
+    "condition",
+    "description",
+    "max_temperature",
+    "min_temperature",
+    "avg_temperature",
+    "feels_like",
+    "uv_index",
+    "wind_kph",
+    "wind_direction",
+    "humidity",
+    "sunrise",
+    "sunset",
+    "air_quality",
+    "alerts",
+    "stale_since",
+];
+
+/// Render a single report field as its bare JSON value: strings unquoted, missing values empty.
+///
+/// `field` is one of `REPORT_FIELDS`.
+pub fn render_field(report: &WeatherReport, field: &str) -> String {
+    let value = serde_json::to_value(report).expect("weather report is always serializable");
+
+    match &value[field] {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// How reports are rendered in text and compact formats.
+#[derive(Debug, Default, Clone)]
+pub struct RenderOptions {
+    /// Color text output, other formats are always plain.
+    pub color: bool,
+    /// Decimals to round temperatures to.
+    pub precision: usize,
+    /// Unit to show temperatures in.
+    pub units: TemperatureUnit,
+    /// Condition icons of compact output.
+    pub icons: Icons,
+    /// Language of text output labels.
+    pub locale: Locale,
+}
+
+/// Render report in the given format (without trailing newline).
+///
+/// JSON ignores `options` and keeps full °C values.
+pub fn render(report: &WeatherReport, format: Format, options: &RenderOptions) -> String {
+    let RenderOptions {
+        color,
+        precision,
+        units,
+        ref icons,
+        locale,
+    } = *options;
+
+    match format {
+        Format::Text => render_text(report, color, precision, units, locale),
+        Format::Compact => render_compact(report, precision, units, icons),
+        Format::Json => render_json(report),
+        Format::JsonPretty => render_json_pretty(report),
+    }
+}
+
+/// Render real-time conditions of `get --now` in the given format (without trailing newline).
+///
+/// Same use of `options` as in `render`.
+pub fn render_current(
+    current: &CurrentConditions,
+    format: Format,
+    options: &RenderOptions,
+) -> String {
+    let RenderOptions {
+        color,
+        precision,
+        units,
+        ref icons,
+        locale,
+    } = *options;
+
+    match format {
+        Format::Text => render_current_text(current, color, precision, units, locale),
+        Format::Compact => render_current_compact(current, precision, units, icons),
+        Format::Json => {
+            serde_json::to_string(current).expect("current conditions are always serializable")
+        }
+        Format::JsonPretty => serde_json::to_string_pretty(current)
+            .expect("current conditions are always serializable"),
+    }
+}
+
+/// Render report as a single-line JSON object.
+fn render_json(report: &WeatherReport) -> String {
+    serde_json::to_string(report).expect("weather report is always serializable")
+}
+
+/// Render report as indented multi-line JSON.
+fn render_json_pretty(report: &WeatherReport) -> String {
+    serde_json::to_string_pretty(report).expect("weather report is always serializable")
+}
+
+/// Temperature fields of JSON reports and current conditions, all in °C.
+const JSON_TEMPERATURES: &[&str] = &[
+    "max_temperature",
+    "min_temperature",
+    "avg_temperature",
+    "feels_like",
+    "temperature",
+];
+
+/// Render a report or current conditions as JSON, pretty if `pretty`, with every temperature
+/// field `name` replaced by `name_c` and `name_f` holding it in °C and °F.
+pub fn render_json_both_units<T: Serialize>(value: &T, pretty: bool) -> String {
+    let mut json = serde_json::to_value(value).expect("reports are always serializable");
+    if let Value::Object(fields) = &mut json {
+        for name in JSON_TEMPERATURES {
+            let Some(celsius) = fields.remove(*name) else {
+                continue;
+            };
+            let fahrenheit = celsius.as_f64().map(to_fahrenheit);
+            fields.insert(format!("{name}_c"), celsius);
+            fields.insert(format!("{name}_f"), fahrenheit.into());
+        }
+    }
+
+    if pretty {
+        serde_json::to_string_pretty(&json)
+    } else {
+        serde_json::to_string(&json)
+    }
+    .expect("reports are always serializable")
+}
+
+/// Render report as labeled multi-line text.
+fn render_text(
+    report: &WeatherReport,
+    color: bool,
+    precision: usize,
+    units: TemperatureUnit,
+    locale: Locale,
+) -> String {
+    let description = if color {
+        report.description.bold().to_string()
+    } else {
+        report.description.clone()
+    };
+
+    let mut temperatures = format!(
+        "{}: {} {}, {} {}",
+        locale.tr("Temperature"),
+        locale.tr("max"),
+        temperature(report.max_temperature, precision, color, units),
+        locale.tr("min"),
+        temperature(report.min_temperature, precision, color, units)
+    );
+    if let Some(avg_temperature) = report.avg_temperature {
+        temperatures.push_str(&format!(
+            ", {} {}",
+            locale.tr("avg"),
+            temperature(avg_temperature, precision, color, units)
+        ));
+    }
+
+    let mut lines = vec![
+        format!("{}: {}", locale.tr("Location"), report.location),
+        format!("{}: {}", locale.tr("Date"), report.date),
+        format!("{}: {:?}", locale.tr("Provider"), report.provider),
+        format!("{}: {description}", locale.tr("Conditions")),
+        temperatures,
+    ];
+    if let Some(feels_like) = report.feels_like {
+        lines.push(format!(
+            "{}: {}",
+            locale.tr("Feels like"),
+            temperature(feels_like, precision, color, units)
+        ));
+    }
+    if let Some(uv_index) = report.uv_index {
+        lines.push(format!(
+            "{}: {uv_index} ({})",
+            locale.tr("UV index"),
+            uv_label(uv_index)
+        ));
+    }
+    if let Some(wind) = wind(report.wind_kph, report.wind_direction.as_deref()) {
+        lines.push(format!("{}: {wind}", locale.tr("Wind")));
+    }
+    if let Some(humidity) = report.humidity {
+        lines.push(format!("{}: {humidity:.0}%", locale.tr("Humidity")));
+    }
+    if let Some(sunrise) = report.sunrise {
+        lines.push(format!(
+            "{}: {}",
+            locale.tr("Sunrise"),
+            sunrise.format("%H:%M")
+        ));
+    }
+    if let Some(sunset) = report.sunset {
+        lines.push(format!(
+            "{}: {}",
+            locale.tr("Sunset"),
+            sunset.format("%H:%M")
+        ));
+    }
+    if let Some(air_quality) = &report.air_quality {
+        lines.push(format!(
+            "{}: {}",
+            locale.tr("AQI"),
+            aqi_summary(air_quality)
+        ));
+    }
+    match report.alerts.as_deref() {
+        Some([]) => lines.push(locale.tr("No active alerts.").to_string()),
+        Some(alerts) => lines.extend(alerts.iter().map(|alert| alert_lines(alert, color))),
+        None => {}
+    }
+    if let Some(minutes) = report.stale_minutes() {
+        lines.push(stale_note(minutes));
+    }
+
+    lines.join("\n")
+}
+
+/// Render current conditions as labeled multi-line text.
+fn render_current_text(
+    current: &CurrentConditions,
+    color: bool,
+    precision: usize,
+    units: TemperatureUnit,
+    locale: Locale,
+) -> String {
+    let description = if color {
+        current.description.bold().to_string()
+    } else {
+        current.description.clone()
+    };
+
+    let mut lines = vec![
+        format!("{}: {}", locale.tr("Location"), current.location),
+        format!(
+            "{}: {}",
+            locale.tr("Observed"),
+            current.observed_at.format("%Y-%m-%d %H:%M UTC")
+        ),
+        format!("{}: {:?}", locale.tr("Provider"), current.provider),
+        format!("{}: {description}", locale.tr("Conditions")),
+        format!(
+            "{}: {}",
+            locale.tr("Temperature"),
+            temperature(current.temperature, precision, color, units)
+        ),
+    ];
+    if let Some(feels_like) = current.feels_like {
+        lines.push(format!(
+            "{}: {}",
+            locale.tr("Feels like"),
+            temperature(feels_like, precision, color, units)
+        ));
+    }
+    if let Some(wind) = wind(current.wind_kph, current.wind_direction.as_deref()) {
+        lines.push(format!("{}: {wind}", locale.tr("Wind")));
+    }
+    if let Some(humidity) = current.humidity {
+        lines.push(format!("{}: {humidity:.0}%", locale.tr("Humidity")));
+    }
+
+    lines.join("\n")
+}
+
+/// Format °C `value` in `units`, blue when cold and red when hot if `color` is set.
+fn temperature(value: f64, precision: usize, color: bool, units: TemperatureUnit) -> String {
+    let text = format!(
+        "{}{}",
+        round(units.convert(value), precision),
+        units.symbol()
+    );
+
+    match value {
+        _ if !color => text,
+        value if value <= COLD_THRESHOLD => text.blue().to_string(),
+        value if value >= HOT_THRESHOLD => text.red().to_string(),
+        _ => text,
+    }
+}
+
+/// Round half away from zero to `precision` decimals, avoiding `-0`.
+pub fn round(value: f64, precision: usize) -> String {
+    let factor = 10f64.powi(precision as i32);
+    // Adding zero turns `-0.0` into `0.0`.
+    let rounded = (value * factor).round() / factor + 0.0;
+
+    format!("{rounded:.precision$}")
+}
+
+/// Air quality summary, e.g. `42 (Good)` or `Good, PM2.5 3.1 μg/m³`.
+fn aqi_summary(air_quality: &AirQuality) -> String {
+    let label = air_quality.category.label();
+    let mut summary = match air_quality.index {
+        Some(index) => format!("{index:.0} ({label})"),
+        None => label.to_string(),
+    };
+    if let Some(pm2_5) = air_quality.pm2_5 {
+        summary.push_str(&format!(", PM2.5 {pm2_5:.1} μg/m³"));
+    }
+
+    summary
+}
+
+/// Alert headline with severity and affected areas, e.g.
+/// `Alert: Flood Warning (Moderate)` followed by `  Areas: Kyiv, Brovary`.
+fn alert_lines(alert: &WeatherAlert, color: bool) -> String {
+    let mut text = format!("Alert: {}", alert.headline);
+    if let Some(severity) = &alert.severity {
+        text.push_str(&format!(" ({severity})"));
+    }
+    if color {
+        text = text.red().bold().to_string();
+    }
+    if !alert.areas.is_empty() {
+        text.push_str(&format!("\n  Areas: {}", alert.areas.join(", ")));
+    }
+
+    text
+}
+
+/// Wind speed and direction, e.g. `12 kph NW`, whichever of them is known.
+fn wind(kph: Option<f64>, direction: Option<&str>) -> Option<String> {
+    let speed = kph.map(|kph| format!("{kph:.0} kph"));
+
+    match (speed, direction) {
+        (Some(speed), Some(direction)) => Some(format!("{speed} {direction}")),
+        (Some(speed), None) => Some(speed),
+        (None, Some(direction)) => Some(direction.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Note for cached reports served while the provider is down.
+fn stale_note(minutes: i64) -> String {
+    let unit = if minutes == 1 { "minute" } else { "minutes" };
+
+    format!("(stale, fetched {minutes} {unit} ago)")
+}
+
+/// WHO exposure category for a UV index value.
+fn uv_label(uv_index: f64) -> &'static str {
+    match uv_index {
+        uv if uv < 3.0 => "low",
+        uv if uv < 6.0 => "moderate",
+        uv if uv < 8.0 => "high",
+        uv if uv < 11.0 => "very high",
+        _ => "extreme",
+    }
+}
+
+/// Render report as a single line: `<city> <date> [emoji] <max>°/<min>°C [(feels <t>°C)]`.
+fn render_compact(
+    report: &WeatherReport,
+    precision: usize,
+    units: TemperatureUnit,
+    icons: &Icons,
+) -> String {
+    let city = report
+        .location
+        .split(',')
+        .next()
+        .unwrap_or(&report.location)
+        .trim();
+
+    let mut parts = vec![city.to_string(), report.date.clone()];
+    if let Some(emoji) = icons.icon(report.condition, &report.description) {
+        parts.push(emoji.to_string());
+    }
+    parts.push(format!(
+        "{}°/{}{}",
+        round(units.convert(report.max_temperature), precision),
+        round(units.convert(report.min_temperature), precision),
+        units.symbol()
+    ));
+    if let Some(feels_like) = report.feels_like {
+        parts.push(format!(
+            "(feels {}{})",
+            round(units.convert(feels_like), precision),
+            units.symbol()
+        ));
+    }
+    match report.alerts.as_ref().map(Vec::len) {
+        Some(0) | None => {}
+        Some(1) => parts.push("⚠ 1 alert".to_string()),
+        Some(count) => parts.push(format!("⚠ {count} alerts")),
+    }
+    if let Some(minutes) = report.stale_minutes() {
+        parts.push(stale_note(minutes));
+    }
+
+    parts.join(" ")
+}
+
+/// Render current conditions as a single line: `<city> now [emoji] <t>°C [(feels <t>°C)]`.
+fn render_current_compact(
+    current: &CurrentConditions,
+    precision: usize,
+    units: TemperatureUnit,
+    icons: &Icons,
+) -> String {
+    let city = current
+        .location
+        .split(',')
+        .next()
+        .unwrap_or(&current.location)
+        .trim();
+
+    let mut parts = vec![city.to_string(), "now".to_string()];
+    if let Some(emoji) = icons.icon(current.condition, &current.description) {
+        parts.push(emoji.to_string());
+    }
+    parts.push(temperature(current.temperature, precision, false, units));
+    if let Some(feels_like) = current.feels_like {
+        parts.push(format!(
+            "(feels {})",
+            temperature(feels_like, precision, false, units)
+        ));
+    }
+
+    parts.join(" ")
+}
+
+/// Look up emoji for a free-text condition description.
+fn condition_emoji(description: &str) -> Option<&'static str> {
+    let description = description.to_lowercase();
+
+    CONDITION_EMOJIS
+        .iter()
+        .find(|(keyword, _)| description.contains(keyword))
+        .map(|(_, emoji)| *emoji)
+}
+
+/// Built-in emoji of a normalized condition, none for `Unknown`.
+fn normalized_emoji(condition: WeatherCondition) -> Option<&'static str> {
+    match condition {
+        WeatherCondition::Clear => Some("☀"),
+        WeatherCondition::PartlyCloudy => Some("⛅"),
+        WeatherCondition::Cloudy => Some("☁"),
+        WeatherCondition::Fog => Some("🌫"),
+        WeatherCondition::Drizzle => Some("🌦"),
+        WeatherCondition::Rain => Some("🌧"),
+        WeatherCondition::Sleet => Some("🌨"),
+        WeatherCondition::Snow => Some("❄"),
+        WeatherCondition::Thunderstorm => Some("⛈"),
+        WeatherCondition::Windy => Some("💨"),
+        WeatherCondition::Unknown => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeDelta, Utc};
+    use rstest::rstest;
+    use wezzapp_core::air_quality::AqiCategory;
+    use wezzapp_core::condition::WeatherCondition;
+    use wezzapp_core::provider::Provider;
+    use wezzapp_core::testing;
+
+    fn report(description: &str) -> WeatherReport {
+        WeatherReport {
+            description: description.to_string(),
+            max_temperature: 12.2,
+            ..testing::report()
+        }
+    }
+
+    #[test]
+    fn text_renders_labeled_lines() {
+        assert_eq!(
+            render(&report("Sunny"), Format::Text, &RenderOptions::default()),
+            "Location: Kyiv, Ukraine\n\
+             Date: 2024-11-29\n\
+             Provider: WeatherApi\n\
+             Conditions: Sunny\n\
+             Temperature: max 12°C, min 3°C"
+        );
+    }
+
+    #[test]
+    fn text_renders_labels_in_locale() {
+        let report = WeatherReport {
+            humidity: Some(64.0),
+            ..report("Сонячно")
+        };
+
+        assert_eq!(
+            render(
+                &report,
+                Format::Text,
+                &RenderOptions {
+                    locale: Locale::Uk,
+                    ..RenderOptions::default()
+                }
+            ),
+            "Місце: Kyiv, Ukraine\n\
+             Дата: 2024-11-29\n\
+             Провайдер: WeatherApi\n\
+             Умови: Сонячно\n\
+             Температура: макс 12°C, мін 3°C\n\
+             Вологість: 64%"
+        );
+    }
+
+    #[test]
+    fn text_renders_avg_temperature_when_present() {
+        let report = WeatherReport {
+            avg_temperature: Some(7.6),
+            ..report("Sunny")
+        };
+
+        assert!(
+            render(&report, Format::Text, &RenderOptions::default())
+                .contains("Temperature: max 12°C, min 3°C, avg 8°C")
+        );
+    }
+
+    #[test]
+    fn text_and_compact_mark_stale_reports() {
+        let report = WeatherReport {
+            stale_since: Some(Utc::now() - TimeDelta::minutes(42)),
+            ..report("Sunny")
+        };
+
+        assert!(
+            render(&report, Format::Text, &RenderOptions::default())
+                .ends_with("\n(stale, fetched 42 minutes ago)")
+        );
+        assert_eq!(
+            render(&report, Format::Compact, &RenderOptions::default()),
+            "Kyiv 2024-11-29 ☀ 12°/3°C (stale, fetched 42 minutes ago)"
+        );
+    }
+
+    #[rstest]
+    #[case(0, "(stale, fetched 0 minutes ago)")]
+    #[case(1, "(stale, fetched 1 minute ago)")]
+    fn stale_note_pluralizes(#[case] minutes: i64, #[case] expected: &str) {
+        assert_eq!(stale_note(minutes), expected);
+    }
+
+    #[rstest]
+    #[case(Some(12.4), Some("NW"), Some("Wind: 12 kph NW"))]
+    #[case(Some(12.4), None, Some("Wind: 12 kph"))]
+    #[case(None, Some("NW"), Some("Wind: NW"))]
+    #[case(None, None, None)]
+    fn text_renders_known_wind_parts(
+        #[case] wind_kph: Option<f64>,
+        #[case] wind_direction: Option<&str>,
+        #[case] expected: Option<&str>,
+    ) {
+        let report = WeatherReport {
+            wind_kph,
+            wind_direction: wind_direction.map(String::from),
+            ..report("Sunny")
+        };
+
+        let text = render(&report, Format::Text, &RenderOptions::default());
+        let line = text.lines().find(|line| line.starts_with("Wind:"));
+
+        assert_eq!(line, expected);
+    }
+
+    #[test]
+    fn text_renders_humidity_only_when_present() {
+        let with = WeatherReport {
+            humidity: Some(71.6),
+            ..report("Sunny")
+        };
+        let without = report("Sunny");
+
+        assert!(
+            render(&with, Format::Text, &RenderOptions::default())
+                .lines()
+                .any(|line| line == "Humidity: 72%"),
+            "unexpected output: {}",
+            render(&with, Format::Text, &RenderOptions::default())
+        );
+        assert!(!render(&without, Format::Text, &RenderOptions::default()).contains("Humidity"));
+    }
+
+    #[test]
+    fn text_renders_uv_index_with_label_when_present() {
+        let report = WeatherReport {
+            uv_index: Some(6.0),
+            wind_kph: None,
+            wind_direction: None,
+            humidity: None,
+            ..report("Sunny")
+        };
+
+        assert!(
+            render(&report, Format::Text, &RenderOptions::default())
+                .ends_with("\nUV index: 6 (high)"),
+            "unexpected output: {}",
+            render(&report, Format::Text, &RenderOptions::default())
+        );
+    }
+
+    #[test]
+    fn text_renders_sunrise_and_sunset_when_present() {
+        let report = WeatherReport {
+            sunrise: Some("07:31:00".parse().unwrap()),
+            sunset: Some("16:02:00".parse().unwrap()),
+            ..report("Sunny")
+        };
+
+        assert!(
+            render(&report, Format::Text, &RenderOptions::default())
+                .ends_with("\nSunrise: 07:31\nSunset: 16:02"),
+            "unexpected output: {}",
+            render(&report, Format::Text, &RenderOptions::default())
+        );
+    }
+
+    #[test]
+    fn text_has_no_color_codes_when_color_disabled() {
+        assert!(
+            !render(&report("Sunny"), Format::Text, &RenderOptions::default()).contains('\x1b')
+        );
+    }
+
+    #[test]
+    fn text_colors_temperatures_and_condition_when_enabled() {
+        let output = render(
+            &report("Sunny"),
+            Format::Text,
+            &RenderOptions {
+                color: true,
+                ..RenderOptions::default()
+            },
+        );
+
+        assert!(
+            output.contains(&"Sunny".bold().to_string()),
+            "condition should be bold: {output:?}"
+        );
+        assert!(
+            output.contains(&"3°C".blue().to_string()),
+            "cold temperature should be blue: {output:?}"
+        );
+        assert!(
+            output.contains("max 12°C,"),
+            "mild temperature should stay plain: {output:?}"
+        );
+    }
+
+    #[rstest]
+    #[case(12.2, 0, "12")]
+    #[case(12.2, 1, "12.2")]
+    #[case(12.0, 1, "12.0")]
+    #[case(2.5, 0, "3")]
+    #[case(-0.4, 0, "0")]
+    #[case(-2.5, 0, "-3")]
+    fn rounds_to_precision(#[case] value: f64, #[case] precision: usize, #[case] expected: &str) {
+        assert_eq!(round(value, precision), expected);
+    }
+
+    #[test]
+    fn text_and_compact_honor_precision() {
+        let report = report("Sunny");
+
+        assert!(
+            render(
+                &report,
+                Format::Text,
+                &RenderOptions {
+                    precision: 1,
+                    ..RenderOptions::default()
+                }
+            )
+            .contains("max 12.2°C, min 3.0°C")
+        );
+        assert!(
+            render(
+                &report,
+                Format::Compact,
+                &RenderOptions {
+                    precision: 1,
+                    ..RenderOptions::default()
+                }
+            )
+            .ends_with("12.2°/3.0°C")
+        );
+        assert!(
+            render(&report, Format::Json, &RenderOptions::default())
+                .contains(r#""max_temperature":12.2"#)
+        );
+    }
+
+    #[test]
+    fn json_is_single_line_and_json_pretty_is_not() {
+        let report = report("Sunny");
+
+        let compact = render(&report, Format::Json, &RenderOptions::default());
+        let pretty = render(&report, Format::JsonPretty, &RenderOptions::default());
+
+        assert!(!compact.contains('\n'), "{compact}");
+        assert!(
+            pretty.contains("\n  \"location\": \"Kyiv, Ukraine\""),
+            "{pretty}"
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap()
+        );
+    }
+
+    #[test]
+    fn report_fields_match_json_keys() {
+        let value = serde_json::to_value(report("Sunny")).unwrap();
+        let keys: Vec<_> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+
+        assert_eq!(keys, REPORT_FIELDS);
+    }
+
+    #[rstest]
+    #[case("max_temperature", "12.2")]
+    #[case("description", "Sunny")]
+    #[case("location", "Kyiv, Ukraine")]
+    #[case("condition", "clear")]
+    #[case("feels_like", "")]
+    fn field_renders_bare_value(#[case] field: &str, #[case] expected: &str) {
+        assert_eq!(render_field(&report("Sunny"), field), expected);
+    }
+
+    #[test]
+    fn hot_temperature_is_red() {
+        assert_eq!(
+            temperature(30.0, 0, true, TemperatureUnit::Celsius),
+            "30°C".red().to_string()
+        );
+    }
+
+    #[test]
+    fn compact_is_never_colored() {
+        assert!(
+            !render(
+                &report("Sunny"),
+                Format::Compact,
+                &RenderOptions {
+                    color: true,
+                    ..RenderOptions::default()
+                }
+            )
+            .contains('\x1b')
+        );
+    }
+
+    #[rstest]
+    #[case(ColorChoice::Auto, true, false, true)]
+    #[case(ColorChoice::Auto, false, false, false)]
+    #[case(ColorChoice::Auto, true, true, false)]
+    #[case(ColorChoice::Always, false, true, true)]
+    #[case(ColorChoice::Never, true, false, false)]
+    fn color_choice_resolution(
+        #[case] choice: ColorChoice,
+        #[case] is_terminal: bool,
+        #[case] no_color_env: bool,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(choice.resolve(is_terminal, no_color_env), expected);
+    }
+
+    #[test]
+    fn text_has_no_color_codes_under_never_or_non_terminal() {
+        for color in [
+            ColorChoice::Never.resolve(true, false),
+            ColorChoice::Auto.resolve(false, false),
+        ] {
+            assert!(
+                !render(
+                    &report("Sunny"),
+                    Format::Text,
+                    &RenderOptions {
+                        color,
+                        ..RenderOptions::default()
+                    }
+                )
+                .contains('\x1b')
+            );
+        }
+    }
+
+    #[test]
+    fn text_renders_alerts_when_requested() {
+        let report = WeatherReport {
+            alerts: Some(vec![
+                WeatherAlert {
+                    headline: "Flood Warning".into(),
+                    severity: Some("Moderate".into()),
+                    areas: vec!["Kyiv".into(), "Brovary".into()],
+                },
+                WeatherAlert {
+                    headline: "Wind Advisory".into(),
+                    severity: None,
+                    areas: vec![],
+                },
+            ]),
+            ..report("Sunny")
+        };
+
+        assert!(
+            render(&report, Format::Text, &RenderOptions::default()).ends_with(
+                "\nAlert: Flood Warning (Moderate)\n  Areas: Kyiv, Brovary\nAlert: Wind Advisory"
+            )
+        );
+        assert_eq!(
+            render(&report, Format::Compact, &RenderOptions::default()),
+            "Kyiv 2024-11-29 ☀ 12°/3°C ⚠ 2 alerts"
+        );
+    }
+
+    #[rstest]
+    #[case::none_active(Some(vec![]), true)]
+    #[case::not_requested(None, false)]
+    fn text_says_no_active_alerts_only_when_requested(
+        #[case] alerts: Option<Vec<WeatherAlert>>,
+        #[case] expected: bool,
+    ) {
+        let report = WeatherReport {
+            alerts,
+            ..report("Sunny")
+        };
+
+        assert_eq!(
+            render(&report, Format::Text, &RenderOptions::default())
+                .ends_with("\nNo active alerts."),
+            expected
+        );
+    }
+
+    #[test]
+    fn text_renders_aqi_when_present() {
+        let report = WeatherReport {
+            air_quality: Some(AirQuality {
+                index: Some(42.0),
+                category: AqiCategory::Good,
+                pm2_5: None,
+                pm10: None,
+            }),
+            ..report("Sunny")
+        };
+
+        assert!(
+            render(&report, Format::Text, &RenderOptions::default()).ends_with("\nAQI: 42 (Good)"),
+            "unexpected output: {}",
+            render(&report, Format::Text, &RenderOptions::default())
+        );
+    }
+
+    #[test]
+    fn aqi_summary_without_index_uses_category_and_pm2_5() {
+        let air_quality = AirQuality {
+            index: None,
+            category: AqiCategory::Moderate,
+            pm2_5: Some(18.25),
+            pm10: Some(30.0),
+        };
+
+        assert_eq!(aqi_summary(&air_quality), "Moderate, PM2.5 18.2 μg/m³");
+    }
+
+    #[rstest]
+    #[case(0.0, "low")]
+    #[case(2.9, "low")]
+    #[case(3.0, "moderate")]
+    #[case(5.9, "moderate")]
+    #[case(6.0, "high")]
+    #[case(7.9, "high")]
+    #[case(8.0, "very high")]
+    #[case(10.9, "very high")]
+    #[case(11.0, "extreme")]
+    fn uv_label_boundaries(#[case] uv_index: f64, #[case] label: &str) {
+        assert_eq!(uv_label(uv_index), label);
+    }
+
+    #[test]
+    fn compact_renders_single_line_with_emoji() {
+        assert_eq!(
+            render(&report("Sunny"), Format::Compact, &RenderOptions::default()),
+            "Kyiv 2024-11-29 ☀ 12°/3°C"
+        );
+    }
+
+    #[test]
+    fn compact_prefers_most_severe_condition() {
+        assert_eq!(
+            render(
+                &report("Day: Thunderstorms, Night: Clear"),
+                Format::Compact,
+                &RenderOptions::default()
+            ),
+            "Kyiv 2024-11-29 ⛈ 12°/3°C"
+        );
+    }
+
+    #[test]
+    fn compact_uses_normalized_condition_for_unknown_description() {
+        let snowy = WeatherReport {
+            condition: WeatherCondition::Snow,
+            ..report("Сніг")
+        };
+
+        assert_eq!(
+            render(&snowy, Format::Compact, &RenderOptions::default()),
+            "Kyiv 2024-11-29 ❄ 12°/3°C"
+        );
+    }
+
+    #[test]
+    fn compact_prefers_user_icon_and_falls_back_to_builtin() {
+        let icons = Icons::new(HashMap::from([(WeatherCondition::Rain, "☔".to_string())]));
+        let rainy = WeatherReport {
+            condition: WeatherCondition::Rain,
+            ..report("Moderate rain")
+        };
+
+        assert_eq!(
+            render(
+                &rainy,
+                Format::Compact,
+                &RenderOptions {
+                    icons: icons.clone(),
+                    ..RenderOptions::default()
+                }
+            ),
+            "Kyiv 2024-11-29 ☔ 12°/3°C"
+        );
+        assert_eq!(
+            render(
+                &report("Sunny"),
+                Format::Compact,
+                &RenderOptions {
+                    icons: icons.clone(),
+                    ..RenderOptions::default()
+                }
+            ),
+            "Kyiv 2024-11-29 ☀ 12°/3°C"
+        );
+    }
+
+    #[test]
+    fn compact_renders_feels_like_when_present() {
+        let report = WeatherReport {
+            feels_like: Some(9.6),
+            ..report("Sunny")
+        };
+
+        assert_eq!(
+            render(&report, Format::Compact, &RenderOptions::default()),
+            "Kyiv 2024-11-29 ☀ 12°/3°C (feels 10°C)"
+        );
+    }
+
+    #[test]
+    fn compact_omits_emoji_when_no_keyword_matches_and_condition_is_unknown() {
+        let report = WeatherReport {
+            condition: WeatherCondition::Unknown,
+            ..report("Windy")
+        };
+
+        assert_eq!(
+            render(&report, Format::Compact, &RenderOptions::default()),
+            "Kyiv 2024-11-29 12°/3°C"
+        );
+    }
+
+    #[test]
+    fn text_and_compact_convert_to_fahrenheit_on_display() {
+        let report = WeatherReport {
+            max_temperature: 100.0,
+            min_temperature: 0.0,
+            feels_like: Some(-40.0),
+            ..report("Sunny")
+        };
+
+        assert!(
+            render(
+                &report,
+                Format::Text,
+                &RenderOptions {
+                    units: TemperatureUnit::Fahrenheit,
+                    ..RenderOptions::default()
+                }
+            )
+            .contains("Temperature: max 212°F, min 32°F\nFeels like: -40°F")
+        );
+        assert!(
+            render(
+                &report,
+                Format::Compact,
+                &RenderOptions {
+                    units: TemperatureUnit::Fahrenheit,
+                    ..RenderOptions::default()
+                }
+            )
+            .ends_with("212°/32°F (feels -40°F)")
+        );
+    }
+
+    #[test]
+    fn json_both_units_has_consistent_celsius_and_fahrenheit() {
+        let report = WeatherReport {
+            feels_like: Some(-40.0),
+            ..report("Sunny")
+        };
+
+        let json: Value = serde_json::from_str(&render_json_both_units(&report, false)).unwrap();
+
+        assert_eq!(json["max_temperature_c"], 12.2);
+        assert!((json["max_temperature_f"].as_f64().unwrap() - 53.96).abs() < 1e-9);
+        assert_eq!(json["min_temperature_c"], 3.0);
+        assert!((json["min_temperature_f"].as_f64().unwrap() - 37.4).abs() < 1e-9);
+        assert_eq!(json["feels_like_c"], -40.0);
+        assert_eq!(json["feels_like_f"], -40.0);
+        assert!(json["avg_temperature_c"].is_null());
+        assert!(json["avg_temperature_f"].is_null());
+        assert!(json.get("max_temperature").is_none(), "{json}");
+    }
+
+    #[test]
+    fn json_both_units_converts_current_conditions() {
+        let json: Value = serde_json::from_str(&render_json_both_units(&current(), false)).unwrap();
+
+        assert_eq!(json["temperature_c"], 7.4);
+        assert!((json["temperature_f"].as_f64().unwrap() - 45.32).abs() < 1e-9);
+        assert_eq!(json["feels_like_c"], 4.6);
+        assert!(json.get("temperature").is_none(), "{json}");
+    }
+
+    #[test]
+    fn json_temperatures_match_struct_fields() {
+        let mut fields = Vec::new();
+        for json in [
+            serde_json::to_value(report("Sunny")).unwrap(),
+            serde_json::to_value(current()).unwrap(),
+        ] {
+            let Value::Object(object) = json else {
+                panic!("expected object: {json}");
+            };
+            fields.extend(object.into_iter().map(|(name, _)| name));
+        }
+
+        for name in JSON_TEMPERATURES {
+            assert!(fields.iter().any(|field| field == name), "no field {name}");
+        }
+        for field in fields {
+            if field.ends_with("temperature") || field == "feels_like" {
+                assert!(
+                    JSON_TEMPERATURES.contains(&field.as_str()),
+                    "{field} missing"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn json_keeps_celsius_when_displaying_fahrenheit() {
+        let json = render(
+            &report("Sunny"),
+            Format::Json,
+            &RenderOptions {
+                units: TemperatureUnit::Fahrenheit,
+                ..RenderOptions::default()
+            },
+        );
+
+        assert!(json.contains(r#""max_temperature":12.2"#), "{json}");
+    }
+
+    #[test]
+    fn fahrenheit_colors_by_celsius_thresholds() {
+        assert_eq!(
+            temperature(30.0, 0, true, TemperatureUnit::Fahrenheit),
+            "86°F".red().to_string()
+        );
+    }
+
+    fn current() -> CurrentConditions {
+        CurrentConditions {
+            provider: Provider::WeatherApi,
+            location: "Kyiv, Ukraine".into(),
+            observed_at: "2024-11-29T10:15:00Z".parse().unwrap(),
+            condition: WeatherCondition::Clear,
+            description: "Sunny".into(),
+            temperature: 7.4,
+            feels_like: Some(4.6),
+            humidity: Some(64.0),
+            wind_kph: Some(11.9),
+            wind_direction: Some("NW".into()),
+        }
+    }
+
+    #[test]
+    fn current_text_renders_observation_time_and_temperature() {
+        assert_eq!(
+            render_current(&current(), Format::Text, &RenderOptions::default()),
+            "Location: Kyiv, Ukraine\n\
+             Observed: 2024-11-29 10:15 UTC\n\
+             Provider: WeatherApi\n\
+             Conditions: Sunny\n\
+             Temperature: 7°C\n\
+             Feels like: 5°C\n\
+             Wind: 12 kph NW\n\
+             Humidity: 64%"
+        );
+    }
+
+    #[test]
+    fn current_compact_renders_single_line() {
+        assert_eq!(
+            render_current(
+                &current(),
+                Format::Compact,
+                &RenderOptions {
+                    precision: 1,
+                    ..RenderOptions::default()
+                }
+            ),
+            "Kyiv now ☀ 7.4°C (feels 4.6°C)"
+        );
+    }
+
+    #[rstest]
+    #[case("Partly cloudy", Some("⛅"))]
+    #[case("Patchy light drizzle", Some("🌦"))]
+    #[case("Moderate rain", Some("🌧"))]
+    #[case("Heavy snow", Some("❄"))]
+    #[case("Freezing fog", Some("🌫"))]
+    #[case("Windy", None)]
+    fn condition_emoji_matches_keywords(#[case] description: &str, #[case] emoji: Option<&str>) {
+        assert_eq!(condition_emoji(description), emoji);
+    }
+}
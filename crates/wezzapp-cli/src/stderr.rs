@@ -0,0 +1,41 @@
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// Output held back by `hold`, `None` while writing straight to stderr.
+static HELD: Mutex<Option<Vec<u8>>> = Mutex::new(None);
+
+/// Stderr for logs and warnings, held back while a full-screen UI owns the terminal.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Stderr;
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+        match held.as_mut() {
+            Some(held) => {
+                held.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            None => io::stderr().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+/// Keep `Stderr` output in memory until `release`, e.g. while on the alternate screen.
+pub fn hold() {
+    HELD.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(Vec::new);
+}
+
+/// Print what was held back and write straight to stderr again.
+pub fn release() {
+    let held = HELD.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(held) = held {
+        let _ = io::stderr().write_all(&held);
+    }
+}
@@ -1,26 +1,76 @@
+use crate::encryption::ConfigCipher;
+use crate::paths::Paths;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
-use tracing::debug;
-use wezzapp_core::credentials::{Credentials, CredentialsStore};
+use std::mem;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+use wezzapp_core::apis::redact;
+use wezzapp_core::condition::WeatherCondition;
+use wezzapp_core::credentials::{
+    Credentials, CredentialsStore, DEFAULT_PROFILE, ServiceStore, SettingsStore, UsageStore,
+};
 use wezzapp_core::provider::Provider;
 
-/// On-disk configuration format for credentials & default provider.
+/// On-disk configuration format: named profiles with credentials & default provider.
 ///
 /// Example TOML:
 /// ```toml
-/// default = "weatherapi"
+/// language = "uk"
 ///
-/// [providers.accuweather.accuweather]
-/// api_key = "abc"
+/// [profiles.default]
+/// default = "weatherapi"
 ///
-/// [providers.weatherapi.weatherapi]
+/// [profiles.default.providers.weatherapi.weatherapi]
 /// api_key = "xyz"
+///
+/// [profiles.work]
+/// default = "accuweather"
+///
+/// [profiles.work.providers.accuweather.accuweather]
+/// api_key = "abc"
+/// base_url = "https://eu.example.com/"  # optional
 /// ```
 #[derive(Default, Serialize, Deserialize)]
 struct Config {
+    /// Language code for provider condition text, e.g. "uk".
+    #[serde(default)]
+    language: Option<String>,
+
+    /// Map from profile name to its settings.
+    #[serde(default)]
+    profiles: HashMap<String, Profile>,
+
+    /// Saved location aliases, e.g. `home = "Kyiv, Ukraine"`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    locations: HashMap<String, String>,
+
+    /// Per-provider forecast length overrides for paid plans, e.g. `accuweather = 10`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    max_forecast_days: HashMap<Provider, u32>,
+
+    /// Condition icons for compact output, e.g. `rain = "☔"`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    icons: HashMap<WeatherCondition, String>,
+
+    /// Calls made this month to providers without quota headers.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    usage: HashMap<Provider, Usage>,
+
+    /// Default provider in the flat pre-profiles layout, see `migrate`.
+    #[serde(default, skip_serializing)]
+    default: Option<Provider>,
+
+    /// Credentials in the flat pre-profiles layout, see `migrate`.
+    #[serde(default, skip_serializing)]
+    providers: HashMap<Provider, Credentials>,
+}
+
+/// Settings of a single named profile.
+#[derive(Default, Serialize, Deserialize)]
+struct Profile {
     /// Default provider (string encoded via `Provider` serde rename).
     #[serde(default)]
     default: Option<Provider>,
@@ -30,39 +80,139 @@ struct Config {
     providers: HashMap<Provider, Credentials>,
 }
 
-/// TOML-file-based implementation of `CredentialsStore`.
+/// Call counter of a single provider.
+#[derive(Default, Serialize, Deserialize)]
+struct Usage {
+    /// Period the counter belongs to, e.g. "2024-11".
+    period: String,
+    calls: u32,
+}
+
+impl Config {
+    /// Move settings from the flat pre-profiles layout into the `default` profile.
+    ///
+    /// Values already present in the `default` profile win.
+    fn migrate(&mut self) {
+        if self.default.is_none() && self.providers.is_empty() {
+            return;
+        }
+        debug!("Migrating flat config into `{DEFAULT_PROFILE}` profile");
+
+        let legacy_default = self.default.take();
+        let legacy_providers = std::mem::take(&mut self.providers);
+        let profile = self.profile_mut(DEFAULT_PROFILE);
+
+        profile.default = profile.default.or(legacy_default);
+        for (provider, credentials) in legacy_providers {
+            profile.providers.entry(provider).or_insert(credentials);
+        }
+    }
+
+    fn profile(&self, profile: &str) -> Option<&Profile> {
+        self.profiles.get(profile)
+    }
+
+    fn profile_mut(&mut self, profile: &str) -> &mut Profile {
+        self.profiles.entry(profile.to_string()).or_default()
+    }
+}
+
+/// How the serialized TOML config is stored on disk.
+pub trait ConfigCodec {
+    /// Turn file contents into TOML bytes.
+    fn decode(&mut self, contents: &[u8]) -> Result<Vec<u8>>;
+
+    /// Turn TOML bytes into file contents.
+    fn encode(&mut self, config: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Config stored as is.
+pub struct PlainText;
+
+impl ConfigCodec for PlainText {
+    fn decode(&mut self, contents: &[u8]) -> Result<Vec<u8>> {
+        Ok(contents.to_vec())
+    }
+
+    fn encode(&mut self, config: &[u8]) -> Result<Vec<u8>> {
+        Ok(config.to_vec())
+    }
+}
+
+/// File-based implementation of `CredentialsStore`, TOML encoded with `C` on disk.
 ///
-/// Stored in:
-///   `<home>/.wezzapp/credentials.toml`
-pub struct TomlFileCredentialsStore {
-    path: std::path::PathBuf,
+/// Settings are saved as soon as they change. Recorded calls are only counted in memory and
+/// added to the counters in the file once the store is dropped, so a run doesn't rewrite
+/// (and re-encrypt) the file per call.
+pub struct FileCredentialsStore<C: ConfigCodec> {
+    path: PathBuf,
     config: Config,
+    codec: C,
+    /// Period and number of calls recorded per provider since the file was last saved.
+    unsaved_calls: HashMap<Provider, Usage>,
 }
 
+/// Plain TOML store, in `credentials.toml` in the config directory, see `Paths`.
+pub type TomlFileCredentialsStore = FileCredentialsStore<PlainText>;
+
+/// Passphrase-encrypted TOML store, in `credentials.enc` in the config directory.
+pub type EncryptedFileCredentialsStore = FileCredentialsStore<ConfigCipher>;
+
 impl TomlFileCredentialsStore {
-    pub fn new() -> Result<Self> {
+    /// Open the store in the config directory.
+    ///
+    /// With `reset_config`, a file that can't be parsed is moved to `credentials.toml.bak`
+    /// and the store starts empty instead of failing.
+    pub fn new(paths: &Paths, reset_config: bool) -> Result<Self> {
         debug!("Creating new TomlFileCredentialsStore");
-        let dirs =
-            directories::UserDirs::new().context("failed to determine user home directory")?;
-        let home = dirs.home_dir();
-        let dir = home.join(".wezzapp");
-        let path = dir.join("credentials.toml");
+        let path = paths.credentials_file();
         debug!("Using credentials file at {}", path.display());
 
-        Self::new_with_path(&path)
+        Self::new_with_path(&path, reset_config)
     }
 
-    fn new_with_path(path: &Path) -> Result<Self> {
-        debug!(
-            "Creating new TomlFileCredentialsStore with path {}",
-            path.display()
-        );
+    pub(crate) fn new_with_path(path: &Path, reset_config: bool) -> Result<Self> {
+        Self::open(path, PlainText, reset_config)
+    }
+}
+
+impl EncryptedFileCredentialsStore {
+    /// Open the encrypted store in the config directory, decrypting it with `passphrase`.
+    ///
+    /// A wrong passphrase is an error, even with `reset_config`.
+    pub fn new(paths: &Paths, passphrase: String, reset_config: bool) -> Result<Self> {
+        let path = paths.encrypted_credentials_file();
+        debug!("Using encrypted credentials file at {}", path.display());
+
+        Self::open(&path, ConfigCipher::new(passphrase), reset_config)
+    }
+}
+
+impl<C: ConfigCodec> FileCredentialsStore<C> {
+    fn open(path: &Path, mut codec: C, reset_config: bool) -> Result<Self> {
+        debug!("Opening credentials store with path {}", path.display());
         let config = if path.exists() {
-            let contents = fs::read_to_string(path)
-                .context(format!("failed to read config file {}", path.display()))?;
+            let contents =
+                fs::read(path).context(format!("failed to read config file {}", path.display()))?;
             debug!("Loaded credentials from {}", path.display());
+            let contents = codec
+                .decode(&contents)
+                .context(format!("failed to read config file {}", path.display()))?;
 
-            toml::from_str(&contents).context("failed to parse credentials TOML")?
+            match Self::parse(&contents) {
+                Ok(config) => config,
+                Err(err) if reset_config => {
+                    debug!("Resetting broken config: {err:#}");
+                    Self::backup(path)?;
+                    Config::default()
+                }
+                Err(err) => {
+                    return Err(err.context(format!(
+                        "config file {} is broken, re-run with `--reset-config` to back it up and start fresh",
+                        path.display()
+                    )));
+                }
+            }
         } else {
             if let Some(parent) = path.parent() {
                 fs::create_dir_all(parent)
@@ -79,15 +229,42 @@ impl TomlFileCredentialsStore {
         Ok(Self {
             path: path.to_path_buf(),
             config,
+            codec,
+            unsaved_calls: HashMap::new(),
         })
     }
 
-    fn save_file(&self) -> Result<()> {
+    fn parse(contents: &[u8]) -> Result<Config> {
+        let contents = std::str::from_utf8(contents).context("config file is not valid UTF-8")?;
+
+        let mut config: Config =
+            toml::from_str(contents).context("failed to parse credentials TOML")?;
+        config.migrate();
+
+        Ok(config)
+    }
+
+    /// Move broken config file aside to `<name>.bak`, replacing an older backup.
+    fn backup(path: &Path) -> Result<()> {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".bak");
+        let backup = path.with_file_name(name);
+        fs::rename(path, &backup).context(format!(
+            "failed to back up config file to {}",
+            backup.display()
+        ))?;
+        eprintln!("Broken config backed up to {}", backup.display());
+
+        Ok(())
+    }
+
+    fn save_file(&mut self) -> Result<()> {
         debug!("Saving credentials to {}", self.path.display());
         let tmp = self.path.with_extension("tmp");
 
         let data =
             toml::to_string_pretty(&self.config).context("failed to serialize credentials TOML")?;
+        let data = self.codec.encode(data.as_bytes())?;
 
         fs::write(&tmp, data).context(format!("failed to write config file {}", tmp.display()))?;
         debug!("Wrote credentials to {}", tmp.display());
@@ -97,32 +274,251 @@ impl TomlFileCredentialsStore {
             tmp.display()
         ))?;
         debug!("Renamed tmp file to {}", self.path.display());
+        self.unsaved_calls.clear();
 
         Ok(())
     }
+
+    /// Add calls recorded since the last save to the counters in the file, re-reading it first
+    /// so settings changed meanwhile, e.g. by `configure` in another terminal, are kept.
+    fn save_usage(&mut self) -> Result<()> {
+        let mut config = if self.path.exists() {
+            let contents = fs::read(&self.path).context(format!(
+                "failed to read config file {}",
+                self.path.display()
+            ))?;
+            Self::parse(&self.codec.decode(&contents)?)?
+        } else {
+            Config::default()
+        };
+
+        for (provider, recorded) in mem::take(&mut self.unsaved_calls) {
+            let usage = config.usage.entry(provider).or_default();
+            if usage.period != recorded.period {
+                usage.period = recorded.period;
+                usage.calls = 0;
+            }
+            usage.calls += recorded.calls;
+        }
+        self.config = config;
+
+        self.save_file()
+    }
+}
+
+impl<C: ConfigCodec> Drop for FileCredentialsStore<C> {
+    /// Save calls recorded since the last save.
+    fn drop(&mut self) {
+        if self.unsaved_calls.is_empty() {
+            return;
+        }
+        if let Err(err) = self.save_usage() {
+            warn!("Failed to save provider call counters: {err:#}");
+        }
+    }
 }
 
-impl CredentialsStore for TomlFileCredentialsStore {
-    fn set_credentials(&mut self, provider: Provider, credentials: &Credentials) -> Result<()> {
-        debug!("Setting credentials for provider {:?}", provider);
-        self.config.providers.insert(provider, credentials.clone());
+/// Config store of the CLI: what the core needs, plus settings only the CLI displays.
+pub trait ConfigStore: ServiceStore {
+    /// User-chosen icons of conditions in compact output, replacing the built-in ones.
+    fn get_icons(&self) -> Result<HashMap<WeatherCondition, String>>;
+
+    /// Replace the icon overrides.
+    fn set_icons(&mut self, icons: &HashMap<WeatherCondition, String>) -> Result<()>;
+}
+
+impl<C: ConfigCodec> ConfigStore for FileCredentialsStore<C> {
+    fn get_icons(&self) -> Result<HashMap<WeatherCondition, String>> {
+        Ok(self.config.icons.clone())
+    }
+
+    fn set_icons(&mut self, icons: &HashMap<WeatherCondition, String>) -> Result<()> {
+        debug!("Setting {} icons", icons.len());
+        self.config.icons = icons.clone();
+        self.save_file()
+    }
+}
+
+impl<S: ConfigStore + ?Sized> ConfigStore for Box<S> {
+    fn get_icons(&self) -> Result<HashMap<WeatherCondition, String>> {
+        (**self).get_icons()
+    }
+
+    fn set_icons(&mut self, icons: &HashMap<WeatherCondition, String>) -> Result<()> {
+        (**self).set_icons(icons)
+    }
+}
+
+impl<S: ConfigStore + ?Sized> ConfigStore for &mut S {
+    fn get_icons(&self) -> Result<HashMap<WeatherCondition, String>> {
+        (**self).get_icons()
+    }
+
+    fn set_icons(&mut self, icons: &HashMap<WeatherCondition, String>) -> Result<()> {
+        (**self).set_icons(icons)
+    }
+}
+
+/// The shared in-memory test store has no icons and accepts none.
+#[cfg(test)]
+impl ConfigStore for wezzapp_core::testing::InMemoryStore {
+    fn get_icons(&self) -> Result<HashMap<WeatherCondition, String>> {
+        Ok(HashMap::new())
+    }
+
+    fn set_icons(&mut self, icons: &HashMap<WeatherCondition, String>) -> Result<()> {
+        anyhow::ensure!(icons.is_empty(), "in-memory store keeps no icons");
+        Ok(())
+    }
+}
+
+impl<C: ConfigCodec> CredentialsStore for FileCredentialsStore<C> {
+    fn set_credentials(
+        &mut self,
+        profile: &str,
+        provider: Provider,
+        credentials: &Credentials,
+    ) -> Result<()> {
+        debug!(
+            "Setting credentials for provider {:?} in profile {:?}",
+            provider, profile
+        );
+        self.config
+            .profile_mut(profile)
+            .providers
+            .insert(provider, credentials.clone());
         self.save_file().context("failed to save credentials")
     }
 
-    fn get_credentials(&self, provider: Provider) -> Result<Option<Credentials>> {
-        debug!("Getting credentials for provider {:?}", provider);
-        Ok(self.config.providers.get(&provider).cloned())
+    fn get_credentials(&self, profile: &str, provider: Provider) -> Result<Option<Credentials>> {
+        debug!(
+            "Getting credentials for provider {:?} in profile {:?}",
+            provider, profile
+        );
+        Ok(self
+            .config
+            .profile(profile)
+            .and_then(|profile| profile.providers.get(&provider))
+            .cloned())
+    }
+
+    fn set_default_provider(&mut self, profile: &str, provider: Provider) -> Result<()> {
+        debug!(
+            "Setting default provider to {:?} in profile {:?}",
+            provider, profile
+        );
+        self.config.profile_mut(profile).default = Some(provider);
+        self.save_file()
+    }
+
+    fn get_default_provider(&self, profile: &str) -> Result<Option<Provider>> {
+        debug!("Getting default provider in profile {:?}", profile);
+        Ok(self
+            .config
+            .profile(profile)
+            .and_then(|profile| profile.default))
+    }
+
+    fn list_profiles(&self) -> Result<Vec<String>> {
+        let mut profiles: Vec<_> = self.config.profiles.keys().cloned().collect();
+        profiles.sort();
+
+        Ok(profiles)
+    }
+}
+
+impl<C: ConfigCodec> SettingsStore for FileCredentialsStore<C> {
+    fn set_language(&mut self, language: &str) -> Result<()> {
+        debug!("Setting language to {:?}", language);
+        self.config.language = Some(language.to_string());
+        self.save_file()
+    }
+
+    fn get_language(&self) -> Result<Option<String>> {
+        debug!("Getting language");
+        Ok(self.config.language.clone())
     }
 
-    fn set_default_provider(&mut self, provider: Provider) -> Result<()> {
-        debug!("Setting default provider to {:?}", provider);
-        self.config.default = Some(provider);
+    fn set_location_alias(&mut self, alias: &str, address: &str) -> Result<()> {
+        debug!(
+            "Saving location alias {:?} for {}",
+            alias,
+            redact::address(address)
+        );
+        self.config
+            .locations
+            .insert(alias.to_string(), address.to_string());
         self.save_file()
     }
 
-    fn get_default_provider(&self) -> Result<Option<Provider>> {
-        debug!("Getting default provider");
-        Ok(self.config.default)
+    fn get_location_alias(&self, alias: &str) -> Result<Option<String>> {
+        debug!("Getting location alias {:?}", alias);
+        Ok(self.config.locations.get(alias).cloned())
+    }
+
+    fn list_location_aliases(&self) -> Result<Vec<(String, String)>> {
+        let mut aliases: Vec<_> = self
+            .config
+            .locations
+            .iter()
+            .map(|(alias, address)| (alias.clone(), address.clone()))
+            .collect();
+        aliases.sort();
+
+        Ok(aliases)
+    }
+
+    fn get_max_forecast_days(&self, provider: Provider) -> Result<Option<u32>> {
+        debug!("Getting max forecast days for {:?}", provider);
+        Ok(self.config.max_forecast_days.get(&provider).copied())
+    }
+
+    fn set_max_forecast_days(&mut self, provider: Provider, days: u32) -> Result<()> {
+        debug!("Setting max forecast days for {:?} to {}", provider, days);
+        self.config.max_forecast_days.insert(provider, days);
+        self.save_file()
+    }
+}
+
+impl<C: ConfigCodec> UsageStore for FileCredentialsStore<C> {
+    fn record_call(&mut self, provider: Provider, period: &str) -> Result<u32> {
+        let usage = self.config.usage.entry(provider).or_default();
+        if usage.period != period {
+            debug!("Resetting {:?} call counter for {:?}", provider, period);
+            usage.period = period.to_string();
+            usage.calls = 0;
+        }
+        usage.calls += 1;
+        let calls = usage.calls;
+
+        let unsaved = self.unsaved_calls.entry(provider).or_default();
+        if unsaved.period != period {
+            unsaved.period = period.to_string();
+            unsaved.calls = 0;
+        }
+        unsaved.calls += 1;
+
+        Ok(calls)
+    }
+
+    fn get_usage(&self, provider: Provider) -> Result<Option<(String, u32)>> {
+        Ok(self
+            .config
+            .usage
+            .get(&provider)
+            .map(|usage| (usage.period.clone(), usage.calls)))
+    }
+
+    fn set_usage(&mut self, provider: Provider, period: &str, calls: u32) -> Result<()> {
+        debug!("Setting {:?} calls in {:?} to {}", provider, period, calls);
+        self.config.usage.insert(
+            provider,
+            Usage {
+                period: period.to_string(),
+                calls,
+            },
+        );
+        self.save_file()
     }
 }
 
@@ -143,8 +539,8 @@ mod tests {
             let tmpdir = tempfile::tempdir().expect("create temp dir");
             let path = tmpdir.path().join("credentials.toml");
 
-            let store =
-                TomlFileCredentialsStore::new_with_path(&path).expect("create file-based store");
+            let store = TomlFileCredentialsStore::new_with_path(&path, false)
+                .expect("create file-based store");
 
             StoreFixture {
                 _tmpdir: tmpdir,
@@ -154,7 +550,7 @@ mod tests {
 
         /// Create a second store reading from the same path to test persistence.
         fn reopen(&self) -> TomlFileCredentialsStore {
-            TomlFileCredentialsStore::new_with_path(&self.store.path)
+            TomlFileCredentialsStore::new_with_path(&self.store.path, false)
                 .expect("reopen file-based store")
         }
     }
@@ -164,12 +560,8 @@ mod tests {
         let fixture = StoreFixture::new();
 
         assert!(
-            fixture.store.config.providers.is_empty(),
-            "providers map should be empty"
-        );
-        assert!(
-            fixture.store.config.default.is_none(),
-            "default provider should be None"
+            fixture.store.config.profiles.is_empty(),
+            "profiles map should be empty"
         );
 
         assert!(
@@ -185,14 +577,21 @@ mod tests {
     )]
     #[case(
         Provider::AccuWeather,
-        Credentials::AccuWeather { api_key: "accu-key".into() }
+        Credentials::AccuWeather { api_key: "accu-key".into(), base_url: None }
+    )]
+    #[case(
+        Provider::AccuWeather,
+        Credentials::AccuWeather {
+            api_key: "accu-key".into(),
+            base_url: Some("https://eu.example.com/".into()),
+        }
     )]
     fn set_and_get_credentials_roundtrip(#[case] provider: Provider, #[case] creds: Credentials) {
         let mut fixture = StoreFixture::new();
 
         fixture
             .store
-            .set_credentials(provider, &creds)
+            .set_credentials(DEFAULT_PROFILE, provider, &creds)
             .expect("set_credentials");
 
         assert!(
@@ -202,7 +601,7 @@ mod tests {
 
         let loaded = fixture
             .store
-            .get_credentials(provider)
+            .get_credentials(DEFAULT_PROFILE, provider)
             .expect("get_credentials");
 
         assert!(
@@ -217,12 +616,12 @@ mod tests {
 
         fixture
             .store
-            .set_default_provider(Provider::AccuWeather)
+            .set_default_provider(DEFAULT_PROFILE, Provider::AccuWeather)
             .expect("set_default_provider");
 
         let default = fixture
             .store
-            .get_default_provider()
+            .get_default_provider(DEFAULT_PROFILE)
             .expect("get_default_provider");
 
         assert_eq!(
@@ -233,12 +632,12 @@ mod tests {
 
         fixture
             .store
-            .set_default_provider(Provider::WeatherApi)
+            .set_default_provider(DEFAULT_PROFILE, Provider::WeatherApi)
             .expect("set_default_provider");
 
         let default2 = fixture
             .store
-            .get_default_provider()
+            .get_default_provider(DEFAULT_PROFILE)
             .expect("get_default_provider");
 
         assert_eq!(
@@ -248,6 +647,113 @@ mod tests {
         );
     }
 
+    #[test]
+    fn language_persists_across_reloads() {
+        let mut fixture = StoreFixture::new();
+
+        assert_eq!(fixture.store.get_language().expect("get_language"), None);
+
+        fixture.store.set_language("uk").expect("set_language");
+
+        let store2 = fixture.reopen();
+        assert_eq!(
+            store2.get_language().expect("get_language").as_deref(),
+            Some("uk"),
+            "language should survive reload"
+        );
+    }
+
+    #[test]
+    fn location_aliases_persist_across_reloads() {
+        let mut fixture = StoreFixture::new();
+
+        fixture
+            .store
+            .set_location_alias("home", "Kyiv, Ukraine")
+            .expect("set_location_alias");
+
+        let store2 = fixture.reopen();
+        assert_eq!(
+            store2
+                .get_location_alias("home")
+                .expect("get_location_alias")
+                .as_deref(),
+            Some("Kyiv, Ukraine")
+        );
+        assert_eq!(
+            store2
+                .get_location_alias("work")
+                .expect("get_location_alias"),
+            None
+        );
+    }
+
+    #[test]
+    fn call_counter_persists_and_resets_on_new_period() {
+        let fixture = StoreFixture::new();
+
+        let record = |store: &mut TomlFileCredentialsStore, period| {
+            store
+                .record_call(Provider::WeatherApi, period)
+                .expect("record_call")
+        };
+
+        let mut store = fixture.reopen();
+        assert_eq!(record(&mut store, "2024-11"), 1);
+        assert_eq!(record(&mut store, "2024-11"), 2);
+        assert_eq!(
+            fixture
+                .reopen()
+                .get_usage(Provider::WeatherApi)
+                .expect("get_usage"),
+            None,
+            "calls are saved when the store is dropped"
+        );
+        drop(store);
+
+        let mut store2 = fixture.reopen();
+        assert_eq!(
+            record(&mut store2, "2024-11"),
+            3,
+            "counter should survive reload"
+        );
+        assert_eq!(record(&mut store2, "2024-12"), 1, "counter should reset");
+        assert_eq!(
+            store2
+                .record_call(Provider::AccuWeather, "2024-12")
+                .expect("record_call"),
+            1,
+            "counters are per provider"
+        );
+    }
+
+    #[test]
+    fn saving_calls_keeps_changes_and_calls_of_other_stores() {
+        let fixture = StoreFixture::new();
+        let mut store = fixture.reopen();
+        let mut other = fixture.reopen();
+
+        store
+            .record_call(Provider::WeatherApi, "2024-11")
+            .expect("record_call");
+        other.set_language("uk").expect("set_language");
+        other
+            .record_call(Provider::WeatherApi, "2024-11")
+            .expect("record_call");
+        drop(other);
+        drop(store);
+
+        let reopened = fixture.reopen();
+        assert_eq!(
+            reopened.get_language().expect("get_language").as_deref(),
+            Some("uk")
+        );
+        assert_eq!(
+            reopened.get_usage(Provider::WeatherApi).expect("get_usage"),
+            Some(("2024-11".to_string(), 2))
+        );
+    }
+
     #[test]
     fn credentials_persist_across_reloads() {
         let mut fixture = StoreFixture::new();
@@ -258,19 +764,21 @@ mod tests {
 
         fixture
             .store
-            .set_credentials(Provider::WeatherApi, &creds)
+            .set_credentials(DEFAULT_PROFILE, Provider::WeatherApi, &creds)
             .expect("set_credentials");
         fixture
             .store
-            .set_default_provider(Provider::WeatherApi)
+            .set_default_provider(DEFAULT_PROFILE, Provider::WeatherApi)
             .expect("set_default_provider");
 
         let store2 = fixture.reopen();
 
         let loaded_creds = store2
-            .get_credentials(Provider::WeatherApi)
+            .get_credentials(DEFAULT_PROFILE, Provider::WeatherApi)
             .expect("get_credentials");
-        let default_provider = store2.get_default_provider().expect("get_default_provider");
+        let default_provider = store2
+            .get_default_provider(DEFAULT_PROFILE)
+            .expect("get_default_provider");
 
         assert!(
             Some(creds) == loaded_creds,
@@ -282,4 +790,306 @@ mod tests {
             "default credentials should survive reload"
         );
     }
+
+    #[test]
+    fn profiles_persist_independently_across_reloads() {
+        let mut fixture = StoreFixture::new();
+
+        let personal = Credentials::WeatherApi {
+            api_key: "personal-key".into(),
+        };
+        let work = Credentials::WeatherApi {
+            api_key: "work-key".into(),
+        };
+
+        fixture
+            .store
+            .set_credentials(DEFAULT_PROFILE, Provider::WeatherApi, &personal)
+            .expect("set_credentials");
+        fixture
+            .store
+            .set_credentials("work", Provider::WeatherApi, &work)
+            .expect("set_credentials");
+
+        let store2 = fixture.reopen();
+
+        assert!(
+            Some(personal)
+                == store2
+                    .get_credentials(DEFAULT_PROFILE, Provider::WeatherApi)
+                    .expect("get_credentials"),
+            "default profile credentials should survive reload"
+        );
+        assert!(
+            Some(work)
+                == store2
+                    .get_credentials("work", Provider::WeatherApi)
+                    .expect("get_credentials"),
+            "work profile credentials should survive reload"
+        );
+        assert!(
+            store2
+                .get_credentials("work", Provider::AccuWeather)
+                .expect("get_credentials")
+                .is_none(),
+            "profiles should not share credentials"
+        );
+    }
+
+    #[test]
+    fn unknown_profile_has_no_credentials() {
+        let fixture = StoreFixture::new();
+
+        assert!(
+            fixture
+                .store
+                .get_credentials("missing", Provider::WeatherApi)
+                .expect("get_credentials")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn default_provider_is_per_profile() {
+        let mut fixture = StoreFixture::new();
+
+        fixture
+            .store
+            .set_default_provider(DEFAULT_PROFILE, Provider::WeatherApi)
+            .expect("set_default_provider");
+        fixture
+            .store
+            .set_default_provider("work", Provider::AccuWeather)
+            .expect("set_default_provider");
+
+        let store2 = fixture.reopen();
+
+        assert_eq!(
+            store2
+                .get_default_provider(DEFAULT_PROFILE)
+                .expect("get_default_provider"),
+            Some(Provider::WeatherApi)
+        );
+        assert_eq!(
+            store2
+                .get_default_provider("work")
+                .expect("get_default_provider"),
+            Some(Provider::AccuWeather)
+        );
+        assert_eq!(
+            store2
+                .get_default_provider("missing")
+                .expect("get_default_provider"),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case::invalid_toml(b"default = [weatherapi".as_slice())]
+    #[case::non_utf8(b"language = \"\xff\xfe\"".as_slice())]
+    fn broken_config_fails_without_reset(#[case] contents: &[u8]) {
+        let fixture = StoreFixture::new();
+        fs::write(&fixture.store.path, contents).expect("write broken config");
+
+        let err = TomlFileCredentialsStore::new_with_path(&fixture.store.path, false)
+            .err()
+            .expect("broken config should fail");
+
+        assert!(format!("{err:#}").contains("--reset-config"), "{err:#}");
+        assert_eq!(fs::read(&fixture.store.path).unwrap(), contents);
+    }
+
+    #[rstest]
+    #[case::invalid_toml(b"default = [weatherapi".as_slice())]
+    #[case::non_utf8(b"language = \"\xff\xfe\"".as_slice())]
+    fn reset_backs_up_broken_config_and_starts_empty(#[case] contents: &[u8]) {
+        let fixture = StoreFixture::new();
+        fs::write(&fixture.store.path, contents).expect("write broken config");
+
+        let mut store = TomlFileCredentialsStore::new_with_path(&fixture.store.path, true)
+            .expect("reset broken config");
+
+        let backup = fixture.store.path.with_file_name("credentials.toml.bak");
+        assert_eq!(fs::read(&backup).expect("read backup"), contents);
+        assert!(store.config.profiles.is_empty());
+        assert_eq!(store.get_language().expect("get_language"), None);
+
+        // Store is usable after reset.
+        store
+            .set_credentials(
+                DEFAULT_PROFILE,
+                Provider::WeatherApi,
+                &Credentials::WeatherApi {
+                    api_key: "fresh".into(),
+                },
+            )
+            .expect("set_credentials");
+        assert!(
+            fixture
+                .reopen()
+                .get_credentials(DEFAULT_PROFILE, Provider::WeatherApi)
+                .expect("get_credentials")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn migrates_flat_config_into_default_profile() {
+        let mut fixture = StoreFixture::new();
+        fs::write(
+            &fixture.store.path,
+            "default = \"weatherapi\"\nlanguage = \"uk\"\n\n\
+             [providers.weatherapi.weatherapi]\napi_key = \"legacy\"\n",
+        )
+        .expect("write legacy config");
+
+        fixture.store = fixture.reopen();
+
+        assert!(
+            Some(Credentials::WeatherApi {
+                api_key: "legacy".into()
+            }) == fixture
+                .store
+                .get_credentials(DEFAULT_PROFILE, Provider::WeatherApi)
+                .expect("get_credentials"),
+            "legacy credentials should belong to default profile"
+        );
+        assert_eq!(
+            fixture
+                .store
+                .get_default_provider(DEFAULT_PROFILE)
+                .expect("get_default_provider"),
+            Some(Provider::WeatherApi)
+        );
+        assert_eq!(
+            fixture
+                .store
+                .get_language()
+                .expect("get_language")
+                .as_deref(),
+            Some("uk")
+        );
+
+        // Next save rewrites the file in the profiles layout.
+        fixture.store.set_language("en").expect("set_language");
+        let contents = fs::read_to_string(&fixture.store.path).expect("read config");
+        let config: toml::Table = toml::from_str(&contents).expect("parse config");
+        assert!(!config.contains_key("providers"), "unexpected: {contents}");
+        assert!(!config.contains_key("default"), "unexpected: {contents}");
+        assert!(
+            Some(Credentials::WeatherApi {
+                api_key: "legacy".into()
+            }) == fixture
+                .reopen()
+                .get_credentials(DEFAULT_PROFILE, Provider::WeatherApi)
+                .expect("get_credentials"),
+            "migrated credentials should survive reload"
+        );
+    }
+
+    #[test]
+    fn accuweather_credentials_without_base_url_stay_compatible() {
+        let mut fixture = StoreFixture::new();
+        fs::write(
+            &fixture.store.path,
+            "[profiles.default.providers.accuweather.accuweather]\napi_key = \"abc\"\n",
+        )
+        .expect("write config");
+
+        fixture.store = fixture.reopen();
+
+        assert!(
+            Some(Credentials::AccuWeather {
+                api_key: "abc".into(),
+                base_url: None,
+            }) == fixture
+                .store
+                .get_credentials(DEFAULT_PROFILE, Provider::AccuWeather)
+                .expect("get_credentials"),
+            "credentials saved before base_url existed should still load"
+        );
+
+        fixture.store.set_language("en").expect("set_language");
+        let contents = fs::read_to_string(&fixture.store.path).expect("read config");
+        assert!(!contents.contains("base_url"), "unexpected: {contents}");
+    }
+
+    #[test]
+    fn reads_max_forecast_days_overrides() {
+        let fixture = StoreFixture::new();
+        fs::write(
+            &fixture.store.path,
+            "[max_forecast_days]\naccuweather = 10\n",
+        )
+        .expect("write config");
+
+        let store = fixture.reopen();
+
+        assert_eq!(
+            store
+                .get_max_forecast_days(Provider::AccuWeather)
+                .expect("get_max_forecast_days"),
+            Some(10)
+        );
+        assert_eq!(
+            store
+                .get_max_forecast_days(Provider::WeatherApi)
+                .expect("get_max_forecast_days"),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_icon_overrides() {
+        let fixture = StoreFixture::new();
+        fs::write(&fixture.store.path, "[icons]\nrain = \"☔\"\n").expect("write config");
+
+        let store = fixture.reopen();
+
+        assert_eq!(
+            store.get_icons().expect("get_icons"),
+            HashMap::from([(WeatherCondition::Rain, "☔".to_string())])
+        );
+    }
+
+    fn open_encrypted(path: &Path, passphrase: &str) -> Result<EncryptedFileCredentialsStore> {
+        EncryptedFileCredentialsStore::open(path, ConfigCipher::new(passphrase.into()), true)
+    }
+
+    #[test]
+    fn encrypted_store_reopens_with_right_passphrase_only() {
+        let tmpdir = tempfile::tempdir().expect("create temp dir");
+        let path = tmpdir.path().join("credentials.enc");
+        let creds = Credentials::WeatherApi {
+            api_key: "weather-key".into(),
+        };
+        open_encrypted(&path, "secret")
+            .expect("create encrypted store")
+            .set_credentials(DEFAULT_PROFILE, Provider::WeatherApi, &creds)
+            .expect("set_credentials");
+
+        let contents = fs::read(&path).expect("read config");
+        assert!(
+            !String::from_utf8_lossy(&contents).contains("weather-key"),
+            "credentials should not be stored in plain text"
+        );
+
+        let loaded = open_encrypted(&path, "secret")
+            .expect("reopen with right passphrase")
+            .get_credentials(DEFAULT_PROFILE, Provider::WeatherApi)
+            .expect("get_credentials");
+        assert!(Some(creds) == loaded, "credentials should survive reload");
+
+        let err = open_encrypted(&path, "wrong")
+            .err()
+            .expect("wrong passphrase should fail");
+        assert!(
+            format!("{err:#}").contains("wrong passphrase"),
+            "unexpected error: {err:#}"
+        );
+        assert!(
+            path.exists(),
+            "file should not be reset on wrong passphrase"
+        );
+    }
 }
@@ -0,0 +1,209 @@
+use crate::render::{TemperatureUnit, render_field, round};
+use wezzapp_core::apis::WeatherReport;
+
+/// Placeholders supported by `--template`, e.g. `{location} {date}: {max}/{min}{unit}`.
+///
+/// Temperatures (`max`, `min`, `avg`, `feels_like`) are rounded and shown in the display
+/// unit, `unit` is its symbol. Missing values render empty.
+pub const PLACEHOLDERS: &[&str] = &[
+    "location",
+    "date",
+    "provider",
+    "condition",
+    "description",
+    "max",
+    "min",
+    "avg",
+    "feels_like",
+    "unit",
+    "uv_index",
+    "wind_kph",
+    "wind_direction",
+    "humidity",
+    "sunrise",
+    "sunset",
+];
+
+/// Output layout parsed from a `--template` format string.
+///
+/// `{name}` is replaced with a report value, `{{` and `}}` are literal braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Placeholder(&'static str),
+}
+
+impl Template {
+    /// Parse format string, failing on unknown placeholders and unbalanced braces.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest
+                        .find('}')
+                        .ok_or_else(|| format!("unclosed `{{` in template `{template}`"))?;
+                    let name = &rest[..end];
+                    let placeholder = PLACEHOLDERS
+                        .iter()
+                        .find(|placeholder| **placeholder == name)
+                        .ok_or_else(|| {
+                            format!(
+                                "unknown placeholder `{{{name}}}`, expected one of: {}",
+                                PLACEHOLDERS.join(", ")
+                            )
+                        })?;
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Placeholder(placeholder));
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => return Err(format!("unmatched `}}` in template `{template}`")),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Fill placeholders with values of `report`.
+    pub fn render(
+        &self,
+        report: &WeatherReport,
+        precision: usize,
+        units: TemperatureUnit,
+    ) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(text) => text.clone(),
+                Segment::Placeholder(name) => value(report, name, precision, units),
+            })
+            .collect()
+    }
+}
+
+/// Value of a `PLACEHOLDERS` entry, empty if the report doesn't have it.
+fn value(
+    report: &WeatherReport,
+    placeholder: &str,
+    precision: usize,
+    units: TemperatureUnit,
+) -> String {
+    let temperature = |value: f64| round(units.convert(value), precision);
+
+    let value = match placeholder {
+        "location" => Some(report.location.clone()),
+        "date" => Some(report.date.clone()),
+        "provider" | "condition" => Some(render_field(report, placeholder)),
+        "description" => Some(report.description.clone()),
+        "max" => Some(temperature(report.max_temperature)),
+        "min" => Some(temperature(report.min_temperature)),
+        "avg" => report.avg_temperature.map(temperature),
+        "feels_like" => report.feels_like.map(temperature),
+        "unit" => Some(units.symbol().to_string()),
+        "uv_index" => report.uv_index.map(|uv_index| uv_index.to_string()),
+        "wind_kph" => report.wind_kph.map(|kph| format!("{kph:.0}")),
+        "wind_direction" => report.wind_direction.clone(),
+        "humidity" => report.humidity.map(|humidity| format!("{humidity:.0}")),
+        "sunrise" => report.sunrise.map(|time| time.format("%H:%M").to_string()),
+        "sunset" => report.sunset.map(|time| time.format("%H:%M").to_string()),
+        _ => unreachable!("placeholders are validated on parse"),
+    };
+
+    value.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use wezzapp_core::testing;
+
+    fn report() -> WeatherReport {
+        WeatherReport {
+            max_temperature: 12.2,
+            feels_like: Some(1.6),
+            wind_direction: Some("NW".into()),
+            ..testing::report()
+        }
+    }
+
+    #[test]
+    fn substitutes_placeholders() {
+        let template =
+            Template::parse("{location} {date}: {max}/{min}{unit}, {description}").unwrap();
+
+        assert_eq!(
+            template.render(&report(), 0, TemperatureUnit::Celsius),
+            "Kyiv, Ukraine 2024-11-29: 12/3°C, Sunny"
+        );
+    }
+
+    #[test]
+    fn temperatures_follow_precision_and_units() {
+        let template = Template::parse("{max}{unit} feels {feels_like}{unit}").unwrap();
+
+        assert_eq!(
+            template.render(&report(), 1, TemperatureUnit::Fahrenheit),
+            "54.0°F feels 34.9°F"
+        );
+    }
+
+    #[test]
+    fn missing_values_render_empty() {
+        let template = Template::parse("[{avg}] [{humidity}] [{wind_direction}]").unwrap();
+
+        assert_eq!(
+            template.render(&report(), 0, TemperatureUnit::Celsius),
+            "[] [] [NW]"
+        );
+    }
+
+    #[test]
+    fn double_braces_are_literal() {
+        let template = Template::parse("{{{max}}}").unwrap();
+
+        assert_eq!(
+            template.render(&report(), 0, TemperatureUnit::Celsius),
+            "{12}"
+        );
+    }
+
+    #[test]
+    fn unknown_placeholder_lists_supported_ones() {
+        let err = Template::parse("{location} {temp}").unwrap_err();
+
+        assert!(err.starts_with("unknown placeholder `{temp}`"), "{err}");
+        assert!(err.contains("location, date, provider"), "{err}");
+    }
+
+    #[rstest]
+    #[case("{max")]
+    #[case("max}")]
+    fn unbalanced_braces_fail(#[case] template: &str) {
+        assert!(Template::parse(template).is_err());
+    }
+}
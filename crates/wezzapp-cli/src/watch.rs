@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDateTime};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::Duration;
+
+/// Waits between `get --watch` refreshes, injected so tests don't sleep.
+pub trait WatchClock {
+    /// Wait for `interval`, returns false if watching should stop instead.
+    fn wait(&mut self, interval: Duration) -> bool;
+
+    /// Current local time, shown as when the report was last updated.
+    fn now(&self) -> NaiveDateTime;
+}
+
+/// `WatchClock` sleeping in real time, woken early by Ctrl-C.
+pub struct InterruptibleClock {
+    /// Set by the Ctrl-C handler, which notifies the condvar.
+    stopped: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl InterruptibleClock {
+    /// Install the Ctrl-C handler, which can only be done once per process.
+    pub fn new() -> Result<Self> {
+        let stopped = Arc::new((Mutex::new(false), Condvar::new()));
+
+        let handler_stopped = stopped.clone();
+        ctrlc::set_handler(move || {
+            let (flag, condvar) = &*handler_stopped;
+            *flag.lock().unwrap_or_else(PoisonError::into_inner) = true;
+            condvar.notify_all();
+        })
+        .context("failed to install Ctrl-C handler")?;
+
+        Ok(Self { stopped })
+    }
+}
+
+impl WatchClock for InterruptibleClock {
+    fn wait(&mut self, interval: Duration) -> bool {
+        let (flag, condvar) = &*self.stopped;
+        let flag = flag.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // Ctrl-C during a fetch leaves the flag set, so this returns right away.
+        let (stopped, _) = condvar
+            .wait_timeout_while(flag, interval, |stopped| !*stopped)
+            .unwrap_or_else(PoisonError::into_inner);
+
+        !*stopped
+    }
+
+    fn now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+}
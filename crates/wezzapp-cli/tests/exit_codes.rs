@@ -0,0 +1,57 @@
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+/// Run the binary with an empty config directory, i.e. nothing configured.
+fn wezzapp(config_dir: &TempDir, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_wezzapp-cli"))
+        .args(args)
+        .env("WEZZAPP_CONFIG_DIR", config_dir.path())
+        .env_remove("RUST_LOG")
+        .output()
+        .expect("failed to run wezzapp")
+}
+
+#[test]
+fn missing_credentials_exit_with_config_code() {
+    let config_dir = tempfile::tempdir().unwrap();
+
+    let output = wezzapp(&config_dir, &["get", "Kyiv", "--provider", "weatherapi"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no credentials found"), "{stderr}");
+}
+
+#[test]
+fn missing_default_provider_exits_with_config_code() {
+    let config_dir = tempfile::tempdir().unwrap();
+
+    let output = wezzapp(&config_dir, &["get", "Kyiv"]);
+
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn invalid_arguments_exit_with_usage_code() {
+    let config_dir = tempfile::tempdir().unwrap();
+
+    let output = wezzapp(&config_dir, &["get", "Kyiv", "--precision", "99"]);
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn crossed_threshold_exits_with_threshold_code_without_error() {
+    let config_dir = tempfile::tempdir().unwrap();
+
+    let output = wezzapp(
+        &config_dir,
+        &["get", "Kyiv", "--provider", "mock", "--alert-above", "-100"],
+    );
+
+    assert_eq!(output.status.code(), Some(6));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is above -100°C"), "{stderr}");
+    assert!(!stderr.contains("Error"), "{stderr}");
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+/// Air quality for the forecast day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AirQuality {
+    /// Numeric index value, if provider returns one (WeatherAPI only reports the category).
+    pub index: Option<f64>,
+    pub category: AqiCategory,
+    /// PM2.5 concentration in μg/m³, if provider returns it.
+    pub pm2_5: Option<f64>,
+    /// PM10 concentration in μg/m³, if provider returns it.
+    pub pm10: Option<f64>,
+}
+
+/// US EPA air quality category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AqiCategory {
+    Good,
+    Moderate,
+    UnhealthyForSensitiveGroups,
+    Unhealthy,
+    VeryUnhealthy,
+    Hazardous,
+}
+
+impl AqiCategory {
+    /// Map 1-based US EPA category number (WeatherAPI `us-epa-index`,
+    /// AccuWeather `CategoryValue`).
+    pub fn from_us_epa_index(index: i64) -> Option<Self> {
+        match index {
+            1 => Some(Self::Good),
+            2 => Some(Self::Moderate),
+            3 => Some(Self::UnhealthyForSensitiveGroups),
+            4 => Some(Self::Unhealthy),
+            5 => Some(Self::VeryUnhealthy),
+            6 => Some(Self::Hazardous),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Good => "Good",
+            Self::Moderate => "Moderate",
+            Self::UnhealthyForSensitiveGroups => "Unhealthy for Sensitive Groups",
+            Self::Unhealthy => "Unhealthy",
+            Self::VeryUnhealthy => "Very Unhealthy",
+            Self::Hazardous => "Hazardous",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, Some(AqiCategory::Good))]
+    #[case(3, Some(AqiCategory::UnhealthyForSensitiveGroups))]
+    #[case(6, Some(AqiCategory::Hazardous))]
+    #[case(0, None)]
+    #[case(7, None)]
+    fn maps_us_epa_index(#[case] index: i64, #[case] expected: Option<AqiCategory>) {
+        assert_eq!(AqiCategory::from_us_epa_index(index), expected);
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Active government weather alert (warning, watch, advisory) for the location.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherAlert {
+    /// Short summary, e.g. "Flood Warning issued for Kyiv".
+    pub headline: String,
+    /// Severity as worded by the issuing agency, e.g. "Moderate" or "Severe".
+    pub severity: Option<String>,
+    /// Names of the affected areas, empty if the provider doesn't list them.
+    pub areas: Vec<String>,
+}
@@ -1,124 +1,550 @@
-use crate::apis::{ProviderClient, WeatherReport};
+use crate::air_quality::{AirQuality, AqiCategory};
+use crate::alert::WeatherAlert;
+use crate::apis::debug_log::{DebugLog, send_logged};
+use crate::apis::location_cache::{LocationCache, normalize};
+use crate::apis::quota::{self, QuotaObserver};
+use crate::apis::rate_limiter::RateLimitBudget;
+use crate::apis::redact;
+use crate::apis::request_span::RequestSpan;
+use crate::apis::retry::RetryPolicy;
+use crate::apis::{
+    CurrentConditions, DEFAULT_LANGUAGE, ProviderClient, ResolvedLocation, VERIFY_ADDRESS,
+    WeatherReport, check_status, ping_result,
+};
+use crate::condition::WeatherCondition;
+use crate::error::WeatherError;
 use crate::provider::Provider;
-use anyhow::{Context, Result, anyhow};
-use chrono::{DateTime, FixedOffset, NaiveDate};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime};
 use reqwest::Url;
 use reqwest::blocking::Client;
 use reqwest::header::AUTHORIZATION;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, de};
-use tracing::debug;
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use tracing::{debug, trace};
+
+/// Production AccuWeather base URL.
+pub(crate) const BASE_URL: &str = "https://dataservice.accuweather.com/";
 
 /// Http client for AccuWeather API
 #[derive(Debug)]
-pub struct AccuWeatherClient<'a> {
+pub struct AccuWeatherClient {
     api_key: String,
-    url: &'a str,
+    url: String,
+    language: String,
+    /// Additional query pairs appended verbatim to the forecast request.
+    extra_params: Vec<(String, String)>,
+    /// Whether to include air quality in reports.
+    air_quality: bool,
+    /// Whether to fetch weather alerts, an extra call per report.
+    alerts: bool,
+    quota_observer: Option<Arc<dyn QuotaObserver>>,
+    location_cache: Option<Arc<dyn LocationCache>>,
+    /// Max number of location search candidates, provider default if unset.
+    search_limit: Option<u32>,
+    /// Search cities only, skipping points of interest and postal codes.
+    cities_only: bool,
+    retry: RetryPolicy,
+    /// Spent once per HTTP request sent, if set.
+    rate_limit: Option<Arc<RateLimitBudget>>,
+    /// Records every HTTP exchange, if set.
+    debug_log: Option<Arc<DebugLog>>,
     client: Client,
 }
-impl AccuWeatherClient<'static> {
-    pub fn new(api_key: String) -> Self {
+impl AccuWeatherClient {
+    /// Create client sending requests to the given base URL over a shared HTTP `client`.
+    pub(crate) fn new(client: Client, api_key: String, url: impl Into<String>) -> Self {
         Self {
             api_key,
-            url: "https://dataservice.accuweather.com/",
-            client: Client::new(),
+            url: url.into(),
+            language: DEFAULT_LANGUAGE.to_string(),
+            extra_params: Vec::new(),
+            air_quality: false,
+            alerts: false,
+            quota_observer: None,
+            location_cache: None,
+            search_limit: None,
+            cities_only: false,
+            retry: RetryPolicy::default(),
+            rate_limit: None,
+            debug_log: None,
+            client,
         }
     }
 
-    fn get(&self, url: Url) -> Result<reqwest::blocking::Response> {
-        self.client
+    /// Limit HTTP requests to `budget`, shared with other clients of the provider.
+    pub(crate) fn with_rate_limit(mut self, budget: Option<Arc<RateLimitBudget>>) -> Self {
+        self.rate_limit = budget;
+        self
+    }
+
+    /// Record every HTTP exchange in `log`.
+    pub(crate) fn with_debug_log(mut self, log: Option<Arc<DebugLog>>) -> Self {
+        self.debug_log = log;
+        self
+    }
+
+    /// Set how failed requests are retried.
+    pub(crate) fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set language for localized condition text.
+    pub(crate) fn with_language(mut self, language: String) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Include air quality in reports.
+    pub(crate) fn with_air_quality(mut self, air_quality: bool) -> Self {
+        self.air_quality = air_quality;
+        self
+    }
+
+    /// Include weather alerts in reports.
+    pub(crate) fn with_alerts(mut self, alerts: bool) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Report quota from response headers to `observer`.
+    pub(crate) fn with_quota_observer(mut self, observer: Option<Arc<dyn QuotaObserver>>) -> Self {
+        self.quota_observer = observer;
+        self
+    }
+
+    /// Look up resolved locations in `cache` before calling the location search API.
+    pub(crate) fn with_location_cache(mut self, cache: Option<Arc<dyn LocationCache>>) -> Self {
+        self.location_cache = cache;
+        self
+    }
+
+    /// Limit location search to `limit` candidates.
+    pub(crate) fn with_search_limit(mut self, limit: Option<u32>) -> Self {
+        self.search_limit = limit;
+        self
+    }
+
+    /// Search cities only instead of all location types.
+    pub(crate) fn with_cities_only(mut self, cities_only: bool) -> Self {
+        self.cities_only = cities_only;
+        self
+    }
+
+    /// Append extra query pairs to the forecast request.
+    pub(crate) fn with_extra_params(mut self, extra_params: Vec<(String, String)>) -> Self {
+        self.extra_params = extra_params;
+        self
+    }
+
+    fn get(&self, url: Url) -> Result<reqwest::blocking::Response, WeatherError> {
+        let request = self
+            .client
             .get(url)
-            .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
-            .send()
-            .context("failed to send request to AccuWeather API")?
-            .error_for_status()
-            .context("AccuWeather API returned error status")
-    }
-
-    fn search_request(&self, address: String) -> Result<Vec<AccuWeatherLocationResponse>> {
-        debug!("Getting location key for address `{address}`");
-        let mut url = Url::parse(self.url).context("Error parsing AccuWeather API URL")?;
-        url = url
-            .join("locations/v1/search")
-            .context("Error joining AccuWeather API URL")?;
+            .header(AUTHORIZATION, format!("Bearer {}", self.api_key));
+        let resp = send_logged(
+            request,
+            &self.retry,
+            self.rate_limit.as_deref(),
+            self.debug_log.as_deref(),
+        )?;
+
+        if let Some(observer) = &self.quota_observer
+            && let Some(status) = quota::from_headers(Provider::AccuWeather, resp.headers())
+        {
+            observer.observe(&status);
+        }
+
+        check_status(resp, Provider::AccuWeather)
+    }
+
+    fn search_request(
+        &self,
+        address: String,
+    ) -> Result<Vec<AccuWeatherLocationResponse>, WeatherError> {
+        debug!(
+            "Getting location key for address {}",
+            redact::address(&address)
+        );
+        let path = if self.cities_only {
+            "locations/v1/cities/search"
+        } else {
+            "locations/v1/search"
+        };
+        let mut url = Url::parse(&self.url)?;
+        url = url.join(path)?;
         {
             let mut qp = url.query_pairs_mut();
             qp.append_pair("q", &address);
+            qp.append_pair("language", &self.language);
+            // Despite the name, `offset` is the number of results to return.
+            if let Some(limit) = self.search_limit {
+                qp.append_pair("offset", &limit.to_string());
+            }
         }
-        debug!("AccuWeather API URL: {url:?}");
+        trace!("AccuWeather API URL: {url:?}");
 
         let resp = self.get(url)?;
 
-        let body = resp
-            .json()
-            .context("failed to deserialize AccuWeather API JSON")?;
-        debug!("AccuWeather API body: {body:?}");
+        let body = resp.json()?;
+        trace!("AccuWeather API body: {body:?}");
 
         Ok(body)
     }
 
-    fn forecast_request(&self, location_key: &str) -> Result<AccuWeatherForecastResponse> {
-        let mut url = Url::parse(self.url).context("Error parsing AccuWeather API URL")?;
-        url = url
-            .join(&format!("forecasts/v1/daily/5day/{}", location_key))
-            .context("Error joining AccuWeather API URL")?;
+    /// Latest observation for the location.
+    fn current_request(
+        &self,
+        location_key: &str,
+    ) -> Result<AccuWeatherCurrentResponse, WeatherError> {
+        let mut url = Url::parse(&self.url)?;
+        url = url.join(&format!("currentconditions/v1/{location_key}"))?;
         {
             let mut qp = url.query_pairs_mut();
-            qp.append_pair("metric", &true.to_string());
+            // RealFeel, humidity and wind are only returned with details.
+            qp.append_pair("details", &true.to_string());
+            qp.append_pair("language", &self.language);
         }
-        debug!("AccuWeather API URL: {url:?}");
+        trace!("AccuWeather API URL: {url:?}");
 
         let resp = self.get(url)?;
 
-        let body = resp
-            .json()
-            .context("Failed to deserialize AccuWeather API JSON")?;
-        debug!("AccuWeather API body: {body:?}");
+        let body: Vec<AccuWeatherCurrentResponse> = resp.json()?;
+        trace!("AccuWeather API current body: {body:?}");
 
-        Ok(body)
+        body.into_iter().next().ok_or_else(|| {
+            WeatherError::UnexpectedResponse("no current conditions in API response".into())
+        })
     }
-}
 
-impl ProviderClient for AccuWeatherClient<'static> {
-    fn get_weather(&self, address: String, day_from_today: u32) -> Result<WeatherReport> {
-        debug!("Getting weather for address `{address} day from today: {day_from_today}`");
-        let days = day_from_today + 1;
-        // It only supports up to 5 days on the free plan.
-        if days > 5 {
-            return Err(anyhow!(
-                "AccuWeather API only supports up to 5 days forecast (including today)."
-            ));
-        }
+    /// Active alerts for the location.
+    fn alerts_request(&self, location_key: &str) -> Result<Vec<WeatherAlert>, WeatherError> {
+        let mut url = Url::parse(&self.url)?;
+        url = url.join(&format!("alerts/v1/{location_key}"))?;
+        url.query_pairs_mut()
+            .append_pair("language", &self.language);
+        trace!("AccuWeather API URL: {url:?}");
 
-        let mut locations = self.search_request(address)?;
+        let resp = self.get(url)?;
 
-        let location = locations
-            .pop()
-            .context("Address not found, please, use more accurate address, eg: Kyiv, Ukraine")?;
-        debug!("AccuWeather API location key: {location:?}");
+        let body: Vec<AccuWeatherAlertResponse> = resp.json()?;
+        trace!("AccuWeather API alerts body: {body:?}");
 
-        let forecast = self.forecast_request(&location.key)?;
+        Ok(body
+            .into_iter()
+            .map(AccuWeatherAlertResponse::into_alert)
+            .collect())
+    }
 
-        let day_forecast = forecast
-            .daily_forecasts
-            .get(day_from_today as usize)
-            .context("Wrong number of days in API response")?;
-        debug!("AccuWeather API forecast: {day_forecast:?}");
+    /// Daily forecast long enough to cover `days` (including today).
+    ///
+    /// Today alone is fetched with the 1 day forecast, sparing the more limited 5 day quota
+    /// of free plans. 10 and 15 day forecasts are only available on paid plans.
+    fn forecast_request<T: DeserializeOwned + Debug>(
+        &self,
+        location_key: &str,
+        days: u32,
+    ) -> Result<T, WeatherError> {
+        let period = match days {
+            ..=1 => 1,
+            2..=5 => 5,
+            6..=10 => 10,
+            _ => 15,
+        };
+        let mut url = Url::parse(&self.url)?;
+        url = url.join(&format!("forecasts/v1/daily/{period}day/{location_key}"))?;
+        {
+            let mut qp = url.query_pairs_mut();
+            qp.append_pair("metric", &true.to_string());
+            // RealFeel temperatures are only returned with details.
+            qp.append_pair("details", &true.to_string());
+            qp.append_pair("language", &self.language);
+            qp.extend_pairs(&self.extra_params);
+        }
+        trace!("AccuWeather API URL: {url:?}");
+
+        let resp = self.get(url)?;
 
-        Ok(WeatherReport {
+        let body = resp.json()?;
+        trace!("AccuWeather API body: {body:?}");
+
+        Ok(body)
+    }
+
+    /// Report for one day of a forecast.
+    fn report(
+        &self,
+        location: &ResolvedLocation,
+        day_forecast: &AccuWeatherDailyForecastResponse,
+        alerts: Option<Vec<WeatherAlert>>,
+    ) -> WeatherReport {
+        WeatherReport {
             provider: Provider::AccuWeather,
             date: day_forecast.date.clone().to_string(),
-            location: format!(
-                "{}, {}",
-                location.localized_name, location.country.localized_name
-            ),
+            location: location.display_name(),
+            condition: WeatherCondition::from_accuweather_icon(day_forecast.day.icon),
             description: format!(
                 "Day: {}, Night: {}",
                 day_forecast.day.icon_prase, day_forecast.night.icon_prase
             ),
-            max_temperature: day_forecast.temperature.minimum.value,
-            min_temperature: day_forecast.temperature.maximum.value,
+            max_temperature: day_forecast.temperature.maximum.value,
+            min_temperature: day_forecast.temperature.minimum.value,
+            // No daily average in the API, use the midpoint of the range.
+            avg_temperature: Some(
+                (day_forecast.temperature.minimum.value + day_forecast.temperature.maximum.value)
+                    / 2.0,
+            ),
+            // Single value for the day: mean of the RealFeel range.
+            feels_like: day_forecast
+                .real_feel_temperature
+                .as_ref()
+                .map(|t| (t.minimum.value + t.maximum.value) / 2.0),
+            uv_index: day_forecast
+                .air_and_pollen
+                .iter()
+                .find(|entry| entry.name == "UVIndex")
+                .map(|entry| entry.value),
+            wind_kph: day_forecast
+                .day
+                .wind
+                .as_ref()
+                .and_then(|wind| wind.speed.as_ref())
+                .map(|speed| speed.value),
+            wind_direction: day_forecast
+                .day
+                .wind
+                .as_ref()
+                .and_then(|wind| wind.direction.as_ref())
+                .map(|direction| direction.localized.clone()),
+            humidity: day_forecast
+                .day
+                .relative_humidity
+                .as_ref()
+                .and_then(|humidity| humidity.average),
+            sunrise: day_forecast
+                .sun
+                .as_ref()
+                .and_then(|sun| local_time(sun.rise.as_deref())),
+            sunset: day_forecast
+                .sun
+                .as_ref()
+                .and_then(|sun| local_time(sun.set.as_deref())),
+            air_quality: self
+                .air_quality
+                .then(|| day_forecast.air_quality())
+                .flatten(),
+            alerts,
+            stale_since: None,
+        }
+    }
+}
+
+impl ProviderClient for AccuWeatherClient {
+    fn get_weather(
+        &self,
+        address: String,
+        day_from_today: u32,
+    ) -> Result<WeatherReport, WeatherError> {
+        // `resolve_location` and `get_weather_at` enter their own spans.
+        let location = self.resolve_location(&address)?;
+
+        self.get_weather_at(&location, day_from_today)
+    }
+
+    fn resolve_location(&self, address: &str) -> Result<ResolvedLocation, WeatherError> {
+        let _span = RequestSpan::enter(Provider::AccuWeather, "location", address);
+        // Names are localized, so cache them per language.
+        let cache_key = format!("accuweather:{}:{}", self.language, normalize(address));
+        if let Some(location) = self
+            .location_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&cache_key))
+        {
+            return Ok(location);
+        }
+
+        let locations = self.search_request(address.to_string())?;
+
+        debug!(
+            "AccuWeather API returned {} location candidates",
+            locations.len()
+        );
+        let location =
+            select_best_match(locations, address).ok_or(WeatherError::AddressNotFound)?;
+        trace!("AccuWeather API location key: {location:?}");
+
+        let location = ResolvedLocation {
+            name: location.localized_name,
+            country: location.country.localized_name,
+            lat: location.geo_position.latitude,
+            lon: location.geo_position.longitude,
+            provider_key: Some(location.key),
+            timezone: location.time_zone.map(|time_zone| time_zone.name),
+        };
+        if let Some(cache) = &self.location_cache {
+            cache.put(&cache_key, &location);
+        }
+
+        Ok(location)
+    }
+
+    fn get_weather_at(
+        &self,
+        location: &ResolvedLocation,
+        day_from_today: u32,
+    ) -> Result<WeatherReport, WeatherError> {
+        let mut reports = self.get_weather_range_at(location, day_from_today..=day_from_today)?;
+
+        Ok(reports.remove(0))
+    }
+
+    fn get_weather_range_at(
+        &self,
+        location: &ResolvedLocation,
+        days: RangeInclusive<u32>,
+    ) -> Result<Vec<WeatherReport>, WeatherError> {
+        let _span = RequestSpan::enter(Provider::AccuWeather, "forecast", &location.display_name());
+        debug!("Getting weather {days:?} days from today");
+
+        // Locations resolved by other providers have no AccuWeather key.
+        let key = match &location.provider_key {
+            Some(key) => key.clone(),
+            None => self
+                .resolve_location(&location.display_name())?
+                .provider_key
+                .ok_or(WeatherError::AddressNotFound)?,
+        };
+
+        // Today is day 0, so the forecast must be one day longer than the last day, and covers
+        // the whole range at once.
+        let requested = days.end() + 1;
+        let forecast: AccuWeatherForecastResponse = self.forecast_request(&key, requested)?;
+
+        let returned = forecast.daily_forecasts.len();
+        let day_forecasts = forecast
+            .daily_forecasts
+            .get(*days.start() as usize..requested as usize)
+            .ok_or(WeatherError::ForecastTooShort {
+                returned: returned as u32,
+                requested,
+                provider: Provider::AccuWeather,
+            })?;
+        debug!("AccuWeather API forecast: {day_forecasts:?}");
+
+        let alerts = if self.alerts {
+            Some(self.alerts_request(&key)?)
+        } else {
+            None
+        };
+
+        Ok(day_forecasts
+            .iter()
+            .map(|day_forecast| self.report(location, day_forecast, alerts.clone()))
+            .collect())
+    }
+
+    fn get_weather_raw(&self, address: String, day_from_today: u32) -> Result<Value, WeatherError> {
+        let _span = RequestSpan::enter(Provider::AccuWeather, "forecast_raw", &address);
+        debug!("Getting raw weather {day_from_today} days from today");
+
+        let key = self
+            .resolve_location(&address)?
+            .provider_key
+            .ok_or(WeatherError::AddressNotFound)?;
+
+        // Forecast is returned as a whole regardless of the requested day.
+        self.forecast_request(&key, day_from_today + 1)
+    }
+
+    fn get_current(&self, address: &str) -> Result<CurrentConditions, WeatherError> {
+        let _span = RequestSpan::enter(Provider::AccuWeather, "current", address);
+
+        let location = self.resolve_location(address)?;
+        let key = location
+            .provider_key
+            .as_deref()
+            .ok_or(WeatherError::AddressNotFound)?;
+        let current = self.current_request(key)?;
+        let observed_at = DateTime::from_timestamp(current.epoch_time, 0)
+            .ok_or_else(|| WeatherError::UnexpectedResponse("invalid observation time".into()))?;
+
+        Ok(CurrentConditions {
+            provider: Provider::AccuWeather,
+            location: location.display_name(),
+            observed_at,
+            condition: WeatherCondition::from_accuweather_icon(current.weather_icon),
+            description: current.weather_text,
+            temperature: current.temperature.metric.value,
+            feels_like: current.real_feel_temperature.map(|t| t.metric.value),
+            humidity: current.relative_humidity,
+            wind_kph: current
+                .wind
+                .as_ref()
+                .and_then(|wind| wind.speed.as_ref())
+                .map(|speed| speed.metric.value),
+            wind_direction: current
+                .wind
+                .and_then(|wind| wind.direction)
+                .map(|direction| direction.localized),
         })
     }
+
+    fn max_forecast_days(&self) -> u32 {
+        // It only supports up to 5 days on the free plan.
+        5
+    }
+
+    fn ping(&self) -> Result<(), WeatherError> {
+        // A location search is a single call, unlike the search and forecast of the default.
+        let mut url = Url::parse(&self.url)?;
+        url = url.join("locations/v1/search")?;
+        url.query_pairs_mut().append_pair("q", VERIFY_ADDRESS);
+        debug!("Pinging AccuWeather: {url:?}");
+
+        ping_result(self.get(url), Provider::AccuWeather)
+    }
+}
+
+/// Candidate whose name best matches the query's first part, e.g. "Paris" of "Paris, France".
+///
+/// An exact (localized or English) name beats one containing the query, which beats the rest.
+/// Ties go to the higher-ranked candidate, as the search returns best matches first.
+fn select_best_match(
+    results: Vec<AccuWeatherLocationResponse>,
+    query: &str,
+) -> Option<AccuWeatherLocationResponse> {
+    let query = normalize(query.split(',').next().unwrap_or(query));
+
+    results
+        .into_iter()
+        .enumerate()
+        .max_by_key(|(rank, location)| (match_score(location, &query), Reverse(*rank)))
+        .map(|(_, location)| location)
+}
+
+/// 2 for an exact name match, 1 for a name containing `query`, 0 otherwise.
+fn match_score(location: &AccuWeatherLocationResponse, query: &str) -> u8 {
+    let names = std::iter::once(location.localized_name.as_str())
+        .chain(location.english_name.as_deref())
+        .map(normalize);
+
+    names
+        .map(|name| {
+            if name == query {
+                2
+            } else if !query.is_empty() && name.contains(query) {
+                1
+            } else {
+                0
+            }
+        })
+        .max()
+        .unwrap_or(0)
 }
 
 #[derive(Debug, Deserialize)]
@@ -127,9 +553,101 @@ struct AccuWeatherLocationResponse {
     key: String,
     #[serde(rename = "LocalizedName")]
     localized_name: String,
+    #[serde(rename = "EnglishName")]
+    english_name: Option<String>,
     #[serde(rename = "Country")]
     country: AccuWeatherCountryResponse,
+    #[serde(rename = "GeoPosition")]
+    geo_position: AccuWeatherGeoPositionResponse,
+    #[serde(rename = "TimeZone")]
+    time_zone: Option<AccuWeatherTimeZoneResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherCurrentResponse {
+    #[serde(rename = "EpochTime")]
+    epoch_time: i64,
+    #[serde(rename = "WeatherText")]
+    weather_text: String,
+    #[serde(rename = "WeatherIcon")]
+    weather_icon: i64,
+    #[serde(rename = "Temperature")]
+    temperature: AccuWeatherMetricResponse,
+    /// Only returned with details, like the rest of the optional fields.
+    #[serde(rename = "RealFeelTemperature")]
+    real_feel_temperature: Option<AccuWeatherMetricResponse>,
+    #[serde(rename = "RelativeHumidity")]
+    relative_humidity: Option<f64>,
+    #[serde(rename = "Wind")]
+    wind: Option<AccuWeatherCurrentWindResponse>,
+}
+
+/// Value in both unit systems, only the metric one is used.
+#[derive(Debug, Deserialize)]
+struct AccuWeatherMetricResponse {
+    #[serde(rename = "Metric")]
+    metric: AccuWeatherTemperatureValueResponse,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherCurrentWindResponse {
+    /// In km/h.
+    #[serde(rename = "Speed")]
+    speed: Option<AccuWeatherMetricResponse>,
+    #[serde(rename = "Direction")]
+    direction: Option<AccuWeatherWindDirectionResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherAlertResponse {
+    #[serde(rename = "Description")]
+    description: AccuWeatherLocalizedResponse,
+    /// Agency's severity wording, often missing.
+    #[serde(rename = "Level")]
+    level: Option<String>,
+    /// Broad kind of the alert, e.g. "FLOOD".
+    #[serde(rename = "Category")]
+    category: Option<String>,
+    #[serde(rename = "Area", default)]
+    area: Vec<AccuWeatherAlertAreaResponse>,
+}
+
+impl AccuWeatherAlertResponse {
+    fn into_alert(self) -> WeatherAlert {
+        WeatherAlert {
+            headline: self.description.localized,
+            severity: self.level.or(self.category),
+            areas: self.area.into_iter().map(|area| area.name).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherLocalizedResponse {
+    #[serde(rename = "Localized")]
+    localized: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherAlertAreaResponse {
+    #[serde(rename = "Name")]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherGeoPositionResponse {
+    #[serde(rename = "Latitude")]
+    latitude: f64,
+    #[serde(rename = "Longitude")]
+    longitude: f64,
 }
+#[derive(Debug, Deserialize)]
+struct AccuWeatherTimeZoneResponse {
+    /// IANA name, e.g. "Europe/Kiev".
+    #[serde(rename = "Name")]
+    name: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct AccuWeatherCountryResponse {
     #[serde(rename = "LocalizedName")]
@@ -148,10 +666,51 @@ struct AccuWeatherDailyForecastResponse {
     date: NaiveDate,
     #[serde(rename = "Temperature")]
     temperature: AccuWeatherTemperatureResponse,
+    #[serde(rename = "RealFeelTemperature")]
+    real_feel_temperature: Option<AccuWeatherTemperatureResponse>,
+    #[serde(rename = "AirAndPollen", default)]
+    air_and_pollen: Vec<AccuWeatherAirAndPollenResponse>,
     #[serde(rename = "Day")]
     day: AccuWeatherDayNightResponse,
     #[serde(rename = "Night")]
     night: AccuWeatherDayNightResponse,
+    /// Only returned with details.
+    #[serde(rename = "Sun")]
+    sun: Option<AccuWeatherSunResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherSunResponse {
+    /// Null when the sun doesn't rise (polar night).
+    #[serde(rename = "Rise")]
+    rise: Option<String>,
+    /// Null when the sun doesn't set (polar day).
+    #[serde(rename = "Set")]
+    set: Option<String>,
+}
+
+/// Local time of an RFC 3339 timestamp like "2024-11-29T07:31:00+02:00".
+fn local_time(timestamp: Option<&str>) -> Option<NaiveTime> {
+    DateTime::parse_from_rfc3339(timestamp?)
+        .ok()
+        .map(|datetime| datetime.time())
+}
+
+impl AccuWeatherDailyForecastResponse {
+    /// Air quality index is part of the `AirAndPollen` details.
+    fn air_quality(&self) -> Option<AirQuality> {
+        let entry = self
+            .air_and_pollen
+            .iter()
+            .find(|entry| entry.name == "AirQuality")?;
+
+        Some(AirQuality {
+            index: Some(entry.value),
+            category: AqiCategory::from_us_epa_index(entry.category_value?)?,
+            pm2_5: None,
+            pm10: None,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -168,10 +727,55 @@ struct AccuWeatherTemperatureValueResponse {
     value: f64,
 }
 
+#[derive(Debug, Deserialize)]
+struct AccuWeatherAirAndPollenResponse {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Value")]
+    value: f64,
+    #[serde(rename = "CategoryValue")]
+    category_value: Option<i64>,
+}
+
 #[derive(Debug, Deserialize)]
 struct AccuWeatherDayNightResponse {
+    #[serde(rename = "Icon")]
+    icon: i64,
     #[serde(rename = "IconPhrase")]
     icon_prase: String,
+    /// Only returned with details.
+    #[serde(rename = "Wind")]
+    wind: Option<AccuWeatherWindResponse>,
+    /// Only returned with details.
+    #[serde(rename = "RelativeHumidity")]
+    relative_humidity: Option<AccuWeatherHumidityResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherHumidityResponse {
+    #[serde(rename = "Average")]
+    average: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherWindResponse {
+    /// In km/h, as requested with `metric=true`.
+    #[serde(rename = "Speed")]
+    speed: Option<AccuWeatherWindSpeedResponse>,
+    #[serde(rename = "Direction")]
+    direction: Option<AccuWeatherWindDirectionResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherWindSpeedResponse {
+    #[serde(rename = "Value")]
+    value: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccuWeatherWindDirectionResponse {
+    #[serde(rename = "Localized")]
+    localized: String,
 }
 
 fn deserialize_naive_date_from_rfc<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
@@ -184,3 +788,815 @@ where
 
     Ok(datetime_with_offset.date_naive())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apis::location_cache::FileLocationCache;
+    use crate::apis::rate_limiter::{RateLimit, RateLimitMode};
+    use mockito::{Matcher, Server};
+    use rstest::rstest;
+    use std::time::Duration;
+
+    fn base_url(server: &Server) -> String {
+        format!("{}/", server.url())
+    }
+
+    #[test]
+    fn unauthorized_returns_invalid_api_key() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_status(401)
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::InvalidApiKey(Provider::AccuWeather)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn too_many_requests_returns_rate_limited() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_status(429)
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url)
+            .with_retry_policy(RetryPolicy::none());
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::RateLimited),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn empty_location_list_returns_address_not_found() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let err = client.get_weather("Nowhere".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::AddressNotFound),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    fn resolve_from_candidates(address: &str, candidates: &[(&str, &str)]) -> ResolvedLocation {
+        let body = candidates
+            .iter()
+            .map(|(key, name)| {
+                format!(
+                    r#"{{
+                        "Key": "{key}", "LocalizedName": "{name}", "Country": {{"LocalizedName": "Ukraine"}},
+                        "GeoPosition": {{"Latitude": 50.45, "Longitude": 30.524}}
+                    }}"#
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(format!("[{body}]"))
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        client.resolve_location(address).unwrap()
+    }
+
+    #[test]
+    fn top_ranked_candidate_is_chosen_without_exact_match() {
+        let location = resolve_from_candidates(
+            "Kiev, Ukraine",
+            &[("1", "Kyiv"), ("2", "Kyivska"), ("3", "Kyiv Oblast")],
+        );
+
+        assert_eq!(location.provider_key.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn exact_name_match_is_preferred_over_rank() {
+        let location = resolve_from_candidates(
+            "kyiv, Ukraine",
+            &[("1", "Kyiv Oblast"), ("2", "Kyiv"), ("3", "Kyivska")],
+        );
+
+        assert_eq!(location.provider_key.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn exact_english_name_match_is_preferred() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[
+                    {
+                        "Key": "1", "LocalizedName": "Київська область", "EnglishName": "Kyiv Oblast",
+                        "Country": {"LocalizedName": "Україна"},
+                        "GeoPosition": {"Latitude": 50.0, "Longitude": 30.0}
+                    },
+                    {
+                        "Key": "2", "LocalizedName": "Київ", "EnglishName": "Kyiv",
+                        "Country": {"LocalizedName": "Україна"},
+                        "GeoPosition": {"Latitude": 50.45, "Longitude": 30.524}
+                    }
+                ]"#,
+            )
+            .create();
+        let url = base_url(&server);
+        let client =
+            AccuWeatherClient::new(Client::new(), "KEY".into(), &url).with_language("uk".into());
+
+        let location = client.resolve_location("Kyiv").unwrap();
+
+        assert_eq!(location.provider_key.as_deref(), Some("2"));
+        assert_eq!(location.name, "Київ");
+    }
+
+    fn candidate(key: &str, name: &str, english_name: Option<&str>) -> AccuWeatherLocationResponse {
+        AccuWeatherLocationResponse {
+            key: key.into(),
+            localized_name: name.into(),
+            english_name: english_name.map(Into::into),
+            country: AccuWeatherCountryResponse {
+                localized_name: "Ukraine".into(),
+            },
+            geo_position: AccuWeatherGeoPositionResponse {
+                latitude: 50.45,
+                longitude: 30.524,
+            },
+            time_zone: None,
+        }
+    }
+
+    #[rstest]
+    #[case::exact_over_substring("Kyiv", &[("1", "Kyiv Oblast", None), ("2", "Kyiv", None)], "2")]
+    #[case::substring_over_rank("Lviv", &[("1", "Lwów", None), ("2", "Lviv Oblast", None)], "2")]
+    #[case::exact_english_name("Kyiv", &[("1", "Київська область", Some("Kyiv Oblast")), ("2", "Київ", Some("Kyiv"))], "2")]
+    #[case::higher_rank_wins_ties("Kyiv", &[("1", "Kyiv Oblast", None), ("2", "Kyivska", None)], "1")]
+    #[case::top_ranked_without_match("Kiev", &[("1", "Kyiv", None), ("2", "Kyivska", None)], "1")]
+    #[case::case_and_whitespace_insensitive("  KYIV , Ukraine", &[("1", "Kyivska", None), ("2", "kyiv", None)], "2")]
+    fn selects_best_match(
+        #[case] query: &str,
+        #[case] candidates: &[(&str, &str, Option<&str>)],
+        #[case] expected_key: &str,
+    ) {
+        let results = candidates
+            .iter()
+            .map(|(key, name, english_name)| candidate(key, name, *english_name))
+            .collect();
+
+        let location = select_best_match(results, query).unwrap();
+
+        assert_eq!(location.key, expected_key);
+    }
+
+    #[test]
+    fn no_results_select_nothing() {
+        assert!(select_best_match(Vec::new(), "Kyiv").is_none());
+    }
+
+    #[test]
+    fn cities_search_is_limited_to_configured_count() {
+        let mut server = Server::new();
+        let search = server
+            .mock("GET", "/locations/v1/cities/search")
+            .match_query(Matcher::UrlEncoded("offset".into(), "3".into()))
+            .with_header("content-type", "application/json")
+            .with_body(LOCATIONS_BODY)
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url)
+            .with_search_limit(Some(3))
+            .with_cities_only(true);
+
+        let location = client.resolve_location("Kyiv").unwrap();
+
+        assert_eq!(location.provider_key.as_deref(), Some("324505"));
+        search.assert();
+    }
+
+    const LOCATIONS_BODY: &str = r#"[
+        {
+            "Key": "324505", "LocalizedName": "Kyiv", "Country": {"LocalizedName": "Ukraine"},
+            "GeoPosition": {"Latitude": 50.45, "Longitude": 30.524},
+            "TimeZone": {"Code": "EET", "Name": "Europe/Kiev", "GmtOffset": 2.0}
+        }
+    ]"#;
+
+    fn forecast_body(real_feel: Option<(f64, f64)>) -> String {
+        let real_feel = real_feel
+            .map(|(min, max)| {
+                format!(
+                    r#","RealFeelTemperature": {{
+                        "Minimum": {{"Value": {min}}}, "Maximum": {{"Value": {max}}}
+                    }}"#
+                )
+            })
+            .unwrap_or_default();
+
+        format!(
+            r#"{{"DailyForecasts": [{{
+                "Date": "2024-11-29T07:00:00+02:00",
+                "Temperature": {{"Minimum": {{"Value": 3.0}}, "Maximum": {{"Value": 12.0}}}},
+                "Day": {{
+                    "Icon": 1, "IconPhrase": "Sunny",
+                    "Wind": {{"Speed": {{"Value": 13.0, "Unit": "km/h"}}, "Direction": {{"Localized": "NW"}}}},
+                    "RelativeHumidity": {{"Minimum": 58, "Maximum": 86, "Average": 72}}
+                }},
+                "Night": {{"Icon": 33, "IconPhrase": "Clear"}},
+                "Sun": {{"Rise": "2024-11-29T07:31:00+02:00", "Set": null}},
+                "AirAndPollen": [
+                    {{"Name": "AirQuality", "Value": 40, "Category": "Good", "CategoryValue": 1}},
+                    {{"Name": "UVIndex", "Value": 3}}
+                ]
+                {real_feel}
+            }}]}}"#
+        )
+    }
+
+    #[test]
+    fn location_search_and_forecast_each_spend_rate_limit() {
+        let mut server = Server::new();
+        let (search, forecast) = mock_forecast(&mut server, forecast_body(None));
+        let search = search.expect(2);
+        let forecast = forecast.expect(1);
+        let limit =
+            RateLimit::per_window(3, Duration::from_secs(60)).with_mode(RateLimitMode::Error);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), base_url(&server))
+            .with_rate_limit(Some(Arc::new(RateLimitBudget::new(limit))));
+
+        client.get_weather("Kyiv".into(), 0).unwrap();
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::RateLimited),
+            "unexpected error: {err:?}"
+        );
+        search.assert();
+        forecast.assert();
+    }
+
+    /// Path of the daily forecast for Kyiv, of any period.
+    const FORECAST_PATH: &str = r"^/forecasts/v1/daily/\d+day/324505$";
+
+    fn mock_forecast(server: &mut Server, forecast: String) -> (mockito::Mock, mockito::Mock) {
+        let search = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(LOCATIONS_BODY)
+            .create();
+        let forecast = server
+            .mock("GET", Matcher::Regex(FORECAST_PATH.into()))
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(forecast)
+            .create();
+
+        (search, forecast)
+    }
+
+    #[rstest]
+    #[case::today_only(0, "1day")]
+    #[case::tomorrow(1, "5day")]
+    #[case::last_free_day(4, "5day")]
+    #[case::paid_plan(5, "10day")]
+    fn picks_shortest_forecast_covering_requested_day(#[case] day: u32, #[case] period: &str) {
+        let mut server = Server::new();
+        let search = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(LOCATIONS_BODY)
+            .create();
+        let forecast = server
+            .mock(
+                "GET",
+                format!("/forecasts/v1/daily/{period}/324505").as_str(),
+            )
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(daily_forecasts_body(day + 1))
+            .expect(1)
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        client.get_weather_raw("Kyiv".into(), day).unwrap();
+
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn cached_location_skips_search_request() {
+        let mut server = Server::new();
+        let search = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(LOCATIONS_BODY)
+            .expect(1)
+            .create();
+        let forecast = server
+            .mock("GET", "/forecasts/v1/daily/1day/324505")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(forecast_body(None))
+            .expect(2)
+            .create();
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cache: Arc<dyn LocationCache> =
+            Arc::new(FileLocationCache::new(tmpdir.path().join("locations.json")));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url)
+            .with_location_cache(Some(cache));
+
+        client.get_weather("Kyiv".into(), 0).unwrap();
+        let report = client.get_weather("  kyiv ".into(), 0).unwrap();
+
+        assert_eq!(report.location, "Kyiv, Ukraine");
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn raw_returns_untouched_forecast_body() {
+        let mut server = Server::new();
+        let (search, forecast) = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let body = client.get_weather_raw("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(
+            body["DailyForecasts"][0]["AirAndPollen"][0]["Category"],
+            "Good"
+        );
+        search.assert();
+        forecast.assert();
+    }
+
+    /// Forecast with `count` consecutive days starting at 2024-12-01.
+    fn daily_forecasts_body(count: u32) -> String {
+        let days: Vec<String> = (1..=count)
+            .map(|day| {
+                format!(
+                    r#"{{
+                        "Date": "2024-12-0{day}T07:00:00+02:00",
+                        "Temperature": {{"Minimum": {{"Value": 3.0}}, "Maximum": {{"Value": 12.0}}}},
+                        "Day": {{"Icon": 1, "IconPhrase": "Sunny"}},
+                        "Night": {{"Icon": 33, "IconPhrase": "Clear"}}
+                    }}"#
+                )
+            })
+            .collect();
+
+        format!(r#"{{"DailyForecasts": [{}]}}"#, days.join(","))
+    }
+
+    #[rstest]
+    #[case::today(0, "2024-12-01")]
+    #[case::last_free_day(4, "2024-12-05")]
+    fn picks_requested_day_of_five_day_forecast(#[case] day: u32, #[case] date: &str) {
+        let mut server = Server::new();
+        let (search, forecast) = mock_forecast(&mut server, daily_forecasts_body(5));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), day).unwrap();
+
+        assert_eq!(report.date, date);
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn short_forecast_reports_returned_and_requested_days() {
+        let mut server = Server::new();
+        let (_search, _forecast) = mock_forecast(&mut server, daily_forecasts_body(3));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let err = client.get_weather("Kyiv".into(), 4).unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                WeatherError::ForecastTooShort {
+                    returned: 3,
+                    requested: 5,
+                    provider: Provider::AccuWeather,
+                }
+            ),
+            "unexpected error: {err:?}"
+        );
+        assert_eq!(
+            err.to_string(),
+            "`AccuWeather` returned 3 days forecast (including today), requested 5"
+        );
+    }
+
+    #[test]
+    fn requests_forecast_once_for_range() {
+        let mut server = Server::new();
+        let (search, forecast) = mock_forecast(&mut server, daily_forecasts_body(5));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let location = client.resolve_location("Kyiv").unwrap();
+        let dates: Vec<String> = client
+            .get_weather_range_at(&location, 2..=4)
+            .unwrap()
+            .into_iter()
+            .map(|report| report.date)
+            .collect();
+
+        assert_eq!(dates, ["2024-12-03", "2024-12-04", "2024-12-05"]);
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn range_past_returned_forecast_reports_returned_and_requested_days() {
+        let mut server = Server::new();
+        let (_search, _forecast) = mock_forecast(&mut server, daily_forecasts_body(3));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let location = client.resolve_location("Kyiv").unwrap();
+        let err = client.get_weather_range_at(&location, 1..=4).unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                WeatherError::ForecastTooShort {
+                    returned: 3,
+                    requested: 5,
+                    ..
+                }
+            ),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn resolves_location_once_for_five_day_range() {
+        let mut server = Server::new();
+        let search = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(LOCATIONS_BODY)
+            .expect(1)
+            .create();
+        let forecast = server
+            .mock("GET", Matcher::Regex(FORECAST_PATH.into()))
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(daily_forecasts_body(5))
+            .expect(5)
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let location = client.resolve_location("Kyiv").unwrap();
+        let dates: Vec<String> = (0..5)
+            .map(|day| client.get_weather_at(&location, day).unwrap().date)
+            .collect();
+
+        assert_eq!(
+            location,
+            ResolvedLocation {
+                name: "Kyiv".into(),
+                country: "Ukraine".into(),
+                lat: 50.45,
+                lon: 30.524,
+                provider_key: Some("324505".into()),
+                timezone: Some("Europe/Kiev".into()),
+            }
+        );
+        assert_eq!(
+            dates,
+            [
+                "2024-12-01",
+                "2024-12-02",
+                "2024-12-03",
+                "2024-12-04",
+                "2024-12-05"
+            ]
+        );
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn location_without_key_is_resolved_by_name() {
+        let mut server = Server::new();
+        let (search, forecast) = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+        let location = ResolvedLocation {
+            name: "Kyiv".into(),
+            country: "Ukraine".into(),
+            lat: 50.45,
+            lon: 30.524,
+            provider_key: None,
+            timezone: None,
+        };
+
+        let report = client.get_weather_at(&location, 0).unwrap();
+
+        assert_eq!(report.location, "Kyiv, Ukraine");
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn maps_real_feel_to_feels_like() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(Some((1.0, 9.0))));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.location, "Kyiv, Ukraine");
+        assert_eq!(report.date, "2024-11-29");
+        assert_eq!(report.condition, WeatherCondition::Clear);
+        assert_eq!(report.uv_index, Some(3.0));
+        assert_eq!(report.feels_like, Some(5.0));
+    }
+
+    #[test]
+    fn maps_day_wind() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.wind_kph, Some(13.0));
+        assert_eq!(report.wind_direction.as_deref(), Some("NW"));
+    }
+
+    #[test]
+    fn maps_day_average_humidity() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.humidity, Some(72.0));
+    }
+
+    #[test]
+    fn maps_maximum_to_max_and_minimum_to_min_temperature() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.max_temperature, 12.0);
+        assert_eq!(report.min_temperature, 3.0);
+    }
+
+    #[test]
+    fn computes_avg_temperature_from_range() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.avg_temperature, Some(7.5));
+    }
+
+    #[test]
+    fn maps_sun_rise_and_missing_set() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.sunrise, NaiveTime::from_hms_opt(7, 31, 0));
+        assert_eq!(report.sunset, None);
+    }
+
+    #[test]
+    fn maps_air_quality_when_enabled() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client =
+            AccuWeatherClient::new(Client::new(), "KEY".into(), &url).with_air_quality(true);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(
+            report.air_quality,
+            Some(AirQuality {
+                index: Some(40.0),
+                category: AqiCategory::Good,
+                pm2_5: None,
+                pm10: None,
+            })
+        );
+    }
+
+    #[test]
+    fn air_quality_is_omitted_when_disabled() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.air_quality, None);
+    }
+
+    #[test]
+    fn fetches_and_maps_alerts_when_enabled() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let alerts = server
+            .mock("GET", "/alerts/v1/324505")
+            .match_query(Matcher::UrlEncoded("language".into(), "en".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{
+                    "AlertID": 42,
+                    "Description": {"Localized": "Flood Warning", "English": "Flood Warning"},
+                    "Category": "FLOOD", "Level": null,
+                    "Area": [{"Name": "Kyiv"}, {"Name": "Brovary"}]
+                }]"#,
+            )
+            .expect(1)
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url).with_alerts(true);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        alerts.assert();
+        assert_eq!(
+            report.alerts,
+            Some(vec![WeatherAlert {
+                headline: "Flood Warning".into(),
+                severity: Some("FLOOD".into()),
+                areas: vec!["Kyiv".into(), "Brovary".into()],
+            }])
+        );
+    }
+
+    #[test]
+    fn alerts_are_not_fetched_when_disabled() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let alerts = server
+            .mock("GET", "/alerts/v1/324505")
+            .match_query(Matcher::Any)
+            .expect(0)
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        alerts.assert();
+        assert_eq!(report.alerts, None);
+    }
+
+    #[test]
+    fn maps_current_conditions() {
+        let mut server = Server::new();
+        let _search = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(LOCATIONS_BODY)
+            .create();
+        let current = server
+            .mock("GET", "/currentconditions/v1/324505")
+            .match_query(Matcher::UrlEncoded("details".into(), "true".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{
+                    "LocalObservationDateTime": "2024-11-29T12:15:00+02:00",
+                    "EpochTime": 1732875300, "WeatherText": "Cloudy", "WeatherIcon": 7,
+                    "Temperature": {"Metric": {"Value": 2.5, "Unit": "C"}},
+                    "RealFeelTemperature": {"Metric": {"Value": -0.8, "Unit": "C"}},
+                    "RelativeHumidity": 84,
+                    "Wind": {
+                        "Direction": {"Degrees": 90, "Localized": "E"},
+                        "Speed": {"Metric": {"Value": 10.8, "Unit": "km/h"}}
+                    }
+                }]"#,
+            )
+            .create();
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let conditions = client.get_current("Kyiv").unwrap();
+
+        current.assert();
+        assert_eq!(
+            conditions,
+            CurrentConditions {
+                provider: Provider::AccuWeather,
+                location: "Kyiv, Ukraine".into(),
+                observed_at: "2024-11-29T10:15:00Z".parse().unwrap(),
+                condition: WeatherCondition::from_accuweather_icon(7),
+                description: "Cloudy".into(),
+                temperature: 2.5,
+                feels_like: Some(-0.8),
+                humidity: Some(84.0),
+                wind_kph: Some(10.8),
+                wind_direction: Some("E".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_real_feel_maps_to_none() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(None));
+        let url = base_url(&server);
+        let client = AccuWeatherClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.feels_like, None);
+    }
+
+    #[test]
+    fn sends_language_param_to_every_request() {
+        let mut server = Server::new();
+        let language = Matcher::UrlEncoded("language".into(), "uk".into());
+        let search = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(language.clone())
+            .with_header("content-type", "application/json")
+            .with_body(LOCATIONS_BODY)
+            .create();
+        let forecast = server
+            .mock("GET", "/forecasts/v1/daily/1day/324505")
+            .match_query(language)
+            .with_header("content-type", "application/json")
+            .with_body(forecast_body(None))
+            .create();
+        let url = base_url(&server);
+        let client =
+            AccuWeatherClient::new(Client::new(), "KEY".into(), &url).with_language("uk".into());
+
+        client.get_weather("Kyiv".into(), 0).unwrap();
+
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn supports_five_days() {
+        assert_eq!(
+            AccuWeatherClient::new(Client::new(), "KEY".into(), BASE_URL).max_forecast_days(),
+            5
+        );
+    }
+}
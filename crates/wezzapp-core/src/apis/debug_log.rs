@@ -0,0 +1,131 @@
+use crate::apis::rate_limiter::RateLimitBudget;
+use crate::apis::redact;
+use crate::apis::retry::{RetryPolicy, send_with_retry};
+use crate::error::WeatherError;
+use reqwest::Url;
+use reqwest::blocking::{RequestBuilder, Response};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::{trace, warn};
+
+/// File recording every provider HTTP exchange, for attaching to bug reports.
+///
+/// Each entry has the request URL with API keys redacted, then response status,
+/// headers and body. Writing is best-effort, failures are only logged.
+#[derive(Debug)]
+pub struct DebugLog {
+    file: Mutex<File>,
+}
+
+impl DebugLog {
+    /// Create (or truncate) the log file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self {
+            file: Mutex::new(File::create(path)?),
+        })
+    }
+
+    /// Write the exchange, returning an equivalent response since the body is consumed.
+    fn record(&self, url: Option<Url>, resp: Response) -> Result<Response, WeatherError> {
+        let status = resp.status();
+        let version = resp.version();
+        let headers = resp.headers().clone();
+        let body = resp.bytes()?;
+
+        let mut entry = String::new();
+        if let Some(url) = url {
+            entry.push_str(&format!("> GET {}\n", redact::secrets(url)));
+        }
+        entry.push_str(&format!("< {status}\n"));
+        for (name, value) in &headers {
+            entry.push_str(&format!(
+                "< {name}: {}\n",
+                String::from_utf8_lossy(value.as_bytes())
+            ));
+        }
+        entry.push_str(&format!("\n{}\n\n", String::from_utf8_lossy(&body)));
+
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(err) = file.write_all(entry.as_bytes()) {
+            warn!("Failed to write debug log: {err}");
+        }
+
+        let mut rebuilt = http::Response::new(body);
+        *rebuilt.status_mut() = status;
+        *rebuilt.version_mut() = version;
+        *rebuilt.headers_mut() = headers;
+
+        Ok(rebuilt.into())
+    }
+}
+
+/// Send request with `send_with_retry`, recording the final exchange in `log` if set.
+///
+/// If the request was redirected, the final URL is logged at trace level.
+pub(crate) fn send_logged(
+    request: RequestBuilder,
+    policy: &RetryPolicy,
+    budget: Option<&RateLimitBudget>,
+    log: Option<&DebugLog>,
+) -> Result<Response, WeatherError> {
+    let url = request
+        .try_clone()
+        .and_then(|request| request.build().ok())
+        .map(|request| request.url().clone());
+    let resp = send_with_retry(request, policy, budget)?;
+
+    if url.as_ref().is_some_and(|url| url != resp.url()) {
+        trace!(
+            "Request redirected to {}",
+            redact::secrets(resp.url().clone())
+        );
+    }
+
+    match log {
+        Some(log) => log.record(url, resp),
+        None => Ok(resp),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use reqwest::blocking::Client;
+    use std::fs;
+
+    #[test]
+    fn records_exchange_and_keeps_response_usable() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("x-test", "yes")
+            .with_body(r#"{"ok":true}"#)
+            .create();
+        let tmpdir = tempfile::tempdir().unwrap();
+        let path = tmpdir.path().join("debug.log");
+        let log = DebugLog::create(&path).unwrap();
+        let request = Client::new().get(format!("{}/forecast.json?key=SECRET", server.url()));
+
+        let resp = send_logged(request, &RetryPolicy::none(), None, Some(&log)).unwrap();
+
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.text().unwrap(), r#"{"ok":true}"#);
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(
+            contents.contains(&format!(
+                "> GET {}/forecast.json?key=REDACTED",
+                server.url()
+            )),
+            "{contents}"
+        );
+        assert!(contents.contains("< 200 OK"), "{contents}");
+        assert!(contents.contains("< x-test: yes"), "{contents}");
+        assert!(contents.contains(r#"{"ok":true}"#), "{contents}");
+        assert!(!contents.contains("SECRET"), "{contents}");
+    }
+}
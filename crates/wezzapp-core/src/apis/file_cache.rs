@@ -0,0 +1,98 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Cached value with the time it was stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Entry<V> {
+    pub(crate) value: V,
+    /// Unix timestamp in seconds.
+    pub(crate) cached_at: u64,
+}
+
+impl<V> Entry<V> {
+    /// Seconds since the entry was stored.
+    pub(crate) fn age(&self) -> u64 {
+        now().saturating_sub(self.cached_at)
+    }
+}
+
+/// Current Unix timestamp in seconds.
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs())
+}
+
+/// JSON file of timestamped entries backing the on-disk caches.
+///
+/// Best-effort: a missing or broken file starts empty, failed writes are logged.
+#[derive(Debug)]
+pub(crate) struct FileCache<V> {
+    path: PathBuf,
+    entries: Mutex<HashMap<String, Entry<V>>>,
+}
+
+impl<V> FileCache<V>
+where
+    V: Clone + Serialize + DeserializeOwned,
+{
+    /// Load entries from `path`, starting empty if it's missing or unreadable.
+    pub(crate) fn new(path: PathBuf) -> Self {
+        let entries = fs::read(&path)
+            .ok()
+            .and_then(|contents| match serde_json::from_slice(&contents) {
+                Ok(entries) => Some(entries),
+                Err(err) => {
+                    warn!("Ignoring broken cache {}: {err}", path.display());
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        Self {
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<Entry<V>> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        entries.get(key).cloned()
+    }
+
+    /// Store `value` under `key`, dropping entries older than `max_age` seconds, and save.
+    pub(crate) fn put(&self, key: &str, value: &V, max_age: Option<u64>) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(max_age) = max_age {
+            entries.retain(|_, entry| entry.age() < max_age);
+        }
+        entries.insert(
+            key.to_string(),
+            Entry {
+                value: value.clone(),
+                cached_at: now(),
+            },
+        );
+
+        if let Err(err) = self.save(&entries) {
+            warn!("Failed to save cache {}: {err:#}", self.path.display());
+        }
+    }
+
+    fn save(&self, entries: &HashMap<String, Entry<V>>) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_vec(entries)?)?;
+
+        Ok(())
+    }
+}
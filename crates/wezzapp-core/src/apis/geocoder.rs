@@ -0,0 +1,172 @@
+use crate::apis::debug_log::{DebugLog, send_logged};
+use crate::apis::redact;
+use crate::apis::retry::RetryPolicy;
+use crate::apis::{DEFAULT_LANGUAGE, ResolvedLocation};
+use crate::error::WeatherError;
+use reqwest::Url;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::fmt::Debug;
+use std::sync::Arc;
+use tracing::{debug, trace};
+
+/// Production Open-Meteo geocoding base URL.
+pub(crate) const BASE_URL: &str = "https://geocoding-api.open-meteo.com/v1/";
+
+/// Address to coordinates lookup for providers which only accept lat/lon.
+pub trait Geocoder: Debug + Send + Sync {
+    /// Best match for `address`, `WeatherError::AddressNotFound` if there's none.
+    fn geocode(&self, address: &str) -> Result<ResolvedLocation, WeatherError>;
+}
+
+/// `Geocoder` backed by the free, keyless Open-Meteo geocoding API.
+///
+/// See https://open-meteo.com/en/docs/geocoding-api
+#[derive(Debug)]
+pub struct OpenMeteoGeocoder {
+    url: String,
+    language: String,
+    retry: RetryPolicy,
+    /// Records every HTTP exchange, if set.
+    debug_log: Option<Arc<DebugLog>>,
+    client: Client,
+}
+
+impl OpenMeteoGeocoder {
+    /// Create geocoder sending requests to the given base URL over a shared HTTP `client`.
+    pub(crate) fn new(client: Client, url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            language: DEFAULT_LANGUAGE.to_string(),
+            retry: RetryPolicy::default(),
+            debug_log: None,
+            client,
+        }
+    }
+
+    /// Set language for place and country names.
+    pub(crate) fn with_language(mut self, language: String) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Set how failed requests are retried.
+    pub(crate) fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Record every HTTP exchange in `log`.
+    pub(crate) fn with_debug_log(mut self, log: Option<Arc<DebugLog>>) -> Self {
+        self.debug_log = log;
+        self
+    }
+}
+
+impl Geocoder for OpenMeteoGeocoder {
+    fn geocode(&self, address: &str) -> Result<ResolvedLocation, WeatherError> {
+        debug!("Geocoding address {}", redact::address(address));
+        let mut url = Url::parse(&self.url)?.join("search")?;
+        {
+            let mut qp = url.query_pairs_mut();
+            qp.append_pair("name", address);
+            qp.append_pair("count", "1");
+            qp.append_pair("language", &self.language);
+        }
+        trace!("Open-Meteo geocoding URL: {url:?}");
+
+        let request = self.client.get(url);
+        let resp = send_logged(request, &self.retry, None, self.debug_log.as_deref())?;
+        let body: OpenMeteoSearchResponse = resp.error_for_status()?.json()?;
+        trace!("Open-Meteo geocoding body: {body:?}");
+
+        let place = body
+            .results
+            .into_iter()
+            .next()
+            .ok_or(WeatherError::AddressNotFound)?;
+
+        Ok(ResolvedLocation {
+            name: place.name,
+            country: place.country.unwrap_or_default(),
+            lat: place.latitude,
+            lon: place.longitude,
+            provider_key: None,
+            timezone: place.timezone,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoSearchResponse {
+    /// Missing altogether when nothing matches.
+    #[serde(default)]
+    results: Vec<OpenMeteoPlaceResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoPlaceResponse {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    /// Missing for places outside any country, e.g. in the ocean.
+    country: Option<String>,
+    timezone: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    #[test]
+    fn resolves_first_match() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/search")
+            .match_query(Matcher::Regex("^name=Kyiv&count=1&language=uk$".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"results": [{
+                    "name": "Київ", "latitude": 50.45466, "longitude": 30.5238, "country": "Україна",
+                    "timezone": "Europe/Kyiv"
+                }]}"#,
+            )
+            .create();
+        let geocoder = OpenMeteoGeocoder::new(Client::new(), format!("{}/", server.url()))
+            .with_language("uk".into());
+
+        let location = geocoder.geocode("Kyiv").unwrap();
+
+        assert_eq!(
+            location,
+            ResolvedLocation {
+                name: "Київ".into(),
+                country: "Україна".into(),
+                lat: 50.45466,
+                lon: 30.5238,
+                provider_key: None,
+                timezone: Some("Europe/Kyiv".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn no_results_returns_address_not_found() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/search")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"generationtime_ms": 0.5}"#)
+            .create();
+        let geocoder = OpenMeteoGeocoder::new(Client::new(), format!("{}/", server.url()));
+
+        let err = geocoder.geocode("Nowhere").unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::AddressNotFound),
+            "unexpected error: {err:?}"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use crate::apis::ResolvedLocation;
+use crate::apis::file_cache::FileCache;
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::debug;
+
+/// How long cached locations stay valid by default, provider location ids rarely change.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// Cache of geocoded locations, so repeated addresses skip the location lookup call.
+///
+/// Best-effort: implementations swallow their own errors, a failed cache only costs quota.
+pub trait LocationCache: Debug + Send + Sync {
+    fn get(&self, key: &str) -> Option<ResolvedLocation>;
+
+    fn put(&self, key: &str, location: &ResolvedLocation);
+}
+
+/// Normalize address for use in cache keys: trimmed, lowercase, single spaces.
+pub(crate) fn normalize(address: &str) -> String {
+    address
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// JSON-file-based `LocationCache`.
+#[derive(Debug)]
+pub struct FileLocationCache {
+    cache: FileCache<ResolvedLocation>,
+    ttl: Duration,
+    /// Ignore cached entries, still storing fresh lookups.
+    refresh: bool,
+}
+
+impl FileLocationCache {
+    /// Load cache from `path`, starting empty if it's missing or unreadable.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            cache: FileCache::new(path.into()),
+            ttl: DEFAULT_TTL,
+            refresh: false,
+        }
+    }
+
+    /// Set how long entries stay valid.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Bypass cached entries (e.g. `--refresh-location`), lookups still refresh the cache.
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+}
+
+impl LocationCache for FileLocationCache {
+    fn get(&self, key: &str) -> Option<ResolvedLocation> {
+        if self.refresh {
+            return None;
+        }
+
+        let entry = self.cache.get(key)?;
+        if entry.age() >= self.ttl.as_secs() {
+            debug!("Cached location expired");
+            return None;
+        }
+        debug!("Using cached location");
+
+        Some(entry.value)
+    }
+
+    fn put(&self, key: &str, location: &ResolvedLocation) {
+        self.cache.put(key, location, None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn kyiv() -> ResolvedLocation {
+        ResolvedLocation {
+            name: "Kyiv".into(),
+            country: "Ukraine".into(),
+            lat: 50.45,
+            lon: 30.524,
+            provider_key: Some("324505".into()),
+            timezone: None,
+        }
+    }
+
+    fn cache_path(tmpdir: &TempDir) -> PathBuf {
+        tmpdir.path().join("cache").join("locations.json")
+    }
+
+    #[test]
+    fn normalizes_case_and_whitespace() {
+        assert_eq!(normalize("  Kyiv,   UKRAINE \t"), "kyiv, ukraine");
+    }
+
+    #[test]
+    fn entries_persist_across_reloads() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        FileLocationCache::new(cache_path(&tmpdir)).put("kyiv", &kyiv());
+
+        let cache = FileLocationCache::new(cache_path(&tmpdir));
+
+        assert_eq!(cache.get("kyiv"), Some(kyiv()));
+        assert_eq!(cache.get("lviv"), None);
+    }
+
+    #[test]
+    fn expired_entries_are_ignored() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cache = FileLocationCache::new(cache_path(&tmpdir)).with_ttl(Duration::ZERO);
+
+        cache.put("kyiv", &kyiv());
+
+        assert_eq!(cache.get("kyiv"), None);
+    }
+
+    #[test]
+    fn refresh_bypasses_entries_but_still_stores_them() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cache = FileLocationCache::new(cache_path(&tmpdir)).with_refresh(true);
+
+        cache.put("kyiv", &kyiv());
+
+        assert_eq!(cache.get("kyiv"), None);
+        assert_eq!(
+            FileLocationCache::new(cache_path(&tmpdir)).get("kyiv"),
+            Some(kyiv())
+        );
+    }
+
+    #[test]
+    fn broken_file_starts_empty() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(cache_path(&tmpdir).parent().unwrap()).unwrap();
+        fs::write(cache_path(&tmpdir), "not json").unwrap();
+
+        assert_eq!(
+            FileLocationCache::new(cache_path(&tmpdir)).get("kyiv"),
+            None
+        );
+    }
+}
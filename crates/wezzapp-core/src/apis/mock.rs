@@ -0,0 +1,155 @@
+use crate::apis::redact;
+use crate::apis::{CurrentConditions, ProviderClient, ResolvedLocation, WeatherReport};
+use crate::condition::WeatherCondition;
+use crate::error::WeatherError;
+use crate::provider::Provider;
+use chrono::{Days, Local, NaiveTime, Utc};
+use serde_json::Value;
+use tracing::debug;
+
+/// Conditions picked from for canned reports.
+const CONDITIONS: &[(WeatherCondition, &str)] = &[
+    (WeatherCondition::Clear, "Sunny"),
+    (WeatherCondition::PartlyCloudy, "Partly cloudy"),
+    (WeatherCondition::Cloudy, "Overcast"),
+    (WeatherCondition::Fog, "Fog"),
+    (WeatherCondition::Drizzle, "Light drizzle"),
+    (WeatherCondition::Rain, "Moderate rain"),
+    (WeatherCondition::Snow, "Light snow"),
+    (WeatherCondition::Thunderstorm, "Thundery outbreaks"),
+];
+
+/// Offline client returning canned reports derived from the address, for demos and tests.
+///
+/// Same address and day always yield the same report.
+pub(crate) struct MockClient;
+
+impl MockClient {
+    /// FNV-1a, stable across platforms and Rust versions unlike `DefaultHasher`.
+    fn hash(address: &str, day_from_today: u32) -> u64 {
+        address
+            .to_lowercase()
+            .bytes()
+            .chain(day_from_today.to_le_bytes())
+            .fold(0xcbf29ce484222325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+            })
+    }
+
+    fn report(address: String, day_from_today: u32) -> WeatherReport {
+        let hash = Self::hash(&address, day_from_today);
+        let (condition, description) = CONDITIONS[(hash % CONDITIONS.len() as u64) as usize];
+        let max_temperature = ((hash >> 8) % 40) as f64 - 10.0;
+        let spread = ((hash >> 16) % 12) as f64;
+        let date = Local::now().date_naive() + Days::new(day_from_today.into());
+
+        WeatherReport {
+            provider: Provider::Mock,
+            date: date.to_string(),
+            location: address,
+            condition,
+            description: description.to_string(),
+            max_temperature,
+            min_temperature: max_temperature - spread,
+            avg_temperature: Some(max_temperature - spread / 2.0),
+            feels_like: None,
+            uv_index: Some(((hash >> 24) % 11) as f64),
+            wind_kph: None,
+            wind_direction: None,
+            humidity: None,
+            sunrise: NaiveTime::from_hms_opt(6, ((hash >> 32) % 60) as u32, 0),
+            sunset: NaiveTime::from_hms_opt(18, ((hash >> 40) % 60) as u32, 0),
+            air_quality: None,
+            alerts: None,
+            stale_since: None,
+        }
+    }
+}
+
+impl ProviderClient for MockClient {
+    fn get_weather(
+        &self,
+        address: String,
+        day_from_today: u32,
+    ) -> Result<WeatherReport, WeatherError> {
+        debug!(
+            "Getting mock weather for address {} day from today: {day_from_today}",
+            redact::address(&address)
+        );
+
+        Ok(Self::report(address, day_from_today))
+    }
+
+    fn resolve_location(&self, address: &str) -> Result<ResolvedLocation, WeatherError> {
+        let hash = Self::hash(address, 0);
+
+        Ok(ResolvedLocation {
+            name: address.to_string(),
+            country: String::new(),
+            lat: (hash % 180) as f64 - 90.0,
+            lon: ((hash >> 8) % 360) as f64 - 180.0,
+            provider_key: None,
+            timezone: None,
+        })
+    }
+
+    fn get_weather_raw(&self, address: String, day_from_today: u32) -> Result<Value, WeatherError> {
+        let report = Self::report(address, day_from_today);
+
+        serde_json::to_value(report)
+            .map_err(|err| WeatherError::UnexpectedResponse(err.to_string()))
+    }
+
+    fn get_current(&self, address: &str) -> Result<CurrentConditions, WeatherError> {
+        debug!(
+            "Getting mock current conditions for address {}",
+            redact::address(address)
+        );
+        let report = Self::report(address.to_string(), 0);
+
+        Ok(CurrentConditions {
+            provider: Provider::Mock,
+            location: report.location,
+            observed_at: Utc::now(),
+            condition: report.condition,
+            description: report.description,
+            temperature: report.avg_temperature.unwrap_or(report.max_temperature),
+            feels_like: None,
+            humidity: None,
+            wind_kph: None,
+            wind_direction: None,
+        })
+    }
+
+    fn max_forecast_days(&self) -> u32 {
+        14
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_address_yields_same_report() {
+        let first = MockClient.get_weather("Kyiv, Ukraine".into(), 1).unwrap();
+        let second = MockClient.get_weather("Kyiv, Ukraine".into(), 1).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(first.provider, Provider::Mock);
+        assert!(first.min_temperature <= first.max_temperature);
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        // Pinned, so canned demo output doesn't change between releases.
+        assert_eq!(MockClient::hash("kyiv", 0), 0xecb42112a2bf5122);
+        assert_eq!(
+            MockClient::hash("Kyiv", 0),
+            MockClient::hash("kyiv", 0),
+            "case shouldn't matter"
+        );
+        assert_ne!(MockClient::hash("Kyiv", 0), MockClient::hash("Lviv", 0));
+        assert_ne!(MockClient::hash("Kyiv", 0), MockClient::hash("Kyiv", 1));
+    }
+}
@@ -1,47 +1,326 @@
+use crate::air_quality::AirQuality;
+use crate::alert::WeatherAlert;
 use crate::apis::accu_weather::AccuWeatherClient;
+use crate::apis::debug_log::{DebugLog, send_logged};
+use crate::apis::geocoder::{Geocoder, OpenMeteoGeocoder};
+use crate::apis::location_cache::LocationCache;
+use crate::apis::quota::QuotaObserver;
+use crate::apis::rate_limiter::{RateLimit, RateLimitBudget};
+use crate::apis::retry::RetryPolicy;
+use crate::apis::tomorrow_io::TomorrowIoClient;
 use crate::apis::weather_api::WeatherApiClient;
+use crate::condition::WeatherCondition;
 use crate::credentials::Credentials;
+use crate::error::WeatherError;
 use crate::provider::Provider;
-use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveTime, Utc};
+use reqwest::blocking::{Client, Response};
+use reqwest::redirect::Policy;
+use reqwest::{Certificate, NoProxy, Proxy, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::mem;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
 
 mod accu_weather;
+pub mod debug_log;
+mod file_cache;
+pub mod geocoder;
+pub mod location_cache;
+#[cfg(feature = "mock")]
+mod mock;
+pub mod quota;
+pub mod rate_limiter;
+pub mod redact;
+pub mod report_cache;
+mod request_span;
+pub mod retry;
+mod tomorrow_io;
 mod weather_api;
 
+/// Language for provider condition text when none is configured.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// `User-Agent` sent to providers unless overridden with `with_user_agent`.
+pub const USER_AGENT: &str = concat!("wezzapp/", env!("CARGO_PKG_VERSION"));
+
 /// Result of a weather query, in a UI-friendly form.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WeatherReport {
     pub provider: Provider,
     pub date: String,
     pub location: String,
+    /// Normalized condition for programmatic use.
+    pub condition: WeatherCondition,
+    /// Human-readable condition text as returned by provider.
     pub description: String,
     pub max_temperature: f64,
     pub min_temperature: f64,
+    /// Average temperature for the day. Providers without one (AccuWeather) report the
+    /// midpoint of min and max.
+    pub avg_temperature: Option<f64>,
+    /// Apparent ("feels like") temperature, if provider returns it.
+    pub feels_like: Option<f64>,
+    /// UV index, if provider returns it.
+    pub uv_index: Option<f64>,
+    /// Daytime wind speed in km/h (WeatherAPI reports the day's maximum).
+    pub wind_kph: Option<f64>,
+    /// Compass direction of daytime wind, e.g. "NW". WeatherAPI has none in the day summary.
+    pub wind_direction: Option<String>,
+    /// Average relative humidity in percent, if provider returns it.
+    pub humidity: Option<f64>,
+    /// Local sunrise time, `None` if provider omits it or the sun doesn't rise (polar night).
+    pub sunrise: Option<NaiveTime>,
+    /// Local sunset time, `None` if provider omits it or the sun doesn't set (polar day).
+    pub sunset: Option<NaiveTime>,
+    /// Air quality, only filled in when requested from the factory.
+    pub air_quality: Option<AirQuality>,
+    /// Active weather alerts, only filled in when requested from the factory
+    /// and supported by the provider. Empty if there are none.
+    pub alerts: Option<Vec<WeatherAlert>>,
+    /// When a cached copy served instead of a failed live fetch was fetched,
+    /// `None` for live reports.
+    pub stale_since: Option<DateTime<Utc>>,
+}
+
+/// Live conditions at observation time, as opposed to the daily forecast of `WeatherReport`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrentConditions {
+    pub provider: Provider,
+    pub location: String,
+    /// When the provider last observed the conditions.
+    pub observed_at: DateTime<Utc>,
+    /// Normalized condition for programmatic use.
+    pub condition: WeatherCondition,
+    /// Human-readable condition text as returned by provider.
+    pub description: String,
+    /// Actual temperature in °C.
+    pub temperature: f64,
+    /// Apparent ("feels like") temperature, if provider returns it.
+    pub feels_like: Option<f64>,
+    /// Relative humidity in percent, if provider returns it.
+    pub humidity: Option<f64>,
+    /// Wind speed in km/h, if provider returns it.
+    pub wind_kph: Option<f64>,
+    /// Compass direction of the wind, e.g. "NW".
+    pub wind_direction: Option<String>,
+}
+
+impl WeatherReport {
+    /// Whole minutes since a stale report was fetched, `None` for live reports.
+    pub fn stale_minutes(&self) -> Option<i64> {
+        self.stale_since
+            .map(|since| (Utc::now() - since).num_minutes().max(0))
+    }
+}
+
+/// Plain labeled multi-line summary, temperatures in °C as returned by provider.
+impl fmt::Display for WeatherReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Location: {}", self.location)?;
+        writeln!(f, "Date: {}", self.date)?;
+        writeln!(f, "Provider: {}", self.provider.as_str())?;
+        writeln!(f, "Conditions: {}", self.description)?;
+        write!(
+            f,
+            "Temperature: max {}°C, min {}°C",
+            self.max_temperature, self.min_temperature
+        )?;
+        if let Some(avg_temperature) = self.avg_temperature {
+            write!(f, ", avg {avg_temperature}°C")?;
+        }
+        if let Some(feels_like) = self.feels_like {
+            write!(f, "\nFeels like: {feels_like}°C")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Geocoded location, resolved once and reused for multiple forecast requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedLocation {
+    pub name: String,
+    pub country: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Provider-specific location id (e.g. AccuWeather location key).
+    ///
+    /// Only set by providers which need it for forecast requests.
+    pub provider_key: Option<String>,
+    /// IANA timezone of the location, e.g. "Europe/Kyiv", if the provider returns it.
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+impl ResolvedLocation {
+    /// Human-readable "<name>, <country>" form, also usable as an address.
+    pub fn display_name(&self) -> String {
+        if self.country.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}, {}", self.name, self.country)
+        }
+    }
 }
 
 /// abstraction over weather API client
-pub trait ProviderClient {
-    fn get_weather(&self, address: String, days: u32) -> Result<WeatherReport>;
+///
+/// `Send`, so independent requests can be fetched on separate threads.
+pub trait ProviderClient: Send {
+    fn get_weather(&self, address: String, days: u32) -> Result<WeatherReport, WeatherError>;
+
+    /// Geocode address, so it can be reused with `get_weather_at`.
+    fn resolve_location(&self, address: &str) -> Result<ResolvedLocation, WeatherError>;
+
+    /// Get weather for an already resolved location.
+    ///
+    /// Falls back to the address-based path, providers override it to skip geocoding.
+    fn get_weather_at(
+        &self,
+        location: &ResolvedLocation,
+        days: u32,
+    ) -> Result<WeatherReport, WeatherError> {
+        self.get_weather(location.display_name(), days)
+    }
+
+    /// Get weather for every day of `days` at an already resolved location, one report per day.
+    ///
+    /// Calls `get_weather_at` per day, providers returning the whole forecast at once override
+    /// it to request it only once.
+    fn get_weather_range_at(
+        &self,
+        location: &ResolvedLocation,
+        days: RangeInclusive<u32>,
+    ) -> Result<Vec<WeatherReport>, WeatherError> {
+        days.map(|days| self.get_weather_at(location, days))
+            .collect()
+    }
+
+    /// Get provider's forecast response as is, without mapping it to `WeatherReport`.
+    ///
+    /// Useful for debugging and for fields wezzapp doesn't map yet.
+    fn get_weather_raw(&self, address: String, days: u32) -> Result<Value, WeatherError>;
+
+    /// Get real-time conditions instead of the daily forecast.
+    fn get_current(&self, address: &str) -> Result<CurrentConditions, WeatherError>;
+
+    /// Max number of forecast days supported (including today).
+    fn max_forecast_days(&self) -> u32;
+
+    /// Check credentials are accepted with the cheapest authenticated request available.
+    ///
+    /// Uses one call of provider quota. Fails with `WeatherError::InvalidApiKey` if the key
+    /// is rejected and `WeatherError::ProviderUnavailable` on a 5xx response, so an outage isn't
+    /// mistaken for bad credentials. Defaults to a forecast for `VERIFY_ADDRESS`, clients with
+    /// a cheaper request override it.
+    fn ping(&self) -> Result<(), WeatherError> {
+        self.get_weather(VERIFY_ADDRESS.to_string(), 0).map(|_| ())
+    }
 }
 
+/// Address looked up by `ProviderClient::ping`, known to every provider.
+pub const VERIFY_ADDRESS: &str = "London, United Kingdom";
+
 /// Factory that returns a client for the given provider & credentials.
 ///
 /// This is where you can hide the mapping:
 ///   Provider::WeatherApi   -> WeatherApiClient
 ///   Provider::AccuWeather  -> AccuWeatherClient
+///   Provider::TomorrowIo   -> TomorrowIoClient
 pub trait ProviderClientFactory {
     fn create_client(
         &self,
         provider: Provider,
         credentials: Credentials,
-    ) -> Result<Box<dyn ProviderClient>>;
+    ) -> Result<Box<dyn ProviderClient>, WeatherError>;
+
+    /// Check the provider can be reached at all, without credentials or quota.
+    ///
+    /// Factories not talking to the network have nothing to probe.
+    fn probe_reachability(&self, _provider: Provider) -> Result<(), WeatherError> {
+        Ok(())
+    }
+
+    /// Options shaping the reports of created clients, e.g. `lang=uk&aqi`.
+    ///
+    /// Part of report cache keys, so a report fetched with other options isn't served.
+    fn request_options(&self) -> String {
+        String::new()
+    }
 }
 
 #[derive(Debug)]
-pub struct HttpProviderClientFactory;
+pub struct HttpProviderClientFactory {
+    /// Per-provider HTTP request budgets, shared by all clients created by this factory.
+    rate_limits: HashMap<Provider, Arc<RateLimitBudget>>,
+    /// Language code for localized condition text.
+    language: String,
+    /// Additional query pairs passed to providers as is.
+    extra_params: Vec<(String, String)>,
+    /// Whether to request air quality data.
+    air_quality: bool,
+    /// Whether to request weather alerts.
+    alerts: bool,
+    /// Notified about quota reported in provider response headers.
+    quota_observer: Option<Arc<dyn QuotaObserver>>,
+    /// Geocoding cache for providers needing a separate location lookup.
+    location_cache: Option<Arc<dyn LocationCache>>,
+    /// Max number of AccuWeather location search candidates.
+    accuweather_search_limit: Option<u32>,
+    /// Search AccuWeather cities only.
+    accuweather_cities_only: bool,
+    /// Records provider HTTP exchanges for debugging.
+    debug_log: Option<Arc<DebugLog>>,
+    retry: RetryPolicy,
+    /// Per-provider base URL overrides (e.g. proxies or mock servers).
+    base_urls: HashMap<Provider, String>,
+    /// HTTP client shared by all created provider clients.
+    http: Client,
+    /// Address lookup for providers which only accept coordinates.
+    geocoder: Arc<dyn Geocoder>,
+}
 
 impl HttpProviderClientFactory {
+    /// Create factory with default settings.
     pub fn new() -> Self {
-        Self {}
+        let http = Client::builder()
+            .user_agent(USER_AGENT)
+            .build()
+            .expect("default HTTP client must build");
+
+        Self {
+            rate_limits: rate_budgets(&HashMap::new()),
+            language: DEFAULT_LANGUAGE.to_string(),
+            extra_params: Vec::new(),
+            air_quality: false,
+            alerts: false,
+            quota_observer: None,
+            location_cache: None,
+            accuweather_search_limit: None,
+            accuweather_cities_only: false,
+            debug_log: None,
+            retry: RetryPolicy::default(),
+            base_urls: HashMap::new(),
+            geocoder: Arc::new(OpenMeteoGeocoder::new(http.clone(), geocoder::BASE_URL)),
+            http,
+        }
+    }
+
+    /// Start configuring a factory with custom options.
+    pub fn builder() -> HttpProviderClientFactoryBuilder {
+        HttpProviderClientFactoryBuilder::default()
+    }
+
+    fn base_url<'a>(&'a self, provider: Provider, default: &'a str) -> &'a str {
+        self.base_urls
+            .get(&provider)
+            .map_or(default, String::as_str)
     }
 }
 
@@ -56,17 +335,1097 @@ impl ProviderClientFactory for HttpProviderClientFactory {
         &self,
         provider: Provider,
         credentials: Credentials,
-    ) -> Result<Box<dyn ProviderClient>> {
-        match (provider, credentials) {
-            (Provider::WeatherApi, Credentials::WeatherApi { api_key }) => {
-                Ok(Box::new(WeatherApiClient::new(api_key)))
-            }
-            (Provider::AccuWeather, Credentials::AccuWeather { api_key }) => {
-                Ok(Box::new(AccuWeatherClient::new(api_key)))
+    ) -> Result<Box<dyn ProviderClient>, WeatherError> {
+        let rate_limit = self.rate_limits.get(&provider).cloned();
+
+        let client: Box<dyn ProviderClient> = match (provider, credentials) {
+            (Provider::WeatherApi, Credentials::WeatherApi { api_key }) => Box::new(
+                WeatherApiClient::new(
+                    self.http.clone(),
+                    api_key,
+                    self.base_url(provider, weather_api::BASE_URL),
+                )
+                .with_language(self.language.clone())
+                .with_extra_params(self.extra_params.clone())
+                .with_air_quality(self.air_quality)
+                .with_alerts(self.alerts)
+                .with_quota_observer(self.quota_observer.clone())
+                .with_rate_limit(rate_limit)
+                .with_debug_log(self.debug_log.clone())
+                .with_retry_policy(self.retry),
+            ),
+            (Provider::AccuWeather, Credentials::AccuWeather { api_key, base_url }) => Box::new(
+                AccuWeatherClient::new(
+                    self.http.clone(),
+                    api_key,
+                    match base_url {
+                        // `Url::join` would drop the last path segment without it.
+                        Some(url) if !url.ends_with('/') => format!("{url}/"),
+                        Some(url) => url,
+                        None => self.base_url(provider, accu_weather::BASE_URL).to_string(),
+                    },
+                )
+                .with_language(self.language.clone())
+                .with_extra_params(self.extra_params.clone())
+                .with_air_quality(self.air_quality)
+                .with_alerts(self.alerts)
+                .with_quota_observer(self.quota_observer.clone())
+                .with_location_cache(self.location_cache.clone())
+                .with_search_limit(self.accuweather_search_limit)
+                .with_cities_only(self.accuweather_cities_only)
+                .with_rate_limit(rate_limit)
+                .with_debug_log(self.debug_log.clone())
+                .with_retry_policy(self.retry),
+            ),
+            (Provider::TomorrowIo, Credentials::TomorrowIo { api_key }) => Box::new(
+                TomorrowIoClient::new(
+                    self.http.clone(),
+                    api_key,
+                    self.base_url(provider, tomorrow_io::BASE_URL),
+                    self.geocoder.clone(),
+                )
+                .with_language(self.language.clone())
+                .with_extra_params(self.extra_params.clone())
+                .with_location_cache(self.location_cache.clone())
+                .with_rate_limit(rate_limit)
+                .with_debug_log(self.debug_log.clone())
+                .with_retry_policy(self.retry),
+            ),
+            #[cfg(feature = "mock")]
+            (Provider::Mock, Credentials::Mock) => Box::new(mock::MockClient),
+            _ => return Err(WeatherError::CredentialsMismatch(provider)),
+        };
+
+        Ok(client)
+    }
+
+    fn probe_reachability(&self, provider: Provider) -> Result<(), WeatherError> {
+        let url = match provider {
+            Provider::WeatherApi => self.base_url(provider, weather_api::BASE_URL),
+            Provider::AccuWeather => self.base_url(provider, accu_weather::BASE_URL),
+            Provider::TomorrowIo => self.base_url(provider, tomorrow_io::BASE_URL),
+            #[cfg(feature = "mock")]
+            Provider::Mock => return Ok(()),
+        };
+        debug!("Probing {provider:?} at {url}");
+
+        // Any response, even an error status for the keyless request, means it's reachable.
+        send_logged(
+            self.http.head(url),
+            &self.retry,
+            None,
+            self.debug_log.as_deref(),
+        )?;
+
+        Ok(())
+    }
+
+    fn request_options(&self) -> String {
+        let mut options = vec![format!("lang={}", self.language)];
+        if self.air_quality {
+            options.push("aqi".to_string());
+        }
+        if self.alerts {
+            options.push("alerts".to_string());
+        }
+        options.extend(
+            self.extra_params
+                .iter()
+                .map(|(key, value)| format!("{key}={value}")),
+        );
+
+        options.join("&")
+    }
+}
+
+/// Budgets for every provider with a rate limit, `Provider::default_rate_limit` unless
+/// overridden (`None` meaning no limit).
+fn rate_budgets(
+    overrides: &HashMap<Provider, Option<RateLimit>>,
+) -> HashMap<Provider, Arc<RateLimitBudget>> {
+    Provider::ALL
+        .iter()
+        .filter_map(|&provider| {
+            let limit = overrides
+                .get(&provider)
+                .copied()
+                .unwrap_or_else(|| provider.default_rate_limit())?;
+            Some((provider, Arc::new(RateLimitBudget::new(limit))))
+        })
+        .collect()
+}
+
+/// Fluent builder for `HttpProviderClientFactory`.
+#[derive(Debug, Default)]
+pub struct HttpProviderClientFactoryBuilder {
+    /// Overrides of `Provider::default_rate_limit`.
+    rate_limits: HashMap<Provider, Option<RateLimit>>,
+    language: Option<String>,
+    extra_params: Vec<(String, String)>,
+    air_quality: bool,
+    alerts: bool,
+    quota_observer: Option<Arc<dyn QuotaObserver>>,
+    location_cache: Option<Arc<dyn LocationCache>>,
+    accuweather_search_limit: Option<u32>,
+    accuweather_cities_only: bool,
+    debug_log: Option<Arc<DebugLog>>,
+    retry: RetryPolicy,
+    base_urls: HashMap<Provider, String>,
+    geocoder: Option<Arc<dyn Geocoder>>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    root_certificates: Vec<Certificate>,
+    danger_accept_invalid_certs: bool,
+    redirect_policy: Option<Policy>,
+    user_agent: Option<String>,
+    http_client: Option<Client>,
+}
+
+impl HttpProviderClientFactoryBuilder {
+    /// Set total timeout for a single HTTP request, from connecting to reading the body.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set timeout for establishing a connection, e.g. shorter than `with_timeout`
+    /// to fail fast on flaky networks while still waiting for slow providers.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Send all provider requests through an HTTP(S) proxy, e.g. "http://127.0.0.1:3128".
+    ///
+    /// Without it, `HTTP_PROXY`/`HTTPS_PROXY` environment variables are honored.
+    /// Hosts listed in `NO_PROXY` bypass the proxy either way.
+    pub fn with_proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Trust `certificate` as a root CA in addition to the system ones, e.g. the corporate CA
+    /// of a TLS-intercepting proxy. Call repeatedly to add several.
+    pub fn with_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Skip TLS certificate validation entirely, for testing only.
+    ///
+    /// Any server can then impersonate a provider and read the API keys. A warning is logged
+    /// when the factory is built with it.
+    pub fn with_danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+
+    /// Set how HTTP redirects are followed, e.g. `Policy::none()` to fail on them.
+    ///
+    /// Defaults to reqwest's policy of following up to 10 redirects.
+    pub fn with_redirect_policy(mut self, policy: Policy) -> Self {
+        self.redirect_policy = Some(policy);
+        self
+    }
+
+    /// Send `user_agent` instead of `USER_AGENT`, e.g. to identify an app embedding this crate.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Send all requests through `client` instead of one built by the factory,
+    /// e.g. to share a connection pool, TLS roots or instrumentation with the rest of an app.
+    ///
+    /// Timeouts, proxy, root certificates, redirect policy and user agent can't be set on this
+    /// builder then, `build` fails with `WeatherError::IgnoredHttpSettings`. Configure them on
+    /// `client` instead.
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Set how failed provider requests are retried.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Request condition text in the given language (e.g. "uk").
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Append provider-specific query pairs (e.g. `aqi=yes`) to forecast requests.
+    ///
+    /// Pairs are sent verbatim after the standard ones, duplicates included,
+    /// so unknown params are up to the provider to accept or ignore.
+    pub fn with_extra_params(mut self, extra_params: Vec<(String, String)>) -> Self {
+        self.extra_params = extra_params;
+        self
+    }
+
+    /// Consider at most `limit` AccuWeather location search candidates.
+    ///
+    /// The top-ranked candidate is used, unless another one is named exactly like the address.
+    pub fn with_accuweather_search_limit(mut self, limit: u32) -> Self {
+        self.accuweather_search_limit = Some(limit);
+        self
+    }
+
+    /// Resolve AccuWeather addresses with the cities search, skipping points of interest
+    /// and postal codes.
+    pub fn with_accuweather_cities_only(mut self, cities_only: bool) -> Self {
+        self.accuweather_cities_only = cities_only;
+        self
+    }
+
+    /// Request air quality data, off by default to conserve provider quota.
+    pub fn with_air_quality(mut self, air_quality: bool) -> Self {
+        self.air_quality = air_quality;
+        self
+    }
+
+    /// Request active weather alerts, off by default as AccuWeather needs an extra call.
+    ///
+    /// Tomorrow.io has no alerts, its reports keep `alerts` unset.
+    pub fn with_alerts(mut self, alerts: bool) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Report quota found in provider response headers to `observer`.
+    pub fn with_quota_observer(mut self, observer: Arc<dyn QuotaObserver>) -> Self {
+        self.quota_observer = Some(observer);
+        self
+    }
+
+    /// Cache location lookups (AccuWeather location keys, geocoded coordinates) in `cache`.
+    pub fn with_location_cache(mut self, cache: Arc<dyn LocationCache>) -> Self {
+        self.location_cache = Some(cache);
+        self
+    }
+
+    /// Resolve addresses for coordinate-only providers (Tomorrow.io) with `geocoder`.
+    ///
+    /// Defaults to the Open-Meteo geocoding API, which needs no key.
+    pub fn with_geocoder(mut self, geocoder: Arc<dyn Geocoder>) -> Self {
+        self.geocoder = Some(geocoder);
+        self
+    }
+
+    /// Record every provider HTTP exchange in `log`, API keys redacted.
+    pub fn with_debug_log(mut self, log: Arc<DebugLog>) -> Self {
+        self.debug_log = Some(log);
+        self
+    }
+
+    /// Limit HTTP requests sent by clients for `provider`, replacing its
+    /// `Provider::default_rate_limit`.
+    pub fn with_rate_limit(mut self, provider: Provider, limit: RateLimit) -> Self {
+        self.rate_limits.insert(provider, Some(limit));
+        self
+    }
+
+    /// Don't limit requests to `provider` client-side, e.g. on a paid plan.
+    pub fn without_rate_limit(mut self, provider: Provider) -> Self {
+        self.rate_limits.insert(provider, None);
+        self
+    }
+
+    /// Send requests for `provider` to a custom base URL instead of the public API.
+    pub fn with_base_url(mut self, provider: Provider, url: impl Into<String>) -> Self {
+        self.base_urls.insert(provider, url.into());
+        self
+    }
+
+    /// Build the factory, failing if the HTTP client can't be initialized.
+    ///
+    /// The HTTP client is built once here, unless given with `with_http_client`, and shared
+    /// by all created provider clients, so they reuse its connection pool.
+    pub fn build(mut self) -> Result<HttpProviderClientFactory, WeatherError> {
+        let http = match self.http_client.take() {
+            Some(http) => {
+                let ignored = self.http_settings();
+                if !ignored.is_empty() {
+                    return Err(WeatherError::IgnoredHttpSettings(ignored));
+                }
+                http
             }
-            _ => Err(anyhow!(
-                "credentials type does not match provider: {provider:?}"
+            None => self.build_http()?,
+        };
+        let language = self
+            .language
+            .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+        let geocoder = self.geocoder.unwrap_or_else(|| {
+            Arc::new(
+                OpenMeteoGeocoder::new(http.clone(), geocoder::BASE_URL)
+                    .with_language(language.clone())
+                    .with_retry_policy(self.retry)
+                    .with_debug_log(self.debug_log.clone()),
+            )
+        });
+
+        Ok(HttpProviderClientFactory {
+            rate_limits: rate_budgets(&self.rate_limits),
+            language,
+            extra_params: self.extra_params,
+            air_quality: self.air_quality,
+            alerts: self.alerts,
+            quota_observer: self.quota_observer,
+            location_cache: self.location_cache,
+            accuweather_search_limit: self.accuweather_search_limit,
+            accuweather_cities_only: self.accuweather_cities_only,
+            debug_log: self.debug_log,
+            retry: self.retry,
+            base_urls: self.base_urls,
+            http,
+            geocoder,
+        })
+    }
+
+    /// Setters of HTTP client settings that were called, e.g. `with_proxy`.
+    fn http_settings(&self) -> Vec<&'static str> {
+        [
+            (self.timeout.is_some(), "`with_timeout`"),
+            (self.connect_timeout.is_some(), "`with_connect_timeout`"),
+            (self.proxy.is_some(), "`with_proxy`"),
+            (
+                !self.root_certificates.is_empty(),
+                "`with_root_certificate`",
+            ),
+            (
+                self.danger_accept_invalid_certs,
+                "`with_danger_accept_invalid_certs`",
+            ),
+            (self.redirect_policy.is_some(), "`with_redirect_policy`"),
+            (self.user_agent.is_some(), "`with_user_agent`"),
+        ]
+        .into_iter()
+        .filter_map(|(set, setter)| set.then_some(setter))
+        .collect()
+    }
+
+    fn build_http(&mut self) -> Result<Client, WeatherError> {
+        let mut http =
+            Client::builder().user_agent(self.user_agent.as_deref().unwrap_or(USER_AGENT));
+        if let Some(timeout) = self.timeout {
+            http = http.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            http = http.connect_timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            http = http.proxy(Proxy::all(proxy)?.no_proxy(NoProxy::from_env()));
+        }
+        for certificate in mem::take(&mut self.root_certificates) {
+            http = http.add_root_certificate(certificate);
+        }
+        if self.danger_accept_invalid_certs {
+            warn!(
+                "TLS certificate validation is DISABLED, provider responses and API keys \
+                 can be intercepted; never use this outside of tests"
+            );
+            http = http.danger_accept_invalid_certs(true);
+        }
+        if let Some(policy) = self.redirect_policy.take() {
+            http = http.redirect(policy);
+        }
+        Ok(http.build()?)
+    }
+}
+
+/// Map provider HTTP error statuses to typed errors.
+///
+/// 401/403 mean the `provider` API key was rejected, 429 means quota is exhausted,
+/// everything else non-2xx is surfaced as a generic HTTP error.
+pub(crate) fn check_status(resp: Response, provider: Provider) -> Result<Response, WeatherError> {
+    match resp.status() {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            Err(WeatherError::InvalidApiKey(provider))
+        }
+        StatusCode::TOO_MANY_REQUESTS => Err(WeatherError::RateLimited),
+        _ => Ok(resp.error_for_status()?),
+    }
+}
+
+/// Result of a `ProviderClient::ping` request, telling outages apart from other HTTP errors.
+pub(crate) fn ping_result(
+    resp: Result<Response, WeatherError>,
+    provider: Provider,
+) -> Result<(), WeatherError> {
+    match resp {
+        Ok(_) => Ok(()),
+        Err(WeatherError::Http(err)) if err.status().is_some_and(|s| s.is_server_error()) => {
+            Err(WeatherError::ProviderUnavailable(provider))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air_quality::AqiCategory;
+    use crate::apis::rate_limiter::RateLimitMode;
+    use crate::testing;
+    use mockito::{Matcher, Server};
+    use rstest::rstest;
+    use std::net::TcpListener;
+
+    fn weatherapi_credentials() -> Credentials {
+        Credentials::WeatherApi {
+            api_key: "KEY".into(),
+        }
+    }
+
+    #[test]
+    fn capabilities_match_client_forecast_limits() {
+        let factory = HttpProviderClientFactory::new();
+
+        for &provider in Provider::ALL {
+            let fields = provider
+                .required_credential_fields()
+                .iter()
+                .map(|field| (field.name, "KEY".to_string()))
+                .collect();
+            let credentials = Credentials::from_fields(provider, &fields).unwrap();
+            let client = factory.create_client(provider, credentials).unwrap();
+
+            assert_eq!(
+                client.max_forecast_days(),
+                provider.capabilities().max_forecast_days,
+                "{provider:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn displays_labeled_summary() {
+        let report = WeatherReport {
+            max_temperature: 12.5,
+            min_temperature: -3.0,
+            feels_like: Some(4.0),
+            ..testing::report()
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "Location: Kyiv, Ukraine\n\
+             Date: 2024-11-29\n\
+             Provider: weatherapi\n\
+             Conditions: Sunny\n\
+             Temperature: max 12.5°C, min -3°C\n\
+             Feels like: 4°C"
+        );
+    }
+
+    #[test]
+    fn report_survives_json_round_trip() {
+        let report = WeatherReport {
+            provider: Provider::AccuWeather,
+            date: "2024-11-29".into(),
+            location: "Kyiv, Ukraine".into(),
+            condition: WeatherCondition::Rain,
+            description: "Rain".into(),
+            max_temperature: 12.5,
+            min_temperature: -3.25,
+            avg_temperature: Some(4.1),
+            feels_like: Some(1.0),
+            uv_index: Some(2.0),
+            wind_kph: Some(13.0),
+            wind_direction: Some("NW".into()),
+            humidity: Some(72.0),
+            sunrise: NaiveTime::from_hms_opt(7, 31, 0),
+            sunset: NaiveTime::from_hms_opt(16, 2, 0),
+            air_quality: Some(AirQuality {
+                index: Some(40.0),
+                category: AqiCategory::Good,
+                pm2_5: Some(3.1),
+                pm10: None,
+            }),
+            alerts: Some(vec![WeatherAlert {
+                headline: "Flood Warning".into(),
+                severity: Some("Moderate".into()),
+                areas: vec!["Kyiv".into()],
+            }]),
+            stale_since: Some("2024-11-29T10:15:00Z".parse().unwrap()),
+        };
+
+        let json = serde_json::to_string(&report).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<WeatherReport>(&json).unwrap(),
+            report
+        );
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn creates_offline_mock_client() {
+        let client = HttpProviderClientFactory::new()
+            .create_client(Provider::Mock, Credentials::Mock)
+            .unwrap();
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.provider, Provider::Mock);
+        assert_eq!(report.location, "Kyiv");
+    }
+
+    #[test]
+    fn builder_applies_base_url_language_and_retry_policy() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::UrlEncoded("lang".into(), "uk".into()))
+            .with_status(429)
+            .expect(1)
+            .create();
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_language("uk")
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::RateLimited),
+            "unexpected error: {err:?}"
+        );
+        mock.assert();
+    }
+
+    #[test]
+    fn builder_appends_extra_params_after_standard_ones() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Regex(
+                "^q=Kyiv&days=1&lang=en&aqi=yes&aqi=no&key=KEY$".into(),
+            ))
+            .with_status(429)
+            .expect(1)
+            .create();
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_extra_params(vec![
+                ("aqi".into(), "yes".into()),
+                ("aqi".into(), "no".into()),
+            ])
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let _ = client.get_weather("Kyiv".into(), 0);
+
+        mock.assert();
+    }
+
+    #[test]
+    fn builder_applies_timeout() {
+        // Accepts connections (via backlog) but never responds.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, url)
+            .with_timeout(Duration::from_millis(100))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(&err, WeatherError::Http(err) if err.is_timeout()),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn read_timeout_fires_after_connect_succeeds() {
+        // Accepts connections (via backlog) but never responds.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, url)
+            .with_connect_timeout(Duration::from_millis(50))
+            .with_timeout(Duration::from_millis(200))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(&err, WeatherError::Http(err) if err.is_timeout() && !err.is_connect()),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn builder_applies_proxy() {
+        let mut proxy = Server::new();
+        let mock = proxy
+            .mock("GET", Matcher::Any)
+            .match_query(Matcher::Any)
+            .with_status(500)
+            .expect(1)
+            .create();
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, "http://weather.invalid/")
+            .with_proxy(proxy.url())
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let _ = client.get_weather("Kyiv".into(), 0);
+
+        mock.assert();
+    }
+
+    #[test]
+    fn builder_follows_redirects_by_default() {
+        let mut server = Server::new();
+        let moved = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_status(301)
+            .with_header("location", "/v2/forecast.json")
+            .expect(1)
+            .create();
+        let target = server
+            .mock("GET", "/v2/forecast.json")
+            .with_status(429)
+            .expect(1)
+            .create();
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::RateLimited),
+            "unexpected error: {err:?}"
+        );
+        moved.assert();
+        target.assert();
+    }
+
+    #[test]
+    fn builder_applies_redirect_policy() {
+        let mut server = Server::new();
+        let moved = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_status(301)
+            .with_header("location", "/v2/forecast.json")
+            .expect(1)
+            .create();
+        let target = server.mock("GET", "/v2/forecast.json").expect(0).create();
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_retry_policy(RetryPolicy::none())
+            .with_redirect_policy(Policy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        assert!(client.get_weather("Kyiv".into(), 0).is_err());
+        moved.assert();
+        target.assert();
+    }
+
+    #[rstest]
+    #[case::default(None, USER_AGENT)]
+    #[case::overridden(Some("my-app/2.0"), "my-app/2.0")]
+    fn builder_sends_user_agent(#[case] user_agent: Option<&str>, #[case] expected: &str) {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .match_header("user-agent", expected)
+            .with_status(429)
+            .expect(1)
+            .create();
+        let mut builder = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_retry_policy(RetryPolicy::none());
+        if let Some(user_agent) = user_agent {
+            builder = builder.with_user_agent(user_agent);
+        }
+        let client = builder
+            .build()
+            .unwrap()
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let _ = client.get_weather("Kyiv".into(), 0);
+
+        mock.assert();
+    }
+
+    #[test]
+    fn builder_uses_injected_http_client() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .match_header("user-agent", "my-app/1.0")
+            .with_status(429)
+            .expect(1)
+            .create();
+        let http = Client::builder().user_agent("my-app/1.0").build().unwrap();
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_http_client(http)
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        mock.assert();
+        assert!(
+            matches!(err, WeatherError::RateLimited),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn builder_rejects_http_settings_with_injected_client() {
+        let err = HttpProviderClientFactory::builder()
+            .with_http_client(Client::new())
+            .with_timeout(Duration::from_secs(5))
+            .with_user_agent("my-app/1.0")
+            .build()
+            .unwrap_err();
+
+        assert!(
+            matches!(&err, WeatherError::IgnoredHttpSettings(settings)
+                if settings == &["`with_timeout`", "`with_user_agent`"]),
+            "unexpected error: {err:?}"
+        );
+        assert_eq!(
+            err.to_string(),
+            "`with_timeout`, `with_user_agent` would be ignored with `with_http_client`, \
+             configure the HTTP client instead"
+        );
+    }
+
+    #[test]
+    fn builder_applies_debug_log() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_status(503)
+            .create();
+        let tmpdir = tempfile::tempdir().unwrap();
+        let path = tmpdir.path().join("debug.log");
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_debug_log(Arc::new(DebugLog::create(&path).unwrap()))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let _ = client.get_weather("Kyiv".into(), 0);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            contents.contains(&format!(
+                "> GET {}/forecast.json?q=Kyiv&days=1&lang=en&key=REDACTED",
+                server.url()
             )),
+            "{contents}"
+        );
+        assert!(contents.contains("< 503 Service Unavailable"), "{contents}");
+        assert!(!contents.contains("KEY"), "{contents}");
+    }
+
+    /// Geocoder placing every address in Kyiv.
+    #[derive(Debug)]
+    struct KyivGeocoder;
+
+    impl Geocoder for KyivGeocoder {
+        fn geocode(&self, _address: &str) -> Result<ResolvedLocation, WeatherError> {
+            Ok(ResolvedLocation {
+                name: "Kyiv".into(),
+                country: "Ukraine".into(),
+                lat: 50.45,
+                lon: 30.52,
+                provider_key: None,
+                timezone: None,
+            })
         }
     }
+
+    #[test]
+    fn tomorrowio_client_uses_builder_geocoder() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/v4/weather/forecast")
+            .match_query(Matcher::UrlEncoded("location".into(), "50.45,30.52".into()))
+            .with_status(500)
+            .expect(1)
+            .create();
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::TomorrowIo, format!("{}/", server.url()))
+            .with_geocoder(Arc::new(KyivGeocoder))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(
+                Provider::TomorrowIo,
+                Credentials::TomorrowIo {
+                    api_key: "KEY".into(),
+                },
+            )
+            .unwrap();
+
+        let _ = client.get_weather("Somewhere".into(), 0);
+
+        mock.assert();
+    }
+
+    #[test]
+    fn invalid_proxy_url_fails_build() {
+        let result = HttpProviderClientFactory::builder()
+            .with_proxy("not a url")
+            .build();
+
+        assert!(
+            matches!(result, Err(WeatherError::Http(_))),
+            "unexpected result: {result:?}"
+        );
+    }
+
+    /// Self-signed CA certificate, valid until 2126.
+    const TEST_CA_PEM: &str = "\
+-----BEGIN CERTIFICATE-----
+MIIBizCCATGgAwIBAgIUMqDXW8pw0TAv8pxitlrC5YsPqNAwCgYIKoZIzj0EAwIw
+GjEYMBYGA1UEAwwPd2V6emFwcCB0ZXN0IENBMCAXDTI2MTAxNzIzMzE0N1oYDzIx
+MjYwOTIzMjMzMTQ3WjAaMRgwFgYDVQQDDA93ZXp6YXBwIHRlc3QgQ0EwWTATBgcq
+hkjOPQIBBggqhkjOPQMBBwNCAARbTFZm5XOXn2qQlFxYTYBE1wX0U33vidGg5Ltj
+vRuMNH+/ph+RbLkkVtUOVFuqqX4xQyy7fvkDGceUHeO/ilUuo1MwUTAdBgNVHQ4E
+FgQUIdc97MKS1YVG7RX8wBULDCtWXsMwHwYDVR0jBBgwFoAUIdc97MKS1YVG7RX8
+wBULDCtWXsMwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNIADBFAiEAz46H
+BAAvWKu/OGz02Oh0A2H9crH35SpoMXow/5hJnxMCIFLxMUb8kQU5x6RElImr2pCd
+8Cz/jfeJEvdnTGkp5buz
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn builds_with_custom_root_certificate() {
+        let certificate = Certificate::from_pem(TEST_CA_PEM.as_bytes()).unwrap();
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_root_certificate(certificate)
+            .build()
+            .unwrap();
+
+        let client = factory.create_client(
+            Provider::WeatherApi,
+            Credentials::WeatherApi {
+                api_key: "KEY".into(),
+            },
+        );
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn builds_accepting_invalid_certificates() {
+        let result = HttpProviderClientFactory::builder()
+            .with_danger_accept_invalid_certs(true)
+            .build();
+
+        assert!(result.is_ok(), "unexpected result: {result:?}");
+    }
+
+    #[test]
+    fn builder_applies_rate_limit() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_status(500)
+            .expect(1)
+            .create();
+
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_retry_policy(RetryPolicy::none())
+            .with_rate_limit(
+                Provider::WeatherApi,
+                RateLimit::per_window(1, Duration::from_secs(60)).with_mode(RateLimitMode::Error),
+            )
+            .build()
+            .unwrap();
+        let client = factory
+            .create_client(Provider::WeatherApi, weatherapi_credentials())
+            .unwrap();
+
+        let _ = client.get_weather("Kyiv".into(), 0);
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+        mock.assert();
+
+        assert!(
+            matches!(err, WeatherError::RateLimited),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn accuweather_is_rate_limited_by_default_unless_disabled() {
+        let limited = |factory: HttpProviderClientFactory| {
+            let mut providers: Vec<_> = factory.rate_limits.into_keys().collect();
+            providers.sort_by_key(|provider| provider.as_str());
+            providers
+        };
+
+        assert_eq!(
+            limited(HttpProviderClientFactory::new()),
+            [Provider::AccuWeather]
+        );
+        assert_eq!(
+            limited(HttpProviderClientFactory::builder().build().unwrap()),
+            [Provider::AccuWeather]
+        );
+        assert_eq!(
+            limited(
+                HttpProviderClientFactory::builder()
+                    .without_rate_limit(Provider::AccuWeather)
+                    .build()
+                    .unwrap()
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn probe_treats_any_response_as_reachable() {
+        let mut server = Server::new();
+        let mock = server.mock("HEAD", "/").with_status(403).create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::AccuWeather, format!("{}/", server.url()))
+            .build()
+            .unwrap();
+
+        factory.probe_reachability(Provider::AccuWeather).unwrap();
+
+        mock.assert();
+    }
+
+    #[test]
+    fn probe_fails_when_provider_is_unreachable() {
+        // Nothing listens on the port once the listener is dropped.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, url)
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+
+        let err = factory
+            .probe_reachability(Provider::WeatherApi)
+            .unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::Http(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[rstest]
+    #[case::with_slash("/")]
+    #[case::without_slash("")]
+    fn accuweather_uses_stored_base_url(#[case] suffix: &str) {
+        let mut server = Server::new();
+        let mock = server
+            .mock("GET", "/eu/locations/v1/search")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body("[]")
+            .create();
+        let credentials = Credentials::AccuWeather {
+            api_key: "KEY".into(),
+            base_url: Some(format!("{}/eu{suffix}", server.url())),
+        };
+        let client = HttpProviderClientFactory::builder()
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap()
+            .create_client(Provider::AccuWeather, credentials)
+            .unwrap();
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        mock.assert();
+        assert!(
+            matches!(err, WeatherError::AddressNotFound),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn mismatched_credentials_are_rejected() {
+        let err = HttpProviderClientFactory::new()
+            .create_client(Provider::AccuWeather, weatherapi_credentials())
+            .err()
+            .unwrap();
+
+        assert!(
+            matches!(
+                err,
+                WeatherError::CredentialsMismatch(Provider::AccuWeather)
+            ),
+            "unexpected error: {err:?}"
+        );
+    }
 }
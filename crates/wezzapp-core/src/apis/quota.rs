@@ -0,0 +1,84 @@
+use crate::provider::Provider;
+use reqwest::header::HeaderMap;
+use std::fmt::Debug;
+
+/// Remaining provider call quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaStatus {
+    pub provider: Provider,
+    pub remaining: u32,
+    /// Total calls allowed in the current period, if known.
+    pub limit: Option<u32>,
+}
+
+/// Receives quota updates, e.g. to warn user before calls start failing.
+pub trait QuotaObserver: Debug + Send + Sync {
+    fn observe(&self, status: &QuotaStatus);
+}
+
+/// Read quota from `RateLimit-*` or `X-RateLimit-*` response headers.
+pub(crate) fn from_headers(provider: Provider, headers: &HeaderMap) -> Option<QuotaStatus> {
+    let header = |name: &str| {
+        [name.to_string(), format!("x-{name}")]
+            .iter()
+            .find_map(|name| headers.get(name)?.to_str().ok()?.trim().parse::<u32>().ok())
+    };
+
+    Some(QuotaStatus {
+        provider,
+        remaining: header("ratelimit-remaining")?,
+        limit: header("ratelimit-limit"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+    use reqwest::blocking::Client;
+
+    #[test]
+    fn parses_rate_limit_headers_from_response() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/")
+            .with_header("RateLimit-Limit", "50")
+            .with_header("RateLimit-Remaining", "3")
+            .create();
+
+        let resp = Client::new().get(server.url()).send().unwrap();
+
+        assert_eq!(
+            from_headers(Provider::AccuWeather, resp.headers()),
+            Some(QuotaStatus {
+                provider: Provider::AccuWeather,
+                remaining: 3,
+                limit: Some(50),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_x_prefixed_headers_without_limit() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "7".parse().unwrap());
+
+        assert_eq!(
+            from_headers(Provider::WeatherApi, &headers),
+            Some(QuotaStatus {
+                provider: Provider::WeatherApi,
+                remaining: 7,
+                limit: None,
+            })
+        );
+    }
+
+    #[test]
+    fn missing_or_invalid_headers_are_ignored() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(from_headers(Provider::WeatherApi, &headers), None);
+
+        headers.insert("ratelimit-remaining", "lots".parse().unwrap());
+        assert_eq!(from_headers(Provider::WeatherApi, &headers), None);
+    }
+}
@@ -0,0 +1,270 @@
+use crate::error::WeatherError;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Source of time for the rate limiter, abstracted so tests can control it.
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Instant;
+
+    fn sleep(&self, duration: Duration);
+}
+
+/// Real clock backed by `Instant::now` and `thread::sleep`.
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// What to do with a call that would exceed the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitMode {
+    /// Wait until the call is allowed.
+    Block,
+    /// Fail immediately with `WeatherError::RateLimited`.
+    Error,
+}
+
+/// Client-side limits for calls to a single provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Minimum time between two consecutive calls.
+    pub min_interval: Option<Duration>,
+    /// Max number of calls within a sliding window.
+    pub max_per_window: Option<(u32, Duration)>,
+    pub mode: RateLimitMode,
+}
+
+impl RateLimit {
+    /// Limit to at most one call per `interval`.
+    pub fn min_interval(interval: Duration) -> Self {
+        Self {
+            min_interval: Some(interval),
+            max_per_window: None,
+            mode: RateLimitMode::Block,
+        }
+    }
+
+    /// Limit to at most `max` calls per sliding `window`.
+    pub fn per_window(max: u32, window: Duration) -> Self {
+        Self {
+            min_interval: None,
+            max_per_window: Some((max, window)),
+            mode: RateLimitMode::Block,
+        }
+    }
+
+    pub fn with_min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = Some(interval);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: RateLimitMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// How long to wait before a call is allowed, given previous call times.
+    fn delay(&self, history: &VecDeque<Instant>, now: Instant) -> Option<Duration> {
+        let mut wait = Duration::ZERO;
+
+        if let (Some(interval), Some(last)) = (self.min_interval, history.back()) {
+            wait = wait.max(interval.saturating_sub(now.saturating_duration_since(*last)));
+        }
+
+        if let Some((max, window)) = self.max_per_window
+            && max > 0
+            && history.len() >= max as usize
+        {
+            let oldest = history[history.len() - max as usize];
+            wait = wait.max(window.saturating_sub(now.saturating_duration_since(oldest)));
+        }
+
+        (wait > Duration::ZERO).then_some(wait)
+    }
+}
+
+/// Request budget for a provider, shared by every client created for it.
+///
+/// Each HTTP request sent to the provider spends a call, so a forecast needing a location
+/// search spends two and a retried request one per attempt. State lives in memory, so
+/// limits apply within a single process run (e.g. a batch), not across runs.
+#[derive(Debug)]
+pub struct RateLimitBudget {
+    limit: RateLimit,
+    clock: Arc<dyn Clock>,
+    history: Mutex<VecDeque<Instant>>,
+}
+
+impl RateLimitBudget {
+    pub fn new(limit: RateLimit) -> Self {
+        Self::with_clock(limit, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(limit: RateLimit, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            limit,
+            clock,
+            history: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Reserve a call, blocking or failing according to the limit mode.
+    pub fn acquire(&self) -> Result<(), WeatherError> {
+        loop {
+            let wait = {
+                let mut history = self.history.lock().expect("rate limiter lock poisoned");
+                let now = self.clock.now();
+                self.prune(&mut history, now);
+
+                match self.limit.delay(&history, now) {
+                    None => {
+                        history.push_back(now);
+                        return Ok(());
+                    }
+                    Some(wait) => wait,
+                }
+            };
+
+            match self.limit.mode {
+                RateLimitMode::Error => return Err(WeatherError::RateLimited),
+                RateLimitMode::Block => {
+                    debug!("Rate limit reached, waiting {wait:?}");
+                    self.clock.sleep(wait);
+                }
+            }
+        }
+    }
+
+    /// Drop call times that no longer affect the limit, keeping the latest one.
+    fn prune(&self, history: &mut VecDeque<Instant>, now: Instant) {
+        let window = self.limit.max_per_window.map(|(_, window)| window);
+
+        while history.len() > 1 {
+            let expired = match window {
+                Some(window) => now.saturating_duration_since(history[0]) >= window,
+                None => true,
+            };
+            if !expired {
+                break;
+            }
+            history.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Clock that only moves when slept on.
+    #[derive(Debug)]
+    struct FakeClock {
+        now: Mutex<Instant>,
+        slept: Mutex<Duration>,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self {
+                now: Mutex::new(Instant::now()),
+                slept: Mutex::new(Duration::ZERO),
+            }
+        }
+
+        fn advance(&self, duration: Duration) {
+            *self.now.lock().unwrap() += duration;
+        }
+
+        fn slept(&self) -> Duration {
+            *self.slept.lock().unwrap()
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            *self.now.lock().unwrap()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            *self.slept.lock().unwrap() += duration;
+            self.advance(duration);
+        }
+    }
+
+    fn budget(limit: RateLimit) -> (RateLimitBudget, Arc<FakeClock>) {
+        let clock = Arc::new(FakeClock::new());
+        (RateLimitBudget::with_clock(limit, clock.clone()), clock)
+    }
+
+    #[test]
+    fn block_mode_waits_for_min_interval() {
+        let (budget, clock) = budget(RateLimit::min_interval(Duration::from_secs(2)));
+
+        budget.acquire().unwrap();
+        budget.acquire().unwrap();
+
+        assert_eq!(clock.slept(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn no_wait_when_interval_already_elapsed() {
+        let (budget, clock) = budget(RateLimit::min_interval(Duration::from_secs(2)));
+
+        budget.acquire().unwrap();
+        clock.advance(Duration::from_secs(3));
+        budget.acquire().unwrap();
+
+        assert_eq!(clock.slept(), Duration::ZERO);
+    }
+
+    #[test]
+    fn error_mode_rejects_call_over_window_limit() {
+        let limit =
+            RateLimit::per_window(2, Duration::from_secs(60)).with_mode(RateLimitMode::Error);
+        let (budget, clock) = budget(limit);
+
+        budget.acquire().unwrap();
+        budget.acquire().unwrap();
+        let err = budget.acquire().unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::RateLimited),
+            "unexpected error: {err:?}"
+        );
+        assert_eq!(clock.slept(), Duration::ZERO);
+    }
+
+    #[test]
+    fn window_limit_resets_after_window_passes() {
+        let limit =
+            RateLimit::per_window(1, Duration::from_secs(60)).with_mode(RateLimitMode::Error);
+        let (budget, clock) = budget(limit);
+
+        budget.acquire().unwrap();
+        clock.advance(Duration::from_secs(60));
+        budget.acquire().unwrap();
+    }
+
+    #[test]
+    fn block_mode_waits_for_oldest_call_to_leave_window() {
+        let (budget, clock) = budget(RateLimit::per_window(2, Duration::from_secs(60)));
+
+        budget.acquire().unwrap();
+        clock.advance(Duration::from_secs(10));
+        budget.acquire().unwrap();
+        budget.acquire().unwrap();
+
+        assert_eq!(clock.slept(), Duration::from_secs(50));
+    }
+}
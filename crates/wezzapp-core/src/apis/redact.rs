@@ -0,0 +1,66 @@
+//! Keeping secrets and addresses out of logs.
+//!
+//! Debug logs name addresses only by `address` hashes, so they can be shared without revealing
+//! where the user is. Request URLs, response bodies and reports, which do, are logged at trace
+//! level only.
+
+use reqwest::Url;
+
+/// Query params carrying API keys, their values are never logged.
+const SECRET_PARAMS: &[&str] = &["key", "apikey", "api_key"];
+
+/// Short hash of `address`, e.g. `5f1c2a9b03d4e7f6`, so calls for the same address still
+/// stand out in logs.
+///
+/// FNV-1a, stable across platforms and Rust versions unlike `DefaultHasher`.
+pub fn address(address: &str) -> String {
+    let hash = address.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+/// Replace values of API key query params with `REDACTED`.
+pub(crate) fn secrets(mut url: Url) -> Url {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if SECRET_PARAMS.contains(&name.to_lowercase().as_str()) {
+                "REDACTED".into()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+
+    if !pairs.is_empty() {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_api_key_params_only() {
+        let url =
+            Url::parse("https://example.com/forecast.json?key=SECRET&q=Kyiv&ApiKey=X").unwrap();
+
+        assert_eq!(
+            secrets(url).as_str(),
+            "https://example.com/forecast.json?key=REDACTED&q=Kyiv&ApiKey=REDACTED"
+        );
+    }
+
+    #[test]
+    fn address_hash_is_stable() {
+        assert_eq!(address("Kyiv"), address("Kyiv"));
+        assert_ne!(address("Kyiv"), address("Lviv"));
+        assert_eq!(address(""), "cbf29ce484222325");
+    }
+}
@@ -0,0 +1,107 @@
+use crate::apis::WeatherReport;
+use crate::apis::file_cache::FileCache;
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::debug;
+
+/// How long reports are kept by default, older ones are dropped on the next write.
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Cache of the last fetched reports, served when the provider is unreachable.
+///
+/// Best-effort: implementations swallow their own errors.
+pub trait ReportCache: Debug + Send + Sync {
+    /// Cached report and when it was fetched.
+    fn get(&self, key: &str) -> Option<(WeatherReport, DateTime<Utc>)>;
+
+    fn put(&self, key: &str, report: &WeatherReport);
+}
+
+/// JSON-file-based `ReportCache`.
+#[derive(Debug)]
+pub struct FileReportCache {
+    cache: FileCache<WeatherReport>,
+    max_age: Duration,
+}
+
+impl FileReportCache {
+    /// Load cache from `path`, starting empty if it's missing or unreadable.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            cache: FileCache::new(path.into()),
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+
+    /// Set how long reports are kept.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+}
+
+impl ReportCache for FileReportCache {
+    fn get(&self, key: &str) -> Option<(WeatherReport, DateTime<Utc>)> {
+        let entry = self.cache.get(key)?;
+        debug!("Found cached report");
+
+        let fetched_at = DateTime::from_timestamp(entry.cached_at as i64, 0)?;
+        Some((entry.value, fetched_at))
+    }
+
+    fn put(&self, key: &str, report: &WeatherReport) {
+        self.cache.put(key, report, Some(self.max_age.as_secs()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn report(location: &str) -> WeatherReport {
+        WeatherReport {
+            location: location.into(),
+            avg_temperature: Some(7.4),
+            uv_index: Some(4.0),
+            ..testing::report()
+        }
+    }
+
+    #[test]
+    fn reports_persist_across_reloads_with_fetch_time() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let path = tmpdir.path().join("reports.json");
+        FileReportCache::new(&path).put("kyiv", &report("Kyiv"));
+
+        let (cached, fetched_at) = FileReportCache::new(&path).get("kyiv").unwrap();
+
+        assert_eq!(cached, report("Kyiv"));
+        assert!((Utc::now() - fetched_at).num_seconds() < 60);
+    }
+
+    #[test]
+    fn reports_cached_before_humidity_load_without_it() {
+        let mut cached = serde_json::to_value(report("Kyiv")).unwrap();
+        cached.as_object_mut().unwrap().remove("humidity");
+
+        let loaded: WeatherReport = serde_json::from_value(cached).unwrap();
+
+        assert_eq!(loaded, report("Kyiv"));
+    }
+
+    #[test]
+    fn old_reports_are_dropped_on_write() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cache =
+            FileReportCache::new(tmpdir.path().join("reports.json")).with_max_age(Duration::ZERO);
+
+        cache.put("kyiv", &report("Kyiv"));
+        cache.put("lviv", &report("Lviv"));
+
+        assert_eq!(cache.get("kyiv"), None);
+        assert!(cache.get("lviv").is_some());
+    }
+}
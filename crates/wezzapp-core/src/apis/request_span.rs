@@ -0,0 +1,94 @@
+use crate::apis::redact;
+use crate::provider::Provider;
+use std::time::Instant;
+use tracing::span::EnteredSpan;
+use tracing::{debug, debug_span, field};
+
+/// `provider_request` span around a provider call, recording `elapsed_ms` when dropped.
+///
+/// Addresses are hashed, see `redact::address`.
+pub(crate) struct RequestSpan {
+    span: EnteredSpan,
+    started: Instant,
+}
+
+impl RequestSpan {
+    /// Enter a span for a call of `provider`'s `endpoint`, e.g. "forecast", for `address`.
+    pub(crate) fn enter(provider: Provider, endpoint: &'static str, address: &str) -> Self {
+        let span = debug_span!(
+            "provider_request",
+            provider = provider.as_str(),
+            endpoint,
+            address = %redact::address(address),
+            elapsed_ms = field::Empty,
+        );
+
+        Self {
+            span: span.entered(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for RequestSpan {
+    fn drop(&mut self) {
+        let elapsed_ms = self.started.elapsed().as_millis() as u64;
+        self.span.record("elapsed_ms", elapsed_ms);
+        debug!("Provider request took {elapsed_ms} ms");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::fmt::MakeWriter;
+
+    /// Log output collected in memory.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Captured {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn records_provider_endpoint_hashed_address_and_timing() {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(captured.clone())
+            .with_ansi(false)
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = RequestSpan::enter(Provider::WeatherApi, "forecast", "Kyiv, Ukraine");
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("provider_request{"), "{output}");
+        assert!(output.contains("provider=\"weatherapi\""), "{output}");
+        assert!(output.contains("endpoint=\"forecast\""), "{output}");
+        assert!(
+            output.contains(&format!("address={}", redact::address("Kyiv, Ukraine"))),
+            "{output}"
+        );
+        assert!(output.contains("elapsed_ms="), "{output}");
+        assert!(!output.contains("Kyiv"), "{output}");
+    }
+}
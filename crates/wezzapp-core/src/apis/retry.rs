@@ -0,0 +1,253 @@
+use crate::apis::rate_limiter::RateLimitBudget;
+use crate::error::WeatherError;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::RETRY_AFTER;
+use std::time::Duration;
+use tracing::debug;
+
+/// How failed provider requests are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each next one.
+    ///
+    /// Up to half of it is added at random, so batched requests don't retry in lockstep.
+    pub base_delay: Duration,
+    /// Upper bound for a server-requested `Retry-After` delay,
+    /// so a hostile server can't make the CLI hang.
+    pub max_retry_after: Duration,
+}
+
+impl RetryPolicy {
+    /// Never retry.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `attempt` (0-based).
+    ///
+    /// `jitter` in `[0, 1)` scales the random extra on top of the backoff,
+    /// a server-requested `retry_after` is used as is.
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>, jitter: f64) -> Duration {
+        match retry_after {
+            Some(retry_after) => retry_after.min(self.max_retry_after),
+            None => {
+                let backoff = self.base_delay.saturating_mul(2u32.saturating_pow(attempt));
+                backoff.saturating_add(backoff.mul_f64(jitter / 2.0))
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            base_delay: Duration::from_millis(500),
+            max_retry_after: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Send request, retrying on 429, 5xx and transient network errors.
+///
+/// A 429 `Retry-After` header takes precedence over the jittered exponential backoff.
+/// The last response is returned as is, status checks are up to the caller.
+/// Every attempt, retries included, spends a call of `budget` if set.
+pub(crate) fn send_with_retry(
+    request: RequestBuilder,
+    policy: &RetryPolicy,
+    budget: Option<&RateLimitBudget>,
+) -> Result<Response, WeatherError> {
+    let mut attempt = 0;
+
+    loop {
+        if let Some(budget) = budget {
+            budget.acquire()?;
+        }
+        let Some(current) = request.try_clone() else {
+            // Streaming bodies can't be replayed, send once.
+            return Ok(request.send()?);
+        };
+
+        let retry_after = match current.send() {
+            Ok(resp) if attempt < policy.max_retries && is_retryable(resp.status()) => {
+                debug!("Provider returned {}, retrying", resp.status());
+                match resp.status() {
+                    StatusCode::TOO_MANY_REQUESTS => parse_retry_after(&resp),
+                    _ => None,
+                }
+            }
+            Ok(resp) => return Ok(resp),
+            Err(err) if attempt < policy.max_retries && (err.is_connect() || err.is_timeout()) => {
+                debug!("Request failed: {err}, retrying");
+                None
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let delay = policy.delay(attempt, retry_after, fastrand::f64());
+        debug!("Waiting {delay:?} before retry #{}", attempt + 1);
+        std::thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+fn parse_retry_after(resp: &Response) -> Option<Duration> {
+    let value = resp.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    retry_after_delay(value, Utc::now())
+}
+
+/// Parse `Retry-After` value in either delta-seconds or HTTP-date form.
+fn retry_after_delay(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // Dates in the past mean "retry now".
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use mockito::Server;
+    use reqwest::blocking::Client;
+    use std::time::Instant;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_delta_seconds() {
+        assert_eq!(retry_after_delay("2", now()), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn parses_http_date() {
+        assert_eq!(
+            retry_after_delay("Wed, 21 Oct 2015 07:28:05 GMT", now()),
+            Some(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn past_http_date_means_no_delay() {
+        assert_eq!(
+            retry_after_delay("Wed, 21 Oct 2015 07:27:00 GMT", now()),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn invalid_value_is_ignored() {
+        assert_eq!(retry_after_delay("soon", now()), None);
+    }
+
+    #[test]
+    fn retry_after_is_capped() {
+        let policy = RetryPolicy {
+            max_retry_after: Duration::from_secs(10),
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(3600)), 0.5),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_without_retry_after() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(policy.delay(0, None, 0.0), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, None, 0.0), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn jitter_adds_up_to_half_of_backoff() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            ..RetryPolicy::default()
+        };
+
+        assert_eq!(policy.delay(1, None, 0.5), Duration::from_millis(250));
+        assert!(policy.delay(1, None, 0.999) < Duration::from_millis(300));
+    }
+
+    #[test]
+    fn retry_after_is_not_jittered() {
+        assert_eq!(
+            RetryPolicy::default().delay(0, Some(Duration::from_secs(2)), 0.9),
+            Duration::from_secs(2)
+        );
+    }
+
+    #[test]
+    fn honors_retry_after_then_succeeds() {
+        let mut server = Server::new();
+        let limited = server
+            .mock("GET", "/")
+            .with_status(429)
+            .with_header("retry-after", "2")
+            .expect(1)
+            .create();
+        let ok = server.mock("GET", "/").with_status(200).expect(1).create();
+
+        let started = Instant::now();
+        let resp = send_with_retry(
+            Client::new().get(server.url()),
+            &RetryPolicy::default(),
+            None,
+        )
+        .unwrap();
+        let elapsed = started.elapsed();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(
+            elapsed >= Duration::from_millis(1900) && elapsed < Duration::from_secs(5),
+            "unexpected wait: {elapsed:?}"
+        );
+        limited.assert();
+        ok.assert();
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let mut server = Server::new();
+        let failing = server.mock("GET", "/").with_status(503).expect(2).create();
+        let policy = RetryPolicy {
+            max_retries: 1,
+            base_delay: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        };
+
+        let resp = send_with_retry(Client::new().get(server.url()), &policy, None).unwrap();
+
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        failing.assert();
+    }
+}
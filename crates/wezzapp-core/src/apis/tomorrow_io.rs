@@ -0,0 +1,591 @@
+use crate::apis::debug_log::{DebugLog, send_logged};
+use crate::apis::geocoder::Geocoder;
+use crate::apis::location_cache::{LocationCache, normalize};
+use crate::apis::rate_limiter::RateLimitBudget;
+use crate::apis::request_span::RequestSpan;
+use crate::apis::retry::RetryPolicy;
+use crate::apis::{
+    CurrentConditions, DEFAULT_LANGUAGE, ProviderClient, ResolvedLocation, WeatherReport,
+    check_status, ping_result,
+};
+use crate::condition::WeatherCondition;
+use crate::error::WeatherError;
+use crate::provider::Provider;
+use chrono::DateTime;
+use reqwest::Url;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt::Debug;
+use std::sync::Arc;
+use tracing::{debug, trace};
+
+/// Production Tomorrow.io base URL.
+pub(crate) const BASE_URL: &str = "https://api.tomorrow.io/";
+
+/// Wind speed is returned in m/s with metric units.
+const KPH_PER_MPS: f64 = 3.6;
+
+/// Http client for Tomorrow.io API
+///
+/// Forecasts are only available by coordinates, addresses are resolved with `geocoder`.
+#[derive(Debug)]
+pub struct TomorrowIoClient {
+    api_key: String,
+    url: String,
+    /// Only used in location cache keys, as geocoded names are localized.
+    language: String,
+    /// Additional query pairs appended verbatim to the forecast request.
+    extra_params: Vec<(String, String)>,
+    geocoder: Arc<dyn Geocoder>,
+    location_cache: Option<Arc<dyn LocationCache>>,
+    retry: RetryPolicy,
+    /// Spent once per HTTP request sent, if set.
+    rate_limit: Option<Arc<RateLimitBudget>>,
+    /// Records every HTTP exchange, if set.
+    debug_log: Option<Arc<DebugLog>>,
+    client: Client,
+}
+
+impl TomorrowIoClient {
+    /// Create client sending requests to the given base URL over a shared HTTP `client`.
+    pub(crate) fn new(
+        client: Client,
+        api_key: String,
+        url: impl Into<String>,
+        geocoder: Arc<dyn Geocoder>,
+    ) -> Self {
+        Self {
+            api_key,
+            url: url.into(),
+            language: DEFAULT_LANGUAGE.to_string(),
+            extra_params: Vec::new(),
+            geocoder,
+            location_cache: None,
+            retry: RetryPolicy::default(),
+            rate_limit: None,
+            debug_log: None,
+            client,
+        }
+    }
+
+    /// Limit HTTP requests to `budget`, shared with other clients of the provider.
+    pub(crate) fn with_rate_limit(mut self, budget: Option<Arc<RateLimitBudget>>) -> Self {
+        self.rate_limit = budget;
+        self
+    }
+
+    /// Record every HTTP exchange in `log`.
+    pub(crate) fn with_debug_log(mut self, log: Option<Arc<DebugLog>>) -> Self {
+        self.debug_log = log;
+        self
+    }
+
+    /// Set how failed requests are retried.
+    pub(crate) fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set language of geocoded location names.
+    pub(crate) fn with_language(mut self, language: String) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Look up resolved locations in `cache` before calling the geocoder.
+    pub(crate) fn with_location_cache(mut self, cache: Option<Arc<dyn LocationCache>>) -> Self {
+        self.location_cache = cache;
+        self
+    }
+
+    /// Append extra query pairs to the forecast request.
+    pub(crate) fn with_extra_params(mut self, extra_params: Vec<(String, String)>) -> Self {
+        self.extra_params = extra_params;
+        self
+    }
+
+    fn forecast_request<T: DeserializeOwned + Debug>(
+        &self,
+        location: &ResolvedLocation,
+    ) -> Result<T, WeatherError> {
+        let mut url = Url::parse(&self.url)?;
+        url = url.join("v4/weather/forecast")?;
+        {
+            let mut qp = url.query_pairs_mut();
+            qp.append_pair("location", &format!("{},{}", location.lat, location.lon));
+            qp.append_pair("timesteps", "1d");
+            qp.append_pair("units", "metric");
+            qp.extend_pairs(&self.extra_params);
+            qp.append_pair("apikey", &self.api_key);
+        }
+
+        let request = self.client.get(url);
+        let resp = send_logged(
+            request,
+            &self.retry,
+            self.rate_limit.as_deref(),
+            self.debug_log.as_deref(),
+        )?;
+        let resp = check_status(resp, Provider::TomorrowIo)?;
+
+        let body = resp.json()?;
+        trace!("Tomorrow.io body: {body:?}");
+
+        Ok(body)
+    }
+
+    fn realtime_request(
+        &self,
+        location: &ResolvedLocation,
+    ) -> Result<TomorrowIoRealtimeResponse, WeatherError> {
+        let mut url = Url::parse(&self.url)?;
+        url = url.join("v4/weather/realtime")?;
+        {
+            let mut qp = url.query_pairs_mut();
+            qp.append_pair("location", &format!("{},{}", location.lat, location.lon));
+            qp.append_pair("units", "metric");
+            qp.append_pair("apikey", &self.api_key);
+        }
+
+        let request = self.client.get(url);
+        let resp = send_logged(
+            request,
+            &self.retry,
+            self.rate_limit.as_deref(),
+            self.debug_log.as_deref(),
+        )?;
+        let resp = check_status(resp, Provider::TomorrowIo)?;
+
+        let body = resp.json()?;
+        trace!("Tomorrow.io realtime body: {body:?}");
+
+        Ok(body)
+    }
+}
+
+impl ProviderClient for TomorrowIoClient {
+    fn get_weather(
+        &self,
+        address: String,
+        day_from_today: u32,
+    ) -> Result<WeatherReport, WeatherError> {
+        // `resolve_location` and `get_weather_at` enter their own spans.
+        let location = self.resolve_location(&address)?;
+
+        self.get_weather_at(&location, day_from_today)
+    }
+
+    fn resolve_location(&self, address: &str) -> Result<ResolvedLocation, WeatherError> {
+        let _span = RequestSpan::enter(Provider::TomorrowIo, "location", address);
+        let cache_key = format!("tomorrowio:{}:{}", self.language, normalize(address));
+        if let Some(location) = self
+            .location_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&cache_key))
+        {
+            return Ok(location);
+        }
+
+        let location = self.geocoder.geocode(address)?;
+        if let Some(cache) = &self.location_cache {
+            cache.put(&cache_key, &location);
+        }
+
+        Ok(location)
+    }
+
+    fn get_weather_at(
+        &self,
+        location: &ResolvedLocation,
+        day_from_today: u32,
+    ) -> Result<WeatherReport, WeatherError> {
+        let _span = RequestSpan::enter(Provider::TomorrowIo, "forecast", &location.display_name());
+        debug!("Getting weather {day_from_today} days from today");
+
+        let forecast: TomorrowIoForecastResponse = self.forecast_request(location)?;
+
+        let day = forecast
+            .timelines
+            .daily
+            .get(day_from_today as usize)
+            .ok_or_else(|| {
+                WeatherError::UnexpectedResponse("wrong number of days in API response".into())
+            })?;
+        let values = &day.values;
+        let date = DateTime::parse_from_rfc3339(&day.time)
+            .map_err(|err| WeatherError::UnexpectedResponse(format!("invalid time: {err}")))?
+            .date_naive();
+        let code = values.weather_code_max.unwrap_or_default();
+
+        Ok(WeatherReport {
+            provider: Provider::TomorrowIo,
+            date: date.to_string(),
+            location: location.display_name(),
+            condition: WeatherCondition::from_tomorrowio_code(code),
+            // The API has codes only, no condition text.
+            description: description(code).to_string(),
+            max_temperature: values.temperature_max,
+            min_temperature: values.temperature_min,
+            avg_temperature: values.temperature_avg,
+            feels_like: values.temperature_apparent_avg,
+            uv_index: values.uv_index_max,
+            wind_kph: values.wind_speed_avg.map(|mps| mps * KPH_PER_MPS),
+            wind_direction: values.wind_direction_avg.map(compass_point),
+            humidity: values.humidity_avg,
+            // Sun times are returned in UTC, without the location's offset.
+            sunrise: None,
+            sunset: None,
+            air_quality: None,
+            alerts: None,
+            stale_since: None,
+        })
+    }
+
+    fn get_weather_raw(&self, address: String, day_from_today: u32) -> Result<Value, WeatherError> {
+        let _span = RequestSpan::enter(Provider::TomorrowIo, "forecast_raw", &address);
+        debug!("Getting raw weather {day_from_today} days from today");
+
+        let location = self.resolve_location(&address)?;
+
+        // Daily forecast is returned as a whole regardless of the requested day.
+        self.forecast_request(&location)
+    }
+
+    fn get_current(&self, address: &str) -> Result<CurrentConditions, WeatherError> {
+        let _span = RequestSpan::enter(Provider::TomorrowIo, "current", address);
+
+        let location = self.resolve_location(address)?;
+        let realtime = self.realtime_request(&location)?.data;
+        let values = realtime.values;
+        let observed_at = DateTime::parse_from_rfc3339(&realtime.time)
+            .map_err(|err| WeatherError::UnexpectedResponse(format!("invalid time: {err}")))?
+            .to_utc();
+        let code = values.weather_code.unwrap_or_default();
+
+        Ok(CurrentConditions {
+            provider: Provider::TomorrowIo,
+            location: location.display_name(),
+            observed_at,
+            condition: WeatherCondition::from_tomorrowio_code(code),
+            description: description(code).to_string(),
+            temperature: values.temperature,
+            feels_like: values.temperature_apparent,
+            humidity: values.humidity,
+            wind_kph: values.wind_speed.map(|mps| mps * KPH_PER_MPS),
+            wind_direction: values.wind_direction.map(compass_point),
+        })
+    }
+
+    fn max_forecast_days(&self) -> u32 {
+        // Today and 5 days ahead.
+        6
+    }
+
+    fn ping(&self) -> Result<(), WeatherError> {
+        // Coordinates of `VERIFY_ADDRESS`, skipping the geocoder lookup of the default.
+        let mut url = Url::parse(&self.url)?;
+        url = url.join("v4/weather/realtime")?;
+        url.query_pairs_mut()
+            .append_pair("location", "51.5072,-0.1276")
+            .append_pair("apikey", &self.api_key);
+
+        let request = self.client.get(url);
+        let resp = send_logged(
+            request,
+            &self.retry,
+            self.rate_limit.as_deref(),
+            self.debug_log.as_deref(),
+        )
+        .and_then(|resp| check_status(resp, Provider::TomorrowIo));
+
+        ping_result(resp, Provider::TomorrowIo)
+    }
+}
+
+/// English text for a Tomorrow.io weather code.
+///
+/// See https://docs.tomorrow.io/reference/data-layers-weather-codes
+fn description(code: i64) -> &'static str {
+    match code {
+        1000 => "Clear, Sunny",
+        1100 => "Mostly Clear",
+        1101 => "Partly Cloudy",
+        1102 => "Mostly Cloudy",
+        1001 => "Cloudy",
+        2000 => "Fog",
+        2100 => "Light Fog",
+        4000 => "Drizzle",
+        4001 => "Rain",
+        4200 => "Light Rain",
+        4201 => "Heavy Rain",
+        5000 => "Snow",
+        5001 => "Flurries",
+        5100 => "Light Snow",
+        5101 => "Heavy Snow",
+        6000 => "Freezing Drizzle",
+        6001 => "Freezing Rain",
+        6200 => "Light Freezing Rain",
+        6201 => "Heavy Freezing Rain",
+        7000 => "Ice Pellets",
+        7101 => "Heavy Ice Pellets",
+        7102 => "Light Ice Pellets",
+        8000 => "Thunderstorm",
+        _ => "Unknown",
+    }
+}
+
+/// 16-point compass direction, e.g. "NW", for a direction in degrees.
+fn compass_point(degrees: f64) -> String {
+    const POINTS: [&str; 16] = [
+        "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+        "NW", "NNW",
+    ];
+    let index = (degrees.rem_euclid(360.0) / 22.5).round() as usize % POINTS.len();
+
+    POINTS[index].to_string()
+}
+
+#[derive(Debug, Deserialize)]
+struct TomorrowIoRealtimeResponse {
+    data: TomorrowIoRealtimeDataResponse,
+}
+
+#[derive(Debug, Deserialize)]
+struct TomorrowIoRealtimeDataResponse {
+    time: String,
+    values: TomorrowIoRealtimeValuesResponse,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TomorrowIoRealtimeValuesResponse {
+    temperature: f64,
+    temperature_apparent: Option<f64>,
+    humidity: Option<f64>,
+    wind_speed: Option<f64>,
+    wind_direction: Option<f64>,
+    weather_code: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TomorrowIoForecastResponse {
+    timelines: TomorrowIoTimelinesResponse,
+}
+
+#[derive(Debug, Deserialize)]
+struct TomorrowIoTimelinesResponse {
+    daily: Vec<TomorrowIoDailyResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TomorrowIoDailyResponse {
+    /// Start of the day as an RFC 3339 timestamp.
+    time: String,
+    values: TomorrowIoDailyValuesResponse,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TomorrowIoDailyValuesResponse {
+    temperature_max: f64,
+    temperature_min: f64,
+    temperature_avg: Option<f64>,
+    temperature_apparent_avg: Option<f64>,
+    uv_index_max: Option<f64>,
+    wind_speed_avg: Option<f64>,
+    wind_direction_avg: Option<f64>,
+    humidity_avg: Option<f64>,
+    weather_code_max: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apis::geocoder::OpenMeteoGeocoder;
+    use crate::testing;
+    use mockito::{Matcher, Server};
+    use rstest::rstest;
+
+    fn base_url(server: &Server) -> String {
+        format!("{}/", server.url())
+    }
+
+    /// Trimmed recording of a `timesteps=1d` forecast for Kyiv.
+    const FORECAST_BODY: &str = r#"{
+        "timelines": {"daily": [
+            {
+                "time": "2024-11-29T04:00:00Z",
+                "values": {
+                    "temperatureMax": 4.13, "temperatureMin": -1.5, "temperatureAvg": 1.24,
+                    "temperatureApparentAvg": -2.8, "uvIndexMax": 1,
+                    "windSpeedAvg": 4.5, "windDirectionAvg": 312.4, "humidityAvg": 81.2,
+                    "weatherCodeMax": 1001, "sunriseTime": "2024-11-29T05:31:00Z"
+                }
+            },
+            {
+                "time": "2024-11-30T04:00:00Z",
+                "values": {"temperatureMax": 2.0, "temperatureMin": -3.0, "weatherCodeMax": 5100}
+            }
+        ]},
+        "location": {"lat": 50.45466, "lon": 30.5238}
+    }"#;
+
+    fn client(server: &mut Server) -> (TomorrowIoClient, mockito::Mock) {
+        let geocoding = server
+            .mock("GET", "/search")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"results": [{
+                    "name": "Kyiv", "latitude": 50.45466, "longitude": 30.5238, "country": "Ukraine"
+                }]}"#,
+            )
+            .create();
+        let geocoder = Arc::new(OpenMeteoGeocoder::new(Client::new(), base_url(server)));
+        let client = TomorrowIoClient::new(Client::new(), "KEY".into(), base_url(server), geocoder)
+            .with_retry_policy(RetryPolicy::none());
+
+        (client, geocoding)
+    }
+
+    #[test]
+    fn maps_daily_forecast_for_geocoded_coordinates() {
+        let mut server = Server::new();
+        let forecast = server
+            .mock("GET", "/v4/weather/forecast")
+            .match_query(Matcher::Regex(
+                "^location=50.45466%2C30.5238&timesteps=1d&units=metric&apikey=KEY$".into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(FORECAST_BODY)
+            .create();
+        let (client, _geocoding) = client(&mut server);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        forecast.assert();
+        assert_eq!(
+            report,
+            WeatherReport {
+                provider: Provider::TomorrowIo,
+                condition: WeatherCondition::Cloudy,
+                description: "Cloudy".into(),
+                max_temperature: 4.13,
+                min_temperature: -1.5,
+                avg_temperature: Some(1.24),
+                feels_like: Some(-2.8),
+                uv_index: Some(1.0),
+                wind_kph: Some(4.5 * KPH_PER_MPS),
+                wind_direction: Some("NW".into()),
+                humidity: Some(81.2),
+                ..testing::report()
+            }
+        );
+    }
+
+    #[test]
+    fn maps_requested_day_with_missing_optional_values() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/v4/weather/forecast")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(FORECAST_BODY)
+            .create();
+        let (client, _geocoding) = client(&mut server);
+
+        let report = client.get_weather("Kyiv".into(), 1).unwrap();
+
+        assert_eq!(report.date, "2024-11-30");
+        assert_eq!(report.condition, WeatherCondition::Snow);
+        assert_eq!(report.description, "Light Snow");
+        assert_eq!(report.avg_temperature, None);
+        assert_eq!(report.wind_kph, None);
+    }
+
+    #[test]
+    fn unauthorized_returns_invalid_api_key() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/v4/weather/forecast")
+            .match_query(Matcher::Any)
+            .with_status(401)
+            .create();
+        let (client, _geocoding) = client(&mut server);
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::InvalidApiKey(Provider::TomorrowIo)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn maps_realtime_conditions() {
+        let mut server = Server::new();
+        let realtime = server
+            .mock("GET", "/v4/weather/realtime")
+            .match_query(Matcher::Regex(
+                "^location=50.45466%2C30.5238&units=metric&apikey=KEY$".into(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"data": {
+                    "time": "2024-11-29T10:15:00Z",
+                    "values": {
+                        "temperature": 2.5, "temperatureApparent": -0.8, "humidity": 84,
+                        "windSpeed": 3.0, "windDirection": 100.0, "weatherCode": 1001
+                    }
+                }}"#,
+            )
+            .create();
+        let (client, _geocoding) = client(&mut server);
+
+        let current = client.get_current("Kyiv").unwrap();
+
+        realtime.assert();
+        assert_eq!(
+            current,
+            CurrentConditions {
+                provider: Provider::TomorrowIo,
+                location: "Kyiv, Ukraine".into(),
+                observed_at: "2024-11-29T10:15:00Z".parse().unwrap(),
+                condition: WeatherCondition::Cloudy,
+                description: "Cloudy".into(),
+                temperature: 2.5,
+                feels_like: Some(-0.8),
+                humidity: Some(84.0),
+                wind_kph: Some(3.0 * KPH_PER_MPS),
+                wind_direction: Some("E".into()),
+            }
+        );
+    }
+
+    #[rstest]
+    #[case(1000, "Clear, Sunny", WeatherCondition::Clear)]
+    #[case(1101, "Partly Cloudy", WeatherCondition::PartlyCloudy)]
+    #[case(2100, "Light Fog", WeatherCondition::Fog)]
+    #[case(4201, "Heavy Rain", WeatherCondition::Rain)]
+    #[case(8000, "Thunderstorm", WeatherCondition::Thunderstorm)]
+    #[case(9999, "Unknown", WeatherCondition::Unknown)]
+    fn translates_weather_codes(
+        #[case] code: i64,
+        #[case] phrase: &str,
+        #[case] condition: WeatherCondition,
+    ) {
+        assert_eq!(description(code), phrase);
+        assert_eq!(WeatherCondition::from_tomorrowio_code(code), condition);
+    }
+
+    #[rstest]
+    #[case(0.0, "N")]
+    #[case(312.4, "NW")]
+    #[case(350.0, "N")]
+    #[case(100.0, "E")]
+    fn maps_degrees_to_compass_points(#[case] degrees: f64, #[case] expected: &str) {
+        assert_eq!(compass_point(degrees), expected);
+    }
+}
@@ -1,103 +1,423 @@
-use crate::apis::{ProviderClient, WeatherReport};
+use crate::air_quality::{AirQuality, AqiCategory};
+use crate::alert::WeatherAlert;
+use crate::apis::debug_log::{DebugLog, send_logged};
+use crate::apis::quota::{self, QuotaObserver};
+use crate::apis::rate_limiter::RateLimitBudget;
+use crate::apis::request_span::RequestSpan;
+use crate::apis::retry::RetryPolicy;
+use crate::apis::{
+    CurrentConditions, DEFAULT_LANGUAGE, ProviderClient, ResolvedLocation, VERIFY_ADDRESS,
+    WeatherReport, check_status, ping_result,
+};
+use crate::condition::WeatherCondition;
+use crate::error::WeatherError;
 use crate::provider::Provider;
-use anyhow::{Context, Result, anyhow};
-use reqwest::Url;
+use chrono::{DateTime, NaiveTime};
 use reqwest::blocking::Client;
 use reqwest::header::AUTHORIZATION;
+use reqwest::{StatusCode, Url};
 use serde::Deserialize;
-use tracing::debug;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt::Debug;
+use std::sync::Arc;
+use tracing::{debug, trace};
+
+/// Production WeatherAPI base URL.
+pub(crate) const BASE_URL: &str = "https://api.weatherapi.com/v1/";
+
+/// WeatherAPI error code for "No matching location found."
+const NO_MATCHING_LOCATION_CODE: i64 = 1006;
 
 /// Http client for WeatherAPI
 #[derive(Debug)]
-pub struct WeatherApiClient<'a> {
+pub struct WeatherApiClient {
     api_key: String,
-    url: &'a str,
+    url: String,
+    language: String,
+    /// Additional query pairs appended verbatim to the forecast request.
+    extra_params: Vec<(String, String)>,
+    /// Whether to include air quality in reports.
+    air_quality: bool,
+    /// Whether to include weather alerts in reports.
+    alerts: bool,
+    quota_observer: Option<Arc<dyn QuotaObserver>>,
+    retry: RetryPolicy,
+    /// Spent once per HTTP request sent, if set.
+    rate_limit: Option<Arc<RateLimitBudget>>,
+    /// Records every HTTP exchange, if set.
+    debug_log: Option<Arc<DebugLog>>,
     client: Client,
 }
 
-impl WeatherApiClient<'static> {
-    pub fn new(api_key: String) -> Self {
+impl WeatherApiClient {
+    /// Create client sending requests to the given base URL over a shared HTTP `client`.
+    pub(crate) fn new(client: Client, api_key: String, url: impl Into<String>) -> Self {
         Self {
             api_key,
-            url: "https://api.weatherapi.com/v1/",
-            client: Client::new(),
+            url: url.into(),
+            language: DEFAULT_LANGUAGE.to_string(),
+            extra_params: Vec::new(),
+            air_quality: false,
+            alerts: false,
+            quota_observer: None,
+            retry: RetryPolicy::default(),
+            rate_limit: None,
+            debug_log: None,
+            client,
         }
     }
 
-    fn get(&self, mut url: Url) -> Result<reqwest::blocking::Response> {
+    /// Limit HTTP requests to `budget`, shared with other clients of the provider.
+    pub(crate) fn with_rate_limit(mut self, budget: Option<Arc<RateLimitBudget>>) -> Self {
+        self.rate_limit = budget;
+        self
+    }
+
+    /// Record every HTTP exchange in `log`.
+    pub(crate) fn with_debug_log(mut self, log: Option<Arc<DebugLog>>) -> Self {
+        self.debug_log = log;
+        self
+    }
+
+    /// Set how failed requests are retried.
+    pub(crate) fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set language for localized condition text.
+    pub(crate) fn with_language(mut self, language: String) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Include air quality in reports.
+    pub(crate) fn with_air_quality(mut self, air_quality: bool) -> Self {
+        self.air_quality = air_quality;
+        self
+    }
+
+    /// Include weather alerts in reports.
+    pub(crate) fn with_alerts(mut self, alerts: bool) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// Report quota from response headers to `observer`.
+    pub(crate) fn with_quota_observer(mut self, observer: Option<Arc<dyn QuotaObserver>>) -> Self {
+        self.quota_observer = observer;
+        self
+    }
+
+    /// Append extra query pairs to the forecast request.
+    pub(crate) fn with_extra_params(mut self, extra_params: Vec<(String, String)>) -> Self {
+        self.extra_params = extra_params;
+        self
+    }
+
+    fn get(&self, mut url: Url) -> Result<reqwest::blocking::Response, WeatherError> {
         {
             let mut qp = url.query_pairs_mut();
             qp.append_pair("key", &self.api_key);
         }
-        self.client
+        let request = self
+            .client
             .get(url)
-            .header(AUTHORIZATION, format!("Bearer {}", self.api_key))
-            .send()
-            .context("failed to send request to AccuWeather API")?
-            .error_for_status()
-            .context("AccuWeather API returned error status")
-    }
-
-    fn forecast_request(&self, address: String, days: u32) -> Result<WeatherApiResponse> {
-        let mut url = Url::parse(self.url).context("Error parsing WeatherAPI URL")?;
-        url = url
-            .join("forecast.json")
-            .context("Error joining WeatherAPI URL")?;
+            .header(AUTHORIZATION, format!("Bearer {}", self.api_key));
+        let resp = send_logged(
+            request,
+            &self.retry,
+            self.rate_limit.as_deref(),
+            self.debug_log.as_deref(),
+        )?;
+
+        if let Some(observer) = &self.quota_observer
+            && let Some(status) = quota::from_headers(Provider::WeatherApi, resp.headers())
+        {
+            observer.observe(&status);
+        }
+
+        if resp.status() == StatusCode::BAD_REQUEST {
+            let body: WeatherApiErrorResponse = resp.json()?;
+            debug!("WeatherAPI error body: {body:?}");
+
+            return Err(match body.error.code {
+                NO_MATCHING_LOCATION_CODE => WeatherError::AddressNotFound,
+                _ => WeatherError::UnexpectedResponse(body.error.message),
+            });
+        }
+
+        check_status(resp, Provider::WeatherApi)
+    }
+
+    fn forecast_request<T: DeserializeOwned + Debug>(
+        &self,
+        address: String,
+        days: u32,
+    ) -> Result<T, WeatherError> {
+        let mut url = Url::parse(&self.url)?;
+        url = url.join("forecast.json")?;
         {
             let mut qp = url.query_pairs_mut();
             qp.append_pair("q", &address);
             qp.append_pair("days", &(days).to_string());
+            qp.append_pair("lang", &self.language);
+            if self.air_quality {
+                qp.append_pair("aqi", "yes");
+            }
+            if self.alerts {
+                qp.append_pair("alerts", "yes");
+            }
+            qp.extend_pairs(&self.extra_params);
         }
-        debug!("WeatherAPI URL: {url:?}");
+        trace!("WeatherAPI URL: {url:?}");
 
         let resp = self.get(url)?;
 
-        debug!("WeatherAPI response: {resp:?}");
+        trace!("WeatherAPI response: {resp:?}");
 
-        let body: WeatherApiResponse = resp
-            .json()
-            .context("failed to deserialize WeatherAPI JSON")?;
-        debug!("WeatherAPI body: {body:?}");
+        let body = resp.json()?;
+        trace!("WeatherAPI body: {body:?}");
 
         Ok(body)
     }
-}
 
-impl ProviderClient for WeatherApiClient<'static> {
-    fn get_weather(&self, address: String, day_from_today: u32) -> Result<WeatherReport> {
-        debug!("Getting weather for address `{address} day from today: {day_from_today}`");
-        let days = day_from_today + 1;
-
-        if days > 14 {
-            return Err(anyhow!(
-                "WeatherAPI only supports up to 14 days forecast (including today)."
-            ));
+    fn current_request(&self, address: &str) -> Result<WeatherApiCurrentResponse, WeatherError> {
+        let mut url = Url::parse(&self.url)?;
+        url = url.join("current.json")?;
+        {
+            let mut qp = url.query_pairs_mut();
+            qp.append_pair("q", address);
+            qp.append_pair("lang", &self.language);
         }
+        trace!("WeatherAPI URL: {url:?}");
 
-        let body = self.forecast_request(address, days)?;
+        let resp = self.get(url)?;
+
+        let body = resp.json()?;
+        trace!("WeatherAPI current body: {body:?}");
+
+        Ok(body)
+    }
+
+    /// Look the address up with the timezone endpoint, which, unlike `search.json`,
+    /// also returns the location's timezone.
+    fn timezone_request(&self, address: &str) -> Result<WeatherApiTimezoneResponse, WeatherError> {
+        let mut url = Url::parse(&self.url)?;
+        url = url.join("timezone.json")?;
+        url.query_pairs_mut().append_pair("q", address);
+        trace!("WeatherAPI URL: {url:?}");
+
+        let resp = self.get(url)?;
+
+        let body = resp.json()?;
+        trace!("WeatherAPI timezone body: {body:?}");
 
+        Ok(body)
+    }
+
+    /// Build report for the requested day of a forecast response.
+    fn report(
+        body: WeatherApiResponse,
+        day_from_today: u32,
+    ) -> Result<WeatherReport, WeatherError> {
         let forecast = body
             .forecast
             .forecastday
             .get(day_from_today as usize)
-            .context("wrong number of days in API response")?;
+            .ok_or_else(|| {
+                WeatherError::UnexpectedResponse("wrong number of days in API response".into())
+            })?;
         debug!("WeatherAPI forecast: {forecast:?}");
 
         Ok(WeatherReport {
             provider: Provider::WeatherApi,
             date: forecast.date.clone(),
             location: format!("{}, {}", body.location.name, body.location.country),
+            condition: WeatherCondition::from_weatherapi_code(forecast.day.condition.code),
             description: forecast.day.condition.text.clone(),
             max_temperature: forecast.day.maxtemp_c,
             min_temperature: forecast.day.mintemp_c,
+            avg_temperature: forecast.day.avgtemp_c,
+            feels_like: forecast.feels_like(),
+            uv_index: forecast.day.uv,
+            wind_kph: forecast.day.maxwind_kph,
+            // Only hourly data has a direction, the day summary doesn't.
+            wind_direction: None,
+            humidity: forecast.day.avghumidity,
+            sunrise: forecast.astro.as_ref().and_then(|a| parse_time(&a.sunrise)),
+            sunset: forecast.astro.as_ref().and_then(|a| parse_time(&a.sunset)),
+            air_quality: forecast
+                .day
+                .air_quality
+                .as_ref()
+                .and_then(WeatherApiAirQuality::to_air_quality),
+            // Alerts are current, not per forecast day.
+            alerts: body.alerts.map(|alerts| {
+                alerts
+                    .alert
+                    .into_iter()
+                    .map(WeatherApiAlert::into_alert)
+                    .collect()
+            }),
+            stale_since: None,
         })
     }
 }
 
+impl ProviderClient for WeatherApiClient {
+    fn get_weather(
+        &self,
+        address: String,
+        day_from_today: u32,
+    ) -> Result<WeatherReport, WeatherError> {
+        let _span = RequestSpan::enter(Provider::WeatherApi, "forecast", &address);
+        debug!("Getting weather {day_from_today} days from today");
+        let days = day_from_today + 1;
+
+        let body = self.forecast_request(address, days)?;
+
+        Self::report(body, day_from_today)
+    }
+
+    fn resolve_location(&self, address: &str) -> Result<ResolvedLocation, WeatherError> {
+        let _span = RequestSpan::enter(Provider::WeatherApi, "location", address);
+        let location = self.timezone_request(address)?.location;
+
+        Ok(ResolvedLocation {
+            name: location.name,
+            country: location.country,
+            lat: location.lat,
+            lon: location.lon,
+            provider_key: None,
+            timezone: Some(location.tz_id),
+        })
+    }
+
+    fn get_weather_at(
+        &self,
+        location: &ResolvedLocation,
+        day_from_today: u32,
+    ) -> Result<WeatherReport, WeatherError> {
+        let _span = RequestSpan::enter(Provider::WeatherApi, "forecast", &location.display_name());
+        debug!("Getting weather {day_from_today} days from today");
+
+        // Coordinates skip WeatherAPI's own geocoding.
+        let query = format!("{},{}", location.lat, location.lon);
+        let body = self.forecast_request(query, day_from_today + 1)?;
+
+        Self::report(body, day_from_today)
+    }
+
+    fn get_weather_raw(&self, address: String, day_from_today: u32) -> Result<Value, WeatherError> {
+        let _span = RequestSpan::enter(Provider::WeatherApi, "forecast_raw", &address);
+        debug!("Getting raw weather {day_from_today} days from today");
+
+        self.forecast_request(address, day_from_today + 1)
+    }
+
+    fn get_current(&self, address: &str) -> Result<CurrentConditions, WeatherError> {
+        let _span = RequestSpan::enter(Provider::WeatherApi, "current", address);
+
+        let body = self.current_request(address)?;
+        let current = body.current;
+        let observed_at = DateTime::from_timestamp(current.last_updated_epoch, 0)
+            .ok_or_else(|| WeatherError::UnexpectedResponse("invalid observation time".into()))?;
+
+        Ok(CurrentConditions {
+            provider: Provider::WeatherApi,
+            location: format!("{}, {}", body.location.name, body.location.country),
+            observed_at,
+            condition: WeatherCondition::from_weatherapi_code(current.condition.code),
+            description: current.condition.text,
+            temperature: current.temp_c,
+            feels_like: current.feelslike_c,
+            humidity: current.humidity,
+            wind_kph: current.wind_kph,
+            wind_direction: current.wind_dir,
+        })
+    }
+
+    fn max_forecast_days(&self) -> u32 {
+        14
+    }
+
+    fn ping(&self) -> Result<(), WeatherError> {
+        // Search only returns matching locations, far smaller than a forecast.
+        let mut url = Url::parse(&self.url)?;
+        url = url.join("search.json")?;
+        url.query_pairs_mut().append_pair("q", VERIFY_ADDRESS);
+        debug!("Pinging WeatherAPI: {url:?}");
+
+        ping_result(self.get(url), Provider::WeatherApi)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherApiCurrentResponse {
+    location: WeatherApiLocation,
+    current: WeatherApiCurrent,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherApiCurrent {
+    last_updated_epoch: i64,
+    temp_c: f64,
+    feelslike_c: Option<f64>,
+    humidity: Option<f64>,
+    wind_kph: Option<f64>,
+    wind_dir: Option<String>,
+    condition: WeatherApiCondition,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherApiErrorResponse {
+    error: WeatherApiError,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherApiError {
+    code: i64,
+    message: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct WeatherApiResponse {
     location: WeatherApiLocation,
     forecast: WeatherApiForecast,
+    /// Only returned with `alerts=yes`.
+    alerts: Option<WeatherApiAlerts>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherApiAlerts {
+    alert: Vec<WeatherApiAlert>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherApiAlert {
+    headline: String,
+    severity: Option<String>,
+    /// Semicolon-separated area names, empty for some agencies.
+    #[serde(default)]
+    areas: String,
+}
+
+impl WeatherApiAlert {
+    fn into_alert(self) -> WeatherAlert {
+        WeatherAlert {
+            headline: self.headline,
+            severity: self.severity.filter(|severity| !severity.is_empty()),
+            areas: self
+                .areas
+                .split(';')
+                .map(str::trim)
+                .filter(|area| !area.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -105,6 +425,21 @@ struct WeatherApiForecast {
     forecastday: Vec<WeatherApiForecastDay>,
 }
 
+#[derive(Debug, Deserialize)]
+struct WeatherApiTimezoneResponse {
+    location: WeatherApiTimezoneLocation,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherApiTimezoneLocation {
+    name: String,
+    country: String,
+    lat: f64,
+    lon: f64,
+    /// IANA timezone, e.g. "Europe/Kiev".
+    tz_id: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct WeatherApiLocation {
     name: String,
@@ -115,16 +450,579 @@ struct WeatherApiLocation {
 struct WeatherApiForecastDay {
     date: String,
     day: WeatherApiDay,
+    astro: Option<WeatherApiAstro>,
+    #[serde(default)]
+    hour: Vec<WeatherApiHour>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherApiAstro {
+    sunrise: String,
+    sunset: String,
+}
+
+/// Parse astro time like "07:31 AM", "No sunrise" during polar night/day yields `None`.
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%I:%M %p").ok()
+}
+
+impl WeatherApiForecastDay {
+    /// Day summary has no apparent temperature, so average the hourly values.
+    fn feels_like(&self) -> Option<f64> {
+        if self.hour.is_empty() {
+            return None;
+        }
+
+        let sum: f64 = self.hour.iter().map(|h| h.feelslike_c).sum();
+        Some(sum / self.hour.len() as f64)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct WeatherApiHour {
+    feelslike_c: f64,
 }
 
 #[derive(Debug, Deserialize)]
 struct WeatherApiDay {
     maxtemp_c: f64,
     mintemp_c: f64,
+    avgtemp_c: Option<f64>,
+    maxwind_kph: Option<f64>,
+    avghumidity: Option<f64>,
+    uv: Option<f64>,
+    /// Only returned with `aqi=yes`.
+    air_quality: Option<WeatherApiAirQuality>,
     condition: WeatherApiCondition,
 }
 
+#[derive(Debug, Deserialize)]
+struct WeatherApiAirQuality {
+    pm2_5: Option<f64>,
+    pm10: Option<f64>,
+    #[serde(rename = "us-epa-index")]
+    us_epa_index: Option<i64>,
+}
+
+impl WeatherApiAirQuality {
+    fn to_air_quality(&self) -> Option<AirQuality> {
+        Some(AirQuality {
+            index: None,
+            category: AqiCategory::from_us_epa_index(self.us_epa_index?)?,
+            pm2_5: self.pm2_5,
+            pm10: self.pm10,
+        })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct WeatherApiCondition {
+    code: i64,
     text: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use mockito::{Matcher, Server};
+    use rstest::rstest;
+
+    fn base_url(server: &Server) -> String {
+        format!("{}/", server.url())
+    }
+
+    #[test]
+    fn unauthorized_returns_invalid_api_key() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_status(401)
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::InvalidApiKey(Provider::WeatherApi)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn maps_current_conditions() {
+        let mut server = Server::new();
+        let current = server
+            .mock("GET", "/current.json")
+            .match_query(Matcher::Regex("^q=Kyiv&lang=en&key=KEY$".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "location": {"name": "Kyiv", "country": "Ukraine"},
+                    "current": {
+                        "last_updated_epoch": 1732875300, "temp_c": 2.5, "feelslike_c": -0.8,
+                        "humidity": 84, "wind_kph": 10.8, "wind_dir": "E",
+                        "condition": {"code": 1003, "text": "Partly cloudy"}
+                    }
+                }"#,
+            )
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let conditions = client.get_current("Kyiv").unwrap();
+
+        current.assert();
+        assert_eq!(
+            conditions,
+            CurrentConditions {
+                provider: Provider::WeatherApi,
+                location: "Kyiv, Ukraine".into(),
+                observed_at: "2024-11-29T10:15:00Z".parse().unwrap(),
+                condition: WeatherCondition::from_weatherapi_code(1003),
+                description: "Partly cloudy".into(),
+                temperature: 2.5,
+                feels_like: Some(-0.8),
+                humidity: Some(84.0),
+                wind_kph: Some(10.8),
+                wind_direction: Some("E".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn forbidden_returns_invalid_api_key() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_status(403)
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::InvalidApiKey(Provider::WeatherApi)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[rstest]
+    #[case::ok(200)]
+    #[case::unauthorized(401)]
+    #[case::outage(503)]
+    fn ping_tells_auth_failure_from_outage(#[case] status: usize) {
+        let mut server = Server::new();
+        let search = server
+            .mock("GET", "/search.json")
+            .match_query(Matcher::UrlEncoded("key".into(), "KEY".into()))
+            .with_status(status)
+            .with_body("[]")
+            .expect(1)
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url)
+            .with_retry_policy(RetryPolicy::none());
+
+        let result = client.ping();
+
+        match status {
+            200 => assert!(result.is_ok(), "{result:?}"),
+            401 => assert!(
+                matches!(
+                    result,
+                    Err(WeatherError::InvalidApiKey(Provider::WeatherApi))
+                ),
+                "{result:?}"
+            ),
+            _ => assert!(
+                matches!(
+                    result,
+                    Err(WeatherError::ProviderUnavailable(Provider::WeatherApi))
+                ),
+                "{result:?}"
+            ),
+        }
+        search.assert();
+    }
+
+    #[test]
+    fn too_many_requests_returns_rate_limited() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_status(429)
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url)
+            .with_retry_policy(RetryPolicy::none());
+
+        let err = client.get_weather("Kyiv".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::RateLimited),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn no_matching_location_returns_address_not_found() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error":{"code":1006,"message":"No matching location found."}}"#)
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let err = client.get_weather("Nowhere".into(), 0).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::AddressNotFound),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    fn forecast_body(hours: &str) -> String {
+        format!(
+            r#"{{
+                "location": {{"name": "Kyiv", "country": "Ukraine"}},
+                "forecast": {{"forecastday": [{{
+                    "date": "2024-11-29",
+                    "day": {{"maxtemp_c": 12.0, "mintemp_c": 3.0, "avgtemp_c": 7.4, "maxwind_kph": 18.4, "avghumidity": 72.0, "uv": 4.0, "condition": {{"code": 1000, "text": "Sunny"}}}}
+                    {hours}
+                }}]}}
+            }}"#
+        )
+    }
+
+    fn mock_forecast(server: &mut Server, body: String) -> mockito::Mock {
+        server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create()
+    }
+
+    #[test]
+    fn resolves_location_and_queries_forecast_by_coordinates() {
+        let mut server = Server::new();
+        let search = server
+            .mock("GET", "/timezone.json")
+            .match_query(Matcher::UrlEncoded("q".into(), "Kyiv".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"location": {
+                    "name": "Kyiv", "country": "Ukraine", "lat": 50.43, "lon": 30.52,
+                    "tz_id": "Europe/Kiev", "localtime": "2024-11-29 14:05"
+                }}"#,
+            )
+            .expect(1)
+            .create();
+        let forecast = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::UrlEncoded("q".into(), "50.43,30.52".into()))
+            .with_header("content-type", "application/json")
+            .with_body(forecast_body(""))
+            .expect(2)
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let location = client.resolve_location("Kyiv").unwrap();
+        client.get_weather_at(&location, 0).unwrap();
+        let report = client.get_weather_at(&location, 0).unwrap();
+
+        assert_eq!(location.display_name(), "Kyiv, Ukraine");
+        assert_eq!(location.timezone.as_deref(), Some("Europe/Kiev"));
+        assert_eq!(report.location, "Kyiv, Ukraine");
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn unknown_location_is_not_resolved() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/timezone.json")
+            .match_query(Matcher::Any)
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error":{"code":1006,"message":"No matching location found."}}"#)
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let err = client.resolve_location("Nowhere").unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::AddressNotFound),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn maps_astro_times_and_ignores_missing_sunrise() {
+        let mut server = Server::new();
+        let _m = mock_forecast(
+            &mut server,
+            forecast_body(r#","astro": {"sunrise": "No sunrise", "sunset": "04:02 PM"}"#),
+        );
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.sunrise, None);
+        assert_eq!(report.sunset, NaiveTime::from_hms_opt(16, 2, 0));
+    }
+
+    #[test]
+    fn maps_hourly_feels_like_average() {
+        let mut server = Server::new();
+        let _m = mock_forecast(
+            &mut server,
+            forecast_body(r#","hour": [{"feelslike_c": 2.0}, {"feelslike_c": 6.0}]"#),
+        );
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(
+            report,
+            WeatherReport {
+                avg_temperature: Some(7.4),
+                feels_like: Some(4.0),
+                uv_index: Some(4.0),
+                wind_kph: Some(18.4),
+                humidity: Some(72.0),
+                ..testing::report()
+            }
+        );
+    }
+
+    #[test]
+    fn missing_avg_temperature_maps_to_none() {
+        let mut server = Server::new();
+        let body = forecast_body("").replace(r#""avgtemp_c": 7.4, "#, "");
+        let _m = mock_forecast(&mut server, body);
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.avg_temperature, None);
+    }
+
+    #[test]
+    fn missing_humidity_maps_to_none() {
+        let mut server = Server::new();
+        let body = forecast_body("").replace(r#""avghumidity": 72.0, "#, "");
+        let _m = mock_forecast(&mut server, body);
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.humidity, None);
+    }
+
+    #[test]
+    fn raw_returns_untouched_forecast_body() {
+        let mut server = Server::new();
+        let _m = mock_forecast(
+            &mut server,
+            forecast_body(r#","astro": {"sunrise": "07:31 AM"}"#),
+        );
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let body = client.get_weather_raw("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(body["location"]["name"], "Kyiv");
+        assert_eq!(
+            body["forecast"]["forecastday"][0]["astro"]["sunrise"],
+            "07:31 AM"
+        );
+    }
+
+    #[test]
+    fn requests_and_maps_air_quality_when_enabled() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::UrlEncoded("aqi".into(), "yes".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "location": {"name": "Kyiv", "country": "Ukraine"},
+                    "forecast": {"forecastday": [{
+                        "date": "2024-11-29",
+                        "day": {
+                            "maxtemp_c": 12.0, "mintemp_c": 3.0,
+                            "condition": {"code": 1000, "text": "Sunny"},
+                            "air_quality": {"pm2_5": 3.1, "pm10": 4.5, "us-epa-index": 1}
+                        }
+                    }]}
+                }"#,
+            )
+            .create();
+        let url = base_url(&server);
+        let client =
+            WeatherApiClient::new(Client::new(), "KEY".into(), &url).with_air_quality(true);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(
+            report.air_quality,
+            Some(AirQuality {
+                index: None,
+                category: AqiCategory::Good,
+                pm2_5: Some(3.1),
+                pm10: Some(4.5),
+            })
+        );
+    }
+
+    #[test]
+    fn does_not_request_air_quality_by_default() {
+        let mut server = Server::new();
+        let m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::Regex("^q=Kyiv&days=1&lang=en&key=KEY$".into()))
+            .with_header("content-type", "application/json")
+            .with_body(forecast_body(""))
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.air_quality, None);
+        assert_eq!(report.alerts, None);
+        m.assert();
+    }
+
+    #[test]
+    fn requests_and_maps_alerts_when_enabled() {
+        let mut server = Server::new();
+        let _m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::UrlEncoded("alerts".into(), "yes".into()))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "location": {"name": "Kyiv", "country": "Ukraine"},
+                    "forecast": {"forecastday": [{
+                        "date": "2024-11-29",
+                        "day": {"maxtemp_c": 12.0, "mintemp_c": 3.0, "condition": {"code": 1000, "text": "Sunny"}}
+                    }]},
+                    "alerts": {"alert": [
+                        {"headline": "Flood Warning", "severity": "Moderate", "areas": "Kyiv; Brovary;"},
+                        {"headline": "Wind Advisory", "severity": "", "areas": ""}
+                    ]}
+                }"#,
+            )
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url).with_alerts(true);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(
+            report.alerts,
+            Some(vec![
+                WeatherAlert {
+                    headline: "Flood Warning".into(),
+                    severity: Some("Moderate".into()),
+                    areas: vec!["Kyiv".into(), "Brovary".into()],
+                },
+                WeatherAlert {
+                    headline: "Wind Advisory".into(),
+                    severity: None,
+                    areas: vec![],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn no_active_alerts_map_to_empty_list() {
+        let mut server = Server::new();
+        let _m = mock_forecast(
+            &mut server,
+            forecast_body("").replacen('{', r#"{"alerts": {"alert": []},"#, 1),
+        );
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url).with_alerts(true);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.alerts, Some(vec![]));
+    }
+
+    #[test]
+    fn missing_hourly_data_maps_feels_like_to_none() {
+        let mut server = Server::new();
+        let _m = mock_forecast(&mut server, forecast_body(""));
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        let report = client.get_weather("Kyiv".into(), 0).unwrap();
+
+        assert_eq!(report.feels_like, None);
+    }
+
+    #[test]
+    fn sends_language_param() {
+        let mut server = Server::new();
+        let m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::UrlEncoded("lang".into(), "uk".into()))
+            .with_header("content-type", "application/json")
+            .with_body(forecast_body(""))
+            .create();
+        let url = base_url(&server);
+        let client =
+            WeatherApiClient::new(Client::new(), "KEY".into(), &url).with_language("uk".into());
+
+        client.get_weather("Kyiv".into(), 0).unwrap();
+
+        m.assert();
+    }
+
+    #[test]
+    fn defaults_to_english() {
+        let mut server = Server::new();
+        let m = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::UrlEncoded("lang".into(), "en".into()))
+            .with_header("content-type", "application/json")
+            .with_body(forecast_body(""))
+            .create();
+        let url = base_url(&server);
+        let client = WeatherApiClient::new(Client::new(), "KEY".into(), &url);
+
+        client.get_weather("Kyiv".into(), 0).unwrap();
+
+        m.assert();
+    }
+
+    #[test]
+    fn supports_fourteen_days() {
+        assert_eq!(
+            WeatherApiClient::new(Client::new(), "KEY".into(), BASE_URL).max_forecast_days(),
+            14
+        );
+    }
+}
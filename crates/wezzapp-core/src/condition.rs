@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+/// Provider-independent weather condition.
+///
+/// Providers use their own codes and localized free text, so reports carry this
+/// normalized value for stable programmatic mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherCondition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    Sleet,
+    Snow,
+    Thunderstorm,
+    Windy,
+    Unknown,
+}
+
+impl WeatherCondition {
+    /// Map WeatherAPI `condition.code`.
+    ///
+    /// See https://www.weatherapi.com/docs/weather_conditions.json
+    pub fn from_weatherapi_code(code: i64) -> Self {
+        match code {
+            1000 => Self::Clear,
+            1003 => Self::PartlyCloudy,
+            1006 | 1009 => Self::Cloudy,
+            1030 | 1135 | 1147 => Self::Fog,
+            1072 | 1150..=1171 => Self::Drizzle,
+            1063 | 1180..=1201 | 1240..=1246 => Self::Rain,
+            1069 | 1204 | 1207 | 1237 | 1249 | 1252 | 1261 | 1264 => Self::Sleet,
+            1066 | 1114 | 1117 | 1210..=1225 | 1255 | 1258 => Self::Snow,
+            1087 | 1273..=1282 => Self::Thunderstorm,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Map AccuWeather icon number.
+    ///
+    /// See https://developer.accuweather.com/weather-icons
+    pub fn from_accuweather_icon(icon: i64) -> Self {
+        match icon {
+            1 | 2 | 30 | 33 | 34 => Self::Clear,
+            3 | 4 | 35 | 36 => Self::PartlyCloudy,
+            6..=8 | 38 => Self::Cloudy,
+            5 | 11 | 37 => Self::Fog,
+            12..=14 | 18 | 39 | 40 => Self::Rain,
+            15..=17 | 41 | 42 => Self::Thunderstorm,
+            19..=23 | 43 | 44 => Self::Snow,
+            24..=26 | 29 => Self::Sleet,
+            32 => Self::Windy,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Map Tomorrow.io `weatherCode`.
+    ///
+    /// See https://docs.tomorrow.io/reference/data-layers-weather-codes
+    pub fn from_tomorrowio_code(code: i64) -> Self {
+        match code {
+            1000 | 1100 => Self::Clear,
+            1101 => Self::PartlyCloudy,
+            1001 | 1102 => Self::Cloudy,
+            2000 | 2100 => Self::Fog,
+            4000 => Self::Drizzle,
+            4001 | 4200 | 4201 => Self::Rain,
+            5000 | 5001 | 5100 | 5101 => Self::Snow,
+            6000 | 6001 | 6200 | 6201 | 7000 | 7101 | 7102 => Self::Sleet,
+            8000 => Self::Thunderstorm,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1000, WeatherCondition::Clear)]
+    #[case(1003, WeatherCondition::PartlyCloudy)]
+    #[case(1009, WeatherCondition::Cloudy)]
+    #[case(1135, WeatherCondition::Fog)]
+    #[case(1153, WeatherCondition::Drizzle)]
+    #[case(1189, WeatherCondition::Rain)]
+    #[case(1243, WeatherCondition::Rain)]
+    #[case(1207, WeatherCondition::Sleet)]
+    #[case(1225, WeatherCondition::Snow)]
+    #[case(1276, WeatherCondition::Thunderstorm)]
+    #[case(9999, WeatherCondition::Unknown)]
+    fn maps_weatherapi_codes(#[case] code: i64, #[case] expected: WeatherCondition) {
+        assert_eq!(WeatherCondition::from_weatherapi_code(code), expected);
+    }
+
+    #[rstest]
+    #[case(1, WeatherCondition::Clear)]
+    #[case(33, WeatherCondition::Clear)]
+    #[case(4, WeatherCondition::PartlyCloudy)]
+    #[case(7, WeatherCondition::Cloudy)]
+    #[case(11, WeatherCondition::Fog)]
+    #[case(18, WeatherCondition::Rain)]
+    #[case(15, WeatherCondition::Thunderstorm)]
+    #[case(22, WeatherCondition::Snow)]
+    #[case(25, WeatherCondition::Sleet)]
+    #[case(32, WeatherCondition::Windy)]
+    #[case(31, WeatherCondition::Unknown)]
+    fn maps_accuweather_icons(#[case] icon: i64, #[case] expected: WeatherCondition) {
+        assert_eq!(WeatherCondition::from_accuweather_icon(icon), expected);
+    }
+
+    #[rstest]
+    #[case(1100, WeatherCondition::Clear)]
+    #[case(1101, WeatherCondition::PartlyCloudy)]
+    #[case(1001, WeatherCondition::Cloudy)]
+    #[case(2100, WeatherCondition::Fog)]
+    #[case(4000, WeatherCondition::Drizzle)]
+    #[case(4201, WeatherCondition::Rain)]
+    #[case(5001, WeatherCondition::Snow)]
+    #[case(6200, WeatherCondition::Sleet)]
+    #[case(7102, WeatherCondition::Sleet)]
+    #[case(8000, WeatherCondition::Thunderstorm)]
+    #[case(0, WeatherCondition::Unknown)]
+    fn maps_tomorrowio_codes(#[case] code: i64, #[case] expected: WeatherCondition) {
+        assert_eq!(WeatherCondition::from_tomorrowio_code(code), expected);
+    }
+}
@@ -1,5 +1,9 @@
 use crate::provider::Provider;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Profile used when user does not specify one explicitly.
+pub const DEFAULT_PROFILE: &str = "default";
 
 /// Credentials for a concrete provider.
 /// Use enum, since each provider may have different auth fields
@@ -8,40 +12,352 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "lowercase")]
 #[cfg_attr(test, derive(Debug))]
 pub enum Credentials {
-    WeatherApi { api_key: String },
-    AccuWeather { api_key: String },
+    WeatherApi {
+        api_key: String,
+    },
+    AccuWeather {
+        api_key: String,
+        /// Regional or proxy endpoint used instead of the public API.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_url: Option<String>,
+    },
+    TomorrowIo {
+        api_key: String,
+    },
+    /// Mock provider needs no credentials.
+    #[cfg(feature = "mock")]
+    Mock,
+}
+
+/// Description of a single credential value user has to provide, e.g. an API key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialField {
+    /// Key used by `Credentials::from_fields`.
+    pub name: &'static str,
+    /// Human-readable name, e.g. "WeatherAPI API key".
+    pub label: &'static str,
+    /// Hint on where to get the value.
+    pub help: &'static str,
 }
 
 impl Credentials {
+    /// Build credentials for `provider` from values keyed by `CredentialField::name`.
+    ///
+    /// Returns `None` if any of `Provider::required_credential_fields` is missing.
+    pub fn from_fields(provider: Provider, fields: &HashMap<&str, String>) -> Option<Self> {
+        let field = |name: &str| fields.get(name).cloned();
+
+        Some(match provider {
+            Provider::WeatherApi => Credentials::WeatherApi {
+                api_key: field("api_key")?,
+            },
+            Provider::AccuWeather => Credentials::AccuWeather {
+                api_key: field("api_key")?,
+                base_url: field("base_url"),
+            },
+            Provider::TomorrowIo => Credentials::TomorrowIo {
+                api_key: field("api_key")?,
+            },
+            #[cfg(feature = "mock")]
+            Provider::Mock => Credentials::Mock,
+        })
+    }
+
+    /// Values keyed by `CredentialField::name`, the inverse of `from_fields`.
+    pub fn fields(&self) -> HashMap<&'static str, String> {
+        let mut fields = HashMap::new();
+        match self {
+            Credentials::WeatherApi { api_key } | Credentials::TomorrowIo { api_key } => {
+                fields.insert("api_key", api_key.clone());
+            }
+            Credentials::AccuWeather { api_key, base_url } => {
+                fields.insert("api_key", api_key.clone());
+                if let Some(base_url) = base_url {
+                    fields.insert("base_url", base_url.clone());
+                }
+            }
+            #[cfg(feature = "mock")]
+            Credentials::Mock => {}
+        }
+        fields
+    }
+
     /// Return which provider these credentials belong to.
     pub fn provider(&self) -> Provider {
         match self {
             Credentials::WeatherApi { .. } => Provider::WeatherApi,
             Credentials::AccuWeather { .. } => Provider::AccuWeather,
+            Credentials::TomorrowIo { .. } => Provider::TomorrowIo,
+            #[cfg(feature = "mock")]
+            Credentials::Mock => Provider::Mock,
         }
     }
 }
 
-/// Abstraction over a storage for credentials and default provider.
+/// Storage of credentials and default provider of each profile.
 ///
 /// Different frontends (CLI, GUI, etc.) can have their own implementations:
 /// - TOML file
 /// - OS keychain
 /// - encrypted DB
 pub trait CredentialsStore {
-    /// Set credentials for the given provider.
+    /// Set credentials for the given provider within a named profile.
     fn set_credentials(
         &mut self,
+        profile: &str,
         provider: Provider,
         credentials: &Credentials,
     ) -> anyhow::Result<()>;
 
-    /// Get credentials for the given provider.
-    fn get_credentials(&self, provider: Provider) -> anyhow::Result<Option<Credentials>>;
+    /// Get credentials for the given provider within a named profile.
+    fn get_credentials(
+        &self,
+        profile: &str,
+        provider: Provider,
+    ) -> anyhow::Result<Option<Credentials>>;
+
+    /// Set the default provider of a named profile, used when user does not specify it explicitly.
+    fn set_default_provider(&mut self, profile: &str, provider: Provider) -> anyhow::Result<()>;
+
+    /// Get the default provider of a named profile, if configured.
+    fn get_default_provider(&self, profile: &str) -> anyhow::Result<Option<Provider>>;
+
+    /// Names of all profiles, sorted.
+    fn list_profiles(&self) -> anyhow::Result<Vec<String>>;
+}
+
+/// Storage of user preferences shared by all profiles.
+pub trait SettingsStore {
+    /// Set the language for provider condition text.
+    fn set_language(&mut self, language: &str) -> anyhow::Result<()>;
+
+    /// Get the language for provider condition text, if configured.
+    fn get_language(&self) -> anyhow::Result<Option<String>>;
+
+    /// Save `address` under a short alias, e.g. "home".
+    fn set_location_alias(&mut self, alias: &str, address: &str) -> anyhow::Result<()>;
+
+    /// Get address saved under `alias`, if any.
+    fn get_location_alias(&self, alias: &str) -> anyhow::Result<Option<String>>;
+
+    /// All saved location aliases with their addresses, sorted by alias.
+    fn list_location_aliases(&self) -> anyhow::Result<Vec<(String, String)>>;
+
+    /// Max forecast days (including today) configured for `provider`, overriding the
+    /// free plan limit, e.g. for paid plans.
+    fn get_max_forecast_days(&self, provider: Provider) -> anyhow::Result<Option<u32>>;
+
+    /// Override max forecast days (including today) of `provider`.
+    fn set_max_forecast_days(&mut self, provider: Provider, days: u32) -> anyhow::Result<()>;
+}
+
+/// Storage of locally counted provider calls, for providers without quota headers.
+pub trait UsageStore {
+    /// Count a call to `provider` within `period` (e.g. "2024-11") and return the total
+    /// for that period. Counter restarts when period changes.
+    fn record_call(&mut self, provider: Provider, period: &str) -> anyhow::Result<u32>;
+
+    /// Period and number of calls last recorded for `provider`, if any.
+    fn get_usage(&self, provider: Provider) -> anyhow::Result<Option<(String, u32)>>;
+
+    /// Replace the counter of `provider`, e.g. when copying it from another store.
+    fn set_usage(&mut self, provider: Provider, period: &str, calls: u32) -> anyhow::Result<()>;
+}
+
+/// Everything `WeatherService` reads and writes, implemented for any type with all three parts.
+pub trait ServiceStore: CredentialsStore + SettingsStore + UsageStore {}
+
+impl<S: CredentialsStore + SettingsStore + UsageStore + ?Sized> ServiceStore for S {}
+
+/// Forwards to the boxed store, so frontends can pick an implementation at runtime.
+impl<S: CredentialsStore + ?Sized> CredentialsStore for Box<S> {
+    fn set_credentials(
+        &mut self,
+        profile: &str,
+        provider: Provider,
+        credentials: &Credentials,
+    ) -> anyhow::Result<()> {
+        (**self).set_credentials(profile, provider, credentials)
+    }
+
+    fn get_credentials(
+        &self,
+        profile: &str,
+        provider: Provider,
+    ) -> anyhow::Result<Option<Credentials>> {
+        (**self).get_credentials(profile, provider)
+    }
+
+    fn set_default_provider(&mut self, profile: &str, provider: Provider) -> anyhow::Result<()> {
+        (**self).set_default_provider(profile, provider)
+    }
+
+    fn get_default_provider(&self, profile: &str) -> anyhow::Result<Option<Provider>> {
+        (**self).get_default_provider(profile)
+    }
+
+    fn list_profiles(&self) -> anyhow::Result<Vec<String>> {
+        (**self).list_profiles()
+    }
+}
+
+/// Forwards to the borrowed store, so callers can keep it after handing it over.
+impl<S: CredentialsStore + ?Sized> CredentialsStore for &mut S {
+    fn set_credentials(
+        &mut self,
+        profile: &str,
+        provider: Provider,
+        credentials: &Credentials,
+    ) -> anyhow::Result<()> {
+        (**self).set_credentials(profile, provider, credentials)
+    }
+
+    fn get_credentials(
+        &self,
+        profile: &str,
+        provider: Provider,
+    ) -> anyhow::Result<Option<Credentials>> {
+        (**self).get_credentials(profile, provider)
+    }
+
+    fn set_default_provider(&mut self, profile: &str, provider: Provider) -> anyhow::Result<()> {
+        (**self).set_default_provider(profile, provider)
+    }
+
+    fn get_default_provider(&self, profile: &str) -> anyhow::Result<Option<Provider>> {
+        (**self).get_default_provider(profile)
+    }
+
+    fn list_profiles(&self) -> anyhow::Result<Vec<String>> {
+        (**self).list_profiles()
+    }
+}
+
+impl<S: SettingsStore + ?Sized> SettingsStore for Box<S> {
+    fn set_language(&mut self, language: &str) -> anyhow::Result<()> {
+        (**self).set_language(language)
+    }
+
+    fn get_language(&self) -> anyhow::Result<Option<String>> {
+        (**self).get_language()
+    }
 
-    /// Set the default provider to use when user does not specify it explicitly.
-    fn set_default_provider(&mut self, provider: Provider) -> anyhow::Result<()>;
+    fn set_location_alias(&mut self, alias: &str, address: &str) -> anyhow::Result<()> {
+        (**self).set_location_alias(alias, address)
+    }
+
+    fn get_location_alias(&self, alias: &str) -> anyhow::Result<Option<String>> {
+        (**self).get_location_alias(alias)
+    }
+
+    fn list_location_aliases(&self) -> anyhow::Result<Vec<(String, String)>> {
+        (**self).list_location_aliases()
+    }
+
+    fn get_max_forecast_days(&self, provider: Provider) -> anyhow::Result<Option<u32>> {
+        (**self).get_max_forecast_days(provider)
+    }
+
+    fn set_max_forecast_days(&mut self, provider: Provider, days: u32) -> anyhow::Result<()> {
+        (**self).set_max_forecast_days(provider, days)
+    }
+}
+
+impl<S: UsageStore + ?Sized> UsageStore for Box<S> {
+    fn record_call(&mut self, provider: Provider, period: &str) -> anyhow::Result<u32> {
+        (**self).record_call(provider, period)
+    }
+
+    fn get_usage(&self, provider: Provider) -> anyhow::Result<Option<(String, u32)>> {
+        (**self).get_usage(provider)
+    }
+
+    fn set_usage(&mut self, provider: Provider, period: &str, calls: u32) -> anyhow::Result<()> {
+        (**self).set_usage(provider, period, calls)
+    }
+}
+
+impl<S: SettingsStore + ?Sized> SettingsStore for &mut S {
+    fn set_language(&mut self, language: &str) -> anyhow::Result<()> {
+        (**self).set_language(language)
+    }
+
+    fn get_language(&self) -> anyhow::Result<Option<String>> {
+        (**self).get_language()
+    }
 
-    /// Get the default provider, if configured.
-    fn get_default_provider(&self) -> anyhow::Result<Option<Provider>>;
+    fn set_location_alias(&mut self, alias: &str, address: &str) -> anyhow::Result<()> {
+        (**self).set_location_alias(alias, address)
+    }
+
+    fn get_location_alias(&self, alias: &str) -> anyhow::Result<Option<String>> {
+        (**self).get_location_alias(alias)
+    }
+
+    fn list_location_aliases(&self) -> anyhow::Result<Vec<(String, String)>> {
+        (**self).list_location_aliases()
+    }
+
+    fn get_max_forecast_days(&self, provider: Provider) -> anyhow::Result<Option<u32>> {
+        (**self).get_max_forecast_days(provider)
+    }
+
+    fn set_max_forecast_days(&mut self, provider: Provider, days: u32) -> anyhow::Result<()> {
+        (**self).set_max_forecast_days(provider, days)
+    }
+}
+
+impl<S: UsageStore + ?Sized> UsageStore for &mut S {
+    fn record_call(&mut self, provider: Provider, period: &str) -> anyhow::Result<u32> {
+        (**self).record_call(provider, period)
+    }
+
+    fn get_usage(&self, provider: Provider) -> anyhow::Result<Option<(String, u32)>> {
+        (**self).get_usage(provider)
+    }
+
+    fn set_usage(&mut self, provider: Provider, period: &str, calls: u32) -> anyhow::Result<()> {
+        (**self).set_usage(provider, period, calls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_credentials_from_required_fields() {
+        for &provider in Provider::ALL {
+            let fields = provider
+                .required_credential_fields()
+                .iter()
+                .map(|field| (field.name, format!("{}-value", field.name)))
+                .collect();
+
+            let credentials = Credentials::from_fields(provider, &fields).unwrap();
+
+            assert_eq!(credentials.provider(), provider);
+        }
+    }
+
+    #[test]
+    fn fields_round_trip() {
+        let credentials = Credentials::AccuWeather {
+            api_key: "KEY".into(),
+            base_url: Some("https://eu.example.com/".into()),
+        };
+
+        assert_eq!(
+            Credentials::from_fields(Provider::AccuWeather, &credentials.fields()),
+            Some(credentials)
+        );
+    }
+
+    #[test]
+    fn missing_field_yields_none() {
+        assert_eq!(
+            Credentials::from_fields(Provider::WeatherApi, &HashMap::new()),
+            None
+        );
+    }
 }
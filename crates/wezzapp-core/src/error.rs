@@ -0,0 +1,112 @@
+use crate::provider::Provider;
+use thiserror::Error;
+
+/// Errors returned by provider clients and `WeatherService`.
+///
+/// Lets library consumers branch on the failure kind instead of matching strings.
+#[derive(Debug, Error)]
+pub enum WeatherError {
+    #[error("address not found, please, use more accurate address, eg: Kyiv, Ukraine")]
+    AddressNotFound,
+
+    #[error("address is empty, please, provide a location, eg: Kyiv, Ukraine")]
+    EmptyAddress,
+
+    #[error("address is too long ({len} characters), at most {max} are allowed")]
+    AddressTooLong { len: usize, max: usize },
+
+    #[error("provider rate limit exceeded, please, try again later")]
+    RateLimited,
+
+    #[error("API key of `{0:?}` was rejected")]
+    InvalidApiKey(Provider),
+
+    #[error(
+        "`{provider:?}` only supports up to {max} days forecast (including today), \
+         requested {requested}"
+    )]
+    ForecastRangeExceeded {
+        requested: u32,
+        max: u32,
+        provider: Provider,
+    },
+
+    #[error(
+        "`{provider:?}` returned {returned} days forecast (including today), requested {requested}"
+    )]
+    ForecastTooShort {
+        returned: u32,
+        requested: u32,
+        provider: Provider,
+    },
+
+    #[error(
+        "no configured provider supports {requested} days forecast (including today): {}",
+        limits_list(.limits)
+    )]
+    NoProviderCoversRange {
+        requested: u32,
+        /// Every configured provider with its max forecast days.
+        limits: Vec<(Provider, u32)>,
+    },
+
+    #[error("invalid date format (expected YYYY-MM-DD)")]
+    InvalidDate(#[source] chrono::ParseError),
+
+    #[error("date is in the past")]
+    DateInPast,
+
+    #[error("date is more than {max} days ahead, providers only forecast the next few days")]
+    DateTooFarAhead { max: u32 },
+
+    #[error("end date `{to}` is before start date `{from}`")]
+    InvalidDateRange { from: String, to: String },
+
+    #[error("no credentials found for provider `{0:?}`, please, configure it first")]
+    MissingCredentials(Provider),
+
+    #[error("no providers configured")]
+    NoProvidersConfigured,
+
+    #[error(
+        "no provider specified and no default provider set, \
+         please, configure a provider and/or set a default"
+    )]
+    NoDefaultProvider,
+
+    #[error("credentials type does not match provider: {0:?}")]
+    CredentialsMismatch(Provider),
+
+    #[error("failed to access credentials store")]
+    Store(#[source] anyhow::Error),
+
+    #[error(
+        "{} would be ignored with `with_http_client`, configure the HTTP client instead",
+        .0.join(", ")
+    )]
+    IgnoredHttpSettings(Vec<&'static str>),
+
+    #[error("invalid provider URL")]
+    Url(#[from] url::ParseError),
+
+    #[error("HTTP request failed")]
+    Http(#[from] reqwest::Error),
+
+    #[error("`{0:?}` is unavailable right now, please, try again later")]
+    ProviderUnavailable(Provider),
+
+    #[error("no cached report available offline")]
+    Offline,
+
+    #[error("unexpected provider response: {0}")]
+    UnexpectedResponse(String),
+}
+
+/// Provider limits, e.g. "`AccuWeather` up to 5, `WeatherApi` up to 14".
+fn limits_list(limits: &[(Provider, u32)]) -> String {
+    limits
+        .iter()
+        .map(|(provider, max)| format!("`{provider:?}` up to {max}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
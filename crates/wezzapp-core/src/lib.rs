@@ -1,4 +1,14 @@
+pub mod air_quality;
+pub mod alert;
 pub mod apis;
+pub mod condition;
 pub mod credentials;
+pub mod error;
+mod lookup;
 pub mod provider;
+pub mod temperature;
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
 pub mod weather_service;
+
+pub use lookup::lookup;
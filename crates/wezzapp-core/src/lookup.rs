@@ -0,0 +1,185 @@
+use crate::apis::{HttpProviderClientFactory, ProviderClientFactory, WeatherReport};
+use crate::credentials::{Credentials, CredentialsStore, SettingsStore, UsageStore};
+use crate::error::WeatherError;
+use crate::provider::Provider;
+use crate::weather_service::WeatherService;
+use std::collections::HashMap;
+
+/// Get weather for `address`, `days` ahead of today, with a single call.
+///
+/// Shortcut for embedding the crate: credentials live only for this call and the default HTTP
+/// clients are used. Use `WeatherService` with your own `ServiceStore` and
+/// `ProviderClientFactory` for profiles, caching, aliases or custom HTTP settings.
+///
+/// ```no_run
+/// use wezzapp_core::provider::Provider;
+///
+/// let report = wezzapp_core::lookup("API_KEY", Provider::WeatherApi, "Kyiv, Ukraine", 1)?;
+/// println!("{report}");
+/// # Ok::<(), wezzapp_core::error::WeatherError>(())
+/// ```
+pub fn lookup(
+    api_key: &str,
+    provider: Provider,
+    address: &str,
+    days: u32,
+) -> Result<WeatherReport, WeatherError> {
+    lookup_with(
+        HttpProviderClientFactory::new(),
+        api_key,
+        provider,
+        address,
+        days,
+    )
+}
+
+fn lookup_with<F: ProviderClientFactory>(
+    factory: F,
+    api_key: &str,
+    provider: Provider,
+    address: &str,
+    days: u32,
+) -> Result<WeatherReport, WeatherError> {
+    let fields = HashMap::from([("api_key", api_key.to_string())]);
+    let credentials = Credentials::from_fields(provider, &fields)
+        .ok_or(WeatherError::MissingCredentials(provider))?;
+
+    WeatherService::new(OneShotStore { credentials }, factory).get_weather_days_ahead(
+        address.to_string(),
+        days,
+        Some(provider),
+    )
+}
+
+/// Store holding just the credentials passed to `lookup`, in every profile, ignoring writes.
+struct OneShotStore {
+    credentials: Credentials,
+}
+
+impl CredentialsStore for OneShotStore {
+    fn set_credentials(
+        &mut self,
+        _profile: &str,
+        _provider: Provider,
+        _credentials: &Credentials,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get_credentials(
+        &self,
+        _profile: &str,
+        provider: Provider,
+    ) -> anyhow::Result<Option<Credentials>> {
+        Ok((self.credentials.provider() == provider).then(|| self.credentials.clone()))
+    }
+
+    fn set_default_provider(&mut self, _profile: &str, _provider: Provider) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get_default_provider(&self, _profile: &str) -> anyhow::Result<Option<Provider>> {
+        Ok(Some(self.credentials.provider()))
+    }
+
+    fn list_profiles(&self) -> anyhow::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+}
+
+impl SettingsStore for OneShotStore {
+    fn set_language(&mut self, _language: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get_language(&self) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn set_location_alias(&mut self, _alias: &str, _address: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get_location_alias(&self, _alias: &str) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn list_location_aliases(&self) -> anyhow::Result<Vec<(String, String)>> {
+        Ok(Vec::new())
+    }
+
+    fn get_max_forecast_days(&self, _provider: Provider) -> anyhow::Result<Option<u32>> {
+        Ok(None)
+    }
+
+    fn set_max_forecast_days(&mut self, _provider: Provider, _days: u32) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+impl UsageStore for OneShotStore {
+    fn record_call(&mut self, _provider: Provider, _period: &str) -> anyhow::Result<u32> {
+        Ok(0)
+    }
+
+    fn get_usage(&self, _provider: Provider) -> anyhow::Result<Option<(String, u32)>> {
+        Ok(None)
+    }
+
+    fn set_usage(&mut self, _provider: Provider, _period: &str, _calls: u32) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    #[test]
+    fn looks_up_report_with_api_key() {
+        let mut server = Server::new();
+        let forecast = server
+            .mock("GET", "/forecast.json")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("key".into(), "KEY".into()),
+                Matcher::UrlEncoded("q".into(), "Kyiv".into()),
+            ]))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "location": {"name": "Kyiv", "country": "Ukraine"},
+                    "forecast": {"forecastday": [{
+                        "date": "2024-11-29",
+                        "day": {"maxtemp_c": 12.0, "mintemp_c": 3.0, "condition": {"code": 1000, "text": "Sunny"}}
+                    }]}
+                }"#,
+            )
+            .create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .build()
+            .unwrap();
+
+        let report = lookup_with(factory, "KEY", Provider::WeatherApi, "Kyiv", 0).unwrap();
+
+        assert_eq!(report.location, "Kyiv, Ukraine");
+        assert_eq!(report.max_temperature, 12.0);
+        forecast.assert();
+    }
+
+    #[test]
+    fn days_past_provider_limit_fail_without_request() {
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::AccuWeather, "http://127.0.0.1:9/")
+            .build()
+            .unwrap();
+
+        let err = lookup_with(factory, "KEY", Provider::AccuWeather, "Kyiv", 10).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::ForecastRangeExceeded { .. }),
+            "unexpected error: {err:?}"
+        );
+    }
+}
@@ -1,4 +1,7 @@
+use crate::apis::rate_limiter::{RateLimit, RateLimitMode};
+use crate::credentials::CredentialField;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Supported weather providers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -6,4 +9,183 @@ use serde::{Deserialize, Serialize};
 pub enum Provider {
     WeatherApi,
     AccuWeather,
+    TomorrowIo,
+    /// Offline provider with canned reports, see `mock` feature.
+    #[cfg(feature = "mock")]
+    Mock,
+}
+
+impl Provider {
+    /// Every provider, in the order they're listed to users.
+    ///
+    /// Adding a provider means adding it here, see `all_lists_every_variant_once`.
+    pub const ALL: &[Provider] = &[
+        Provider::WeatherApi,
+        Provider::AccuWeather,
+        Provider::TomorrowIo,
+        #[cfg(feature = "mock")]
+        Provider::Mock,
+    ];
+
+    /// Lowercase provider key, as used in config files and CLI arguments.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Provider::WeatherApi => "weatherapi",
+            Provider::AccuWeather => "accuweather",
+            Provider::TomorrowIo => "tomorrowio",
+            #[cfg(feature = "mock")]
+            Provider::Mock => "mock",
+        }
+    }
+
+    /// Credential values user has to enter to configure this provider.
+    pub fn required_credential_fields(&self) -> &'static [CredentialField] {
+        match self {
+            Provider::WeatherApi => &[CredentialField {
+                name: "api_key",
+                label: "WeatherAPI API key",
+                help: "Sign up at https://www.weatherapi.com/",
+            }],
+            Provider::AccuWeather => &[CredentialField {
+                name: "api_key",
+                label: "AccuWeather API key",
+                help: "Visit https://developer.accuweather.com/",
+            }],
+            Provider::TomorrowIo => &[CredentialField {
+                name: "api_key",
+                label: "Tomorrow.io API key",
+                help: "Sign up at https://app.tomorrow.io/development/keys",
+            }],
+            #[cfg(feature = "mock")]
+            Provider::Mock => &[],
+        }
+    }
+
+    /// Credential values user may leave empty, e.g. a regional endpoint.
+    pub fn optional_credential_fields(&self) -> &'static [CredentialField] {
+        match self {
+            Provider::AccuWeather => &[CredentialField {
+                name: "base_url",
+                label: "AccuWeather base URL",
+                help: "Regional or proxy endpoint, leave empty for the public API",
+            }],
+            _ => &[],
+        }
+    }
+
+    /// What the provider can do on its free plan, for users picking one.
+    ///
+    /// `max_forecast_days` matches `ProviderClient::max_forecast_days` of its client.
+    pub fn capabilities(&self) -> Capabilities {
+        let needs_api_key = !self.required_credential_fields().is_empty();
+
+        match self {
+            Provider::WeatherApi => Capabilities {
+                max_forecast_days: 14,
+                needs_api_key,
+                current: true,
+                alerts: true,
+                air_quality: true,
+                history: false,
+            },
+            Provider::AccuWeather => Capabilities {
+                max_forecast_days: 5,
+                needs_api_key,
+                current: true,
+                alerts: true,
+                air_quality: true,
+                history: false,
+            },
+            Provider::TomorrowIo => Capabilities {
+                max_forecast_days: 6,
+                needs_api_key,
+                current: true,
+                alerts: false,
+                air_quality: false,
+                history: false,
+            },
+            #[cfg(feature = "mock")]
+            Provider::Mock => Capabilities {
+                max_forecast_days: 14,
+                needs_api_key,
+                current: true,
+                alerts: false,
+                air_quality: false,
+                history: false,
+            },
+        }
+    }
+
+    /// Free plan monthly call cap for providers which don't report quota in response headers.
+    pub fn monthly_call_cap(&self) -> Option<u32> {
+        match self {
+            Provider::WeatherApi => Some(1_000_000),
+            // Reported via `RateLimit-*` headers.
+            Provider::AccuWeather => None,
+            // Capped per hour and day rather than per month.
+            Provider::TomorrowIo => None,
+            #[cfg(feature = "mock")]
+            Provider::Mock => None,
+        }
+    }
+
+    /// Client-side budget of HTTP requests to this provider unless overridden, see
+    /// `HttpProviderClientFactoryBuilder::with_rate_limit`.
+    ///
+    /// Kept in memory, so it only counts requests of the current process run.
+    pub fn default_rate_limit(&self) -> Option<RateLimit> {
+        match self {
+            Provider::WeatherApi => None,
+            // Free tier allows 50 calls per day, fail fast instead of wasting them.
+            Provider::AccuWeather => Some(
+                RateLimit::per_window(50, Duration::from_secs(24 * 60 * 60))
+                    .with_mode(RateLimitMode::Error),
+            ),
+            Provider::TomorrowIo => None,
+            #[cfg(feature = "mock")]
+            Provider::Mock => None,
+        }
+    }
+}
+
+/// Features of a provider, see `Provider::capabilities`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Forecast days including today.
+    pub max_forecast_days: u32,
+    pub needs_api_key: bool,
+    /// Real-time conditions, see `get --now`.
+    pub current: bool,
+    pub alerts: bool,
+    pub air_quality: bool,
+    /// Weather of past dates.
+    pub history: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Position of `provider` in `Provider::ALL`.
+    ///
+    /// Exhaustive, so a new variant doesn't compile until it's listed here (and in `ALL`).
+    fn position(provider: Provider) -> usize {
+        match provider {
+            Provider::WeatherApi => 0,
+            Provider::AccuWeather => 1,
+            Provider::TomorrowIo => 2,
+            #[cfg(feature = "mock")]
+            Provider::Mock => 3,
+        }
+    }
+
+    #[test]
+    fn all_lists_every_variant_once() {
+        let variants = if cfg!(feature = "mock") { 4 } else { 3 };
+
+        assert_eq!(Provider::ALL.len(), variants);
+        for (index, provider) in Provider::ALL.iter().enumerate() {
+            assert_eq!(position(*provider), index, "{provider:?} out of place");
+        }
+    }
 }
@@ -0,0 +1,25 @@
+/// Convert °C, the unit of every report, to °F.
+pub fn to_fahrenheit(celsius: f64) -> f64 {
+    celsius * 9.0 / 5.0 + 32.0
+}
+
+/// Convert °F to °C.
+pub fn to_celsius(fahrenheit: f64) -> f64 {
+    (fahrenheit - 32.0) * 5.0 / 9.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0.0, 32.0)]
+    #[case(100.0, 212.0)]
+    #[case(-40.0, -40.0)]
+    #[case(37.0, 98.6)]
+    fn converts_known_points_both_ways(#[case] celsius: f64, #[case] fahrenheit: f64) {
+        assert!((to_fahrenheit(celsius) - fahrenheit).abs() < 1e-9);
+        assert!((to_celsius(fahrenheit) - celsius).abs() < 1e-9);
+    }
+}
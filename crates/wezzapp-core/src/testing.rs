@@ -0,0 +1,301 @@
+//! Test doubles shared by this crate's and frontends' tests.
+//!
+//! Compiled for this crate's tests and with the `test-support` feature.
+
+use crate::apis::{
+    CurrentConditions, ProviderClient, ProviderClientFactory, ResolvedLocation, WeatherReport,
+};
+use crate::condition::WeatherCondition;
+use crate::credentials::{Credentials, CredentialsStore, SettingsStore, UsageStore};
+use crate::error::WeatherError;
+use crate::provider::Provider;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Store keeping everything in memory, with public fields to set up and inspect state.
+#[derive(Default, PartialEq)]
+pub struct InMemoryStore {
+    pub defaults: HashMap<String, Provider>,
+    pub providers: HashMap<(String, Provider), Credentials>,
+    pub language: Option<String>,
+    pub locations: HashMap<String, String>,
+    pub max_forecast_days: HashMap<Provider, u32>,
+    /// Period and number of calls recorded per provider.
+    pub usage: HashMap<Provider, (String, u32)>,
+}
+
+impl CredentialsStore for InMemoryStore {
+    fn set_credentials(
+        &mut self,
+        profile: &str,
+        provider: Provider,
+        credentials: &Credentials,
+    ) -> anyhow::Result<()> {
+        self.providers
+            .insert((profile.to_string(), provider), credentials.clone());
+        Ok(())
+    }
+
+    fn get_credentials(
+        &self,
+        profile: &str,
+        provider: Provider,
+    ) -> anyhow::Result<Option<Credentials>> {
+        Ok(self
+            .providers
+            .get(&(profile.to_string(), provider))
+            .cloned())
+    }
+
+    fn set_default_provider(&mut self, profile: &str, provider: Provider) -> anyhow::Result<()> {
+        self.defaults.insert(profile.to_string(), provider);
+        Ok(())
+    }
+
+    fn get_default_provider(&self, profile: &str) -> anyhow::Result<Option<Provider>> {
+        Ok(self.defaults.get(profile).copied())
+    }
+
+    fn list_profiles(&self) -> anyhow::Result<Vec<String>> {
+        let mut profiles: Vec<_> = self
+            .providers
+            .keys()
+            .map(|(profile, _)| profile.clone())
+            .chain(self.defaults.keys().cloned())
+            .collect();
+        profiles.sort();
+        profiles.dedup();
+        Ok(profiles)
+    }
+}
+
+impl SettingsStore for InMemoryStore {
+    fn set_language(&mut self, language: &str) -> anyhow::Result<()> {
+        self.language = Some(language.to_string());
+        Ok(())
+    }
+
+    fn get_language(&self) -> anyhow::Result<Option<String>> {
+        Ok(self.language.clone())
+    }
+
+    fn set_location_alias(&mut self, alias: &str, address: &str) -> anyhow::Result<()> {
+        self.locations
+            .insert(alias.to_string(), address.to_string());
+        Ok(())
+    }
+
+    fn get_location_alias(&self, alias: &str) -> anyhow::Result<Option<String>> {
+        Ok(self.locations.get(alias).cloned())
+    }
+
+    fn list_location_aliases(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let mut aliases: Vec<_> = self.locations.clone().into_iter().collect();
+        aliases.sort();
+        Ok(aliases)
+    }
+
+    fn get_max_forecast_days(&self, provider: Provider) -> anyhow::Result<Option<u32>> {
+        Ok(self.max_forecast_days.get(&provider).copied())
+    }
+
+    fn set_max_forecast_days(&mut self, provider: Provider, days: u32) -> anyhow::Result<()> {
+        self.max_forecast_days.insert(provider, days);
+        Ok(())
+    }
+}
+
+impl UsageStore for InMemoryStore {
+    fn record_call(&mut self, provider: Provider, period: &str) -> anyhow::Result<u32> {
+        let (last_period, calls) = self.usage.entry(provider).or_default();
+        if last_period != period {
+            *last_period = period.to_string();
+            *calls = 0;
+        }
+        *calls += 1;
+        Ok(*calls)
+    }
+
+    fn get_usage(&self, provider: Provider) -> anyhow::Result<Option<(String, u32)>> {
+        Ok(self.usage.get(&provider).cloned())
+    }
+
+    fn set_usage(&mut self, provider: Provider, period: &str, calls: u32) -> anyhow::Result<()> {
+        self.usage.insert(provider, (period.to_string(), calls));
+        Ok(())
+    }
+}
+
+/// WeatherAPI report for Kyiv on 2024-11-29, sunny with a high of 12°C and a low of 3°C,
+/// no optional fields. Override fields with struct update syntax:
+/// `WeatherReport { humidity: Some(64.0), ..report() }`.
+pub fn report() -> WeatherReport {
+    WeatherReport {
+        provider: Provider::WeatherApi,
+        date: "2024-11-29".to_string(),
+        location: "Kyiv, Ukraine".to_string(),
+        condition: WeatherCondition::Clear,
+        description: "Sunny".to_string(),
+        max_temperature: 12.0,
+        min_temperature: 3.0,
+        avg_temperature: None,
+        feels_like: None,
+        uv_index: None,
+        wind_kph: None,
+        wind_direction: None,
+        humidity: None,
+        sunrise: None,
+        sunset: None,
+        air_quality: None,
+        alerts: None,
+        stale_since: None,
+    }
+}
+
+/// Answer of `FakeClient` to a forecast request for an address and day offset.
+type Respond = dyn Fn(&str, u32) -> Result<WeatherReport, WeatherError> + Send + Sync;
+
+/// Client answering forecasts with a given report or error, recording every forecast request.
+///
+/// Clones share the recorded requests, so keep one to inspect what a service sent through
+/// the clients of a `FakeFactory`.
+#[derive(Clone)]
+pub struct FakeClient {
+    provider: Provider,
+    respond: Arc<Respond>,
+    /// Returned by `resolve_location`, a location named after the address if unset.
+    location: Option<ResolvedLocation>,
+    forecasts: Arc<Mutex<Vec<(String, u32)>>>,
+}
+
+impl Default for FakeClient {
+    /// `report()` for whatever address is requested.
+    fn default() -> Self {
+        Self {
+            provider: Provider::WeatherApi,
+            respond: Arc::new(|address, _days| {
+                Ok(WeatherReport {
+                    location: address.to_string(),
+                    ..report()
+                })
+            }),
+            location: None,
+            forecasts: Arc::default(),
+        }
+    }
+}
+
+impl FakeClient {
+    /// Answer every forecast with `report`, whatever the address.
+    pub fn with_report(self, report: WeatherReport) -> Self {
+        self.with_response(move |_address, _days| Ok(report.clone()))
+    }
+
+    /// Fail every forecast with the error made by `err`.
+    pub fn with_error(self, err: fn() -> WeatherError) -> Self {
+        self.with_response(move |_address, _days| Err(err()))
+    }
+
+    /// Answer forecasts with `respond`, called with the address and day offset.
+    pub fn with_response(
+        mut self,
+        respond: impl Fn(&str, u32) -> Result<WeatherReport, WeatherError> + Send + Sync + 'static,
+    ) -> Self {
+        self.respond = Arc::new(respond);
+        self
+    }
+
+    /// Resolve every address to `location`.
+    pub fn with_location(mut self, location: ResolvedLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Address and day offset of every forecast requested so far, in order.
+    ///
+    /// Forecasts of resolved locations are recorded with the location's display name.
+    pub fn forecasts(&self) -> Vec<(String, u32)> {
+        self.forecasts.lock().unwrap().clone()
+    }
+}
+
+impl ProviderClient for FakeClient {
+    fn get_weather(&self, address: String, days: u32) -> Result<WeatherReport, WeatherError> {
+        self.forecasts.lock().unwrap().push((address.clone(), days));
+        (self.respond)(&address, days)
+    }
+
+    fn resolve_location(&self, address: &str) -> Result<ResolvedLocation, WeatherError> {
+        Ok(self.location.clone().unwrap_or_else(|| ResolvedLocation {
+            name: address.to_string(),
+            country: String::new(),
+            lat: 0.0,
+            lon: 0.0,
+            provider_key: None,
+            timezone: None,
+        }))
+    }
+
+    /// Provider and address, e.g. `{"provider":"weatherapi","location":{"name":"Kyiv"}}`.
+    fn get_weather_raw(&self, address: String, _days: u32) -> Result<Value, WeatherError> {
+        Ok(json!({"provider": self.provider.as_str(), "location": {"name": address}}))
+    }
+
+    /// Sunny 7°C at the address, observed on 2024-11-29 at 10:15 UTC.
+    fn get_current(&self, address: &str) -> Result<CurrentConditions, WeatherError> {
+        Ok(CurrentConditions {
+            provider: self.provider,
+            location: address.to_string(),
+            observed_at: "2024-11-29T10:15:00Z".parse().unwrap(),
+            condition: WeatherCondition::Clear,
+            description: "Sunny".to_string(),
+            temperature: 7.0,
+            feels_like: None,
+            humidity: None,
+            wind_kph: None,
+            wind_direction: None,
+        })
+    }
+
+    fn max_forecast_days(&self) -> u32 {
+        self.provider.capabilities().max_forecast_days
+    }
+}
+
+/// Factory creating clones of a `FakeClient` for whichever provider is asked for, recording
+/// the credentials of every client created.
+#[derive(Clone, Default)]
+pub struct FakeFactory {
+    client: FakeClient,
+    created: Arc<Mutex<Vec<Credentials>>>,
+}
+
+impl FakeFactory {
+    pub fn new(client: FakeClient) -> Self {
+        Self {
+            client,
+            created: Arc::default(),
+        }
+    }
+
+    /// Credentials of every client created so far, in order.
+    pub fn created(&self) -> Vec<Credentials> {
+        self.created.lock().unwrap().clone()
+    }
+}
+
+impl ProviderClientFactory for FakeFactory {
+    fn create_client(
+        &self,
+        provider: Provider,
+        credentials: Credentials,
+    ) -> Result<Box<dyn ProviderClient>, WeatherError> {
+        self.created.lock().unwrap().push(credentials);
+
+        Ok(Box::new(FakeClient {
+            provider,
+            ..self.client.clone()
+        }))
+    }
+}
@@ -1,27 +1,88 @@
-use crate::apis::{ProviderClientFactory, WeatherReport};
-use crate::credentials::CredentialsStore;
+use crate::apis::location_cache::normalize;
+use crate::apis::quota::{QuotaObserver, QuotaStatus};
+use crate::apis::redact;
+use crate::apis::report_cache::ReportCache;
+use crate::apis::{
+    CurrentConditions, ProviderClient, ProviderClientFactory, ResolvedLocation, WeatherReport,
+};
+use crate::credentials::{Credentials, DEFAULT_PROFILE, ServiceStore};
+use crate::error::WeatherError;
 use crate::provider::Provider;
-use anyhow::{Context, Result, anyhow};
-use chrono::{Local, NaiveDate};
-use tracing::debug;
+use chrono::{DateTime, Days, Local, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde_json::Value;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Where the provider of a request came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionSource {
+    /// Given explicitly, e.g. with `--provider`.
+    Flag,
+    /// Default provider of the profile.
+    StoredDefault,
+    /// No default, but only one provider is configured.
+    OnlyConfigured,
+    /// `requested` can't forecast that far, see `WeatherServiceBuilder::with_auto_provider`.
+    AutoFallback { requested: Provider },
+}
 
 #[derive(Debug)]
 pub struct WeatherService<S, F>
 where
-    S: CredentialsStore,
+    S: ServiceStore,
     F: ProviderClientFactory,
 {
     store: S,
     factory: F,
+    /// Credentials profile to use.
+    profile: String,
+    /// Notified about locally counted quota of providers without quota headers.
+    quota_observer: Option<Arc<dyn QuotaObserver>>,
+    /// Last fetched reports, kept to serve when the provider is down.
+    report_cache: Option<Arc<dyn ReportCache>>,
+    /// Serve a cached report when the live fetch fails.
+    allow_stale: bool,
+    /// Serve cached reports younger than this without calling the provider.
+    fresh_for: Option<Duration>,
+    /// Fall back to another configured provider when the requested one can't forecast that far.
+    auto_provider: bool,
+    /// Count days from today where the location is instead of on this machine.
+    tz_aware: bool,
+    /// Never create provider clients, serve only cached reports.
+    offline: bool,
+    /// Provider of the last request and why it was picked.
+    resolution: Option<(Provider, ResolutionSource)>,
+    /// When the report of the last forecast request was fetched, if it came from the cache.
+    cached_at: Option<DateTime<Utc>>,
 }
 
 impl<S, F> WeatherService<S, F>
 where
-    S: CredentialsStore,
+    S: ServiceStore,
     F: ProviderClientFactory,
 {
+    /// Create service with default options.
     pub fn new(store: S, factory: F) -> Self {
-        Self { store, factory }
+        Self::builder(store, factory).build()
+    }
+
+    /// Start configuring a service with custom options.
+    pub fn builder(store: S, factory: F) -> WeatherServiceBuilder<S, F> {
+        WeatherServiceBuilder {
+            store,
+            factory,
+            profile: None,
+            quota_observer: None,
+            report_cache: None,
+            allow_stale: false,
+            fresh_for: None,
+            auto_provider: false,
+            tz_aware: false,
+            offline: false,
+        }
     }
 
     /// Get weather for provided params
@@ -30,8 +91,488 @@ where
         address: String,
         date: Option<String>,
         provider: Option<Provider>,
-    ) -> Result<WeatherReport> {
-        debug!("Getting weather for address `{address}`");
+    ) -> Result<WeatherReport, WeatherError> {
+        debug!("Getting weather for address {}", redact::address(&address));
+        self.cached_at = None;
+        let address = self.resolve_alias(&address)?;
+        if self.tz_aware
+            && !self.offline
+            && let Some(date) = date
+        {
+            return self.get_weather_tz_aware(address, &date, provider);
+        }
+        let days = Self::days(date)?;
+
+        self.fetch_days_ahead(address, days, provider)
+    }
+
+    /// Get weather for `address`, `days` ahead of this machine's today.
+    ///
+    /// Unlike passing the date to `get_weather`, today isn't looked up again, so the day
+    /// can't shift if it ends meanwhile. With `tz_aware` set, the day is still counted from
+    /// the location's today.
+    pub fn get_weather_days_ahead(
+        &mut self,
+        address: String,
+        days: u32,
+        provider: Option<Provider>,
+    ) -> Result<WeatherReport, WeatherError> {
+        debug!(
+            "Getting weather for address {} {days} days ahead",
+            redact::address(&address)
+        );
+        self.cached_at = None;
+        let address = self.resolve_alias(&address)?;
+        if self.tz_aware && !self.offline {
+            let date = Local::now().date_naive() + Days::new(days.into());
+            return self.get_weather_tz_aware(address, &date.to_string(), provider);
+        }
+
+        self.fetch_days_ahead(address, days, provider)
+    }
+
+    /// Serve `days` ahead from the report cache when offline, fetch it otherwise.
+    fn fetch_days_ahead(
+        &mut self,
+        address: String,
+        days: u32,
+        provider: Option<Provider>,
+    ) -> Result<WeatherReport, WeatherError> {
+        if self.offline {
+            return self.get_weather_offline(&address, days, provider);
+        }
+        let sent = self.prepare_fetch(address, days, provider)?.send();
+        self.finish_fetch(sent)
+    }
+
+    /// Get weather for each of `requests`, address and date pairs, fetching them concurrently.
+    ///
+    /// Results are in `requests` order and independent, one failing doesn't fail the others.
+    /// Offline and timezone-aware requests are served one by one, like `get_weather`.
+    pub fn get_weather_all(
+        &mut self,
+        requests: Vec<(String, Option<String>)>,
+        provider: Option<Provider>,
+    ) -> Vec<Result<WeatherReport, WeatherError>> {
+        self.cached_at = None;
+        if self.offline || self.tz_aware {
+            return requests
+                .into_iter()
+                .map(|(address, date)| self.get_weather(address, date, provider))
+                .collect();
+        }
+
+        let prepared: Vec<_> = requests
+            .into_iter()
+            .map(|(address, date)| {
+                debug!("Getting weather for address {}", redact::address(&address));
+                let address = self.resolve_alias(&address)?;
+                self.prepare_fetch(address, Self::days(date)?, provider)
+            })
+            .collect();
+
+        // Each request has its own client, so they're sent at once and only the store is
+        // updated one by one afterwards.
+        let fetched: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = prepared
+                .into_iter()
+                .map(|prepared| scope.spawn(move || prepared.map(Prepared::send)))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        });
+
+        fetched
+            .into_iter()
+            .map(|sent| self.finish_fetch(sent?))
+            .collect()
+    }
+
+    /// Create the client for a forecast of `address`, unless a fresh cached report answers it.
+    fn prepare_fetch(
+        &mut self,
+        address: String,
+        days: u32,
+        provider: Option<Provider>,
+    ) -> Result<Prepared, WeatherError> {
+        let (provider, client) = self.client_for(provider, days)?;
+
+        let key = self.report_key(provider, &address, Local::now().date_naive(), days);
+        if let Some(report) = self.fresh_report(&key) {
+            return Ok(Prepared::Fresh(Box::new(report)));
+        }
+
+        Ok(Prepared::Fetch(Fetch {
+            provider,
+            client,
+            address,
+            days,
+            key,
+        }))
+    }
+
+    /// Count the call of a sent request and cache its report, see `Prepared::send`.
+    fn finish_fetch(
+        &mut self,
+        (fetch, report): (Option<Fetch>, Result<WeatherReport, WeatherError>),
+    ) -> Result<WeatherReport, WeatherError> {
+        let Some(fetch) = fetch else {
+            return report;
+        };
+        self.track_call(fetch.provider);
+
+        self.cache_report(&fetch.key, report)
+    }
+
+    /// Serve the cached report of any age, counting `days` from this machine's today.
+    fn get_weather_offline(
+        &mut self,
+        address: &str,
+        days: u32,
+        provider: Option<Provider>,
+    ) -> Result<WeatherReport, WeatherError> {
+        let (provider, source) = self.resolve_provider(provider)?;
+        self.resolution = Some((provider, source));
+
+        let key = self.report_key(provider, address, Local::now().date_naive(), days);
+        let (mut report, fetched_at) = self
+            .report_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&key))
+            .ok_or(WeatherError::Offline)?;
+        debug!("Serving cached {provider:?} report fetched at {fetched_at} offline");
+        report.stale_since = Some(fetched_at);
+        self.cached_at = Some(fetched_at);
+
+        Ok(report)
+    }
+
+    /// Resolve the location first, so `date` is counted from its local today.
+    fn get_weather_tz_aware(
+        &mut self,
+        address: String,
+        date: &str,
+        provider: Option<Provider>,
+    ) -> Result<WeatherReport, WeatherError> {
+        // Fail on far-future dates before the location lookup, the exact day count comes later.
+        days_until(Local::now().date_naive(), date)?;
+        let (resolved_by, client) = self.client_for(provider, 0)?;
+        let location = client.resolve_location(&address);
+        self.track_call(resolved_by);
+        let location = location?;
+
+        let today = today_in(location.timezone.as_deref(), Utc::now());
+        let days = days_between(today, date)?;
+        debug!("Days from today at {:?}: {days}", location.timezone);
+        let (provider, client) = self.client_for(provider, days)?;
+
+        let key = self.report_key(provider, &address, today, days);
+        if let Some(report) = self.fresh_report(&key) {
+            return Ok(report);
+        }
+        // Locations carry provider-specific keys, so another provider looks the address up again.
+        let report = if provider == resolved_by {
+            client.get_weather_at(&location, days)
+        } else {
+            client.get_weather(address, days)
+        };
+        self.track_call(provider);
+
+        self.cache_report(&key, report)
+    }
+
+    /// Get provider's untouched forecast response for provided params.
+    pub fn get_weather_raw(
+        &mut self,
+        address: String,
+        date: Option<String>,
+        provider: Option<Provider>,
+    ) -> Result<Value, WeatherError> {
+        debug!(
+            "Getting raw weather for address {}",
+            redact::address(&address)
+        );
+        let address = self.resolve_alias(&address)?;
+        let days = Self::days(date)?;
+        let (provider, client) = self.client_for(provider, days)?;
+
+        let body = client.get_weather_raw(address, days);
+        self.track_call(provider);
+
+        body
+    }
+
+    /// Get real-time conditions instead of the daily forecast, never served from cache.
+    pub fn get_current(
+        &mut self,
+        address: String,
+        provider: Option<Provider>,
+    ) -> Result<CurrentConditions, WeatherError> {
+        debug!(
+            "Getting current conditions for address {}",
+            redact::address(&address)
+        );
+        let address = self.resolve_alias(&address)?;
+        let (provider, client) = self.client_for(provider, 0)?;
+
+        let current = client.get_current(&address);
+        self.track_call(provider);
+
+        current
+    }
+
+    /// Geocode address once, so it can be reused with `get_weather_at`.
+    pub fn resolve_location(
+        &mut self,
+        address: &str,
+        provider: Option<Provider>,
+    ) -> Result<ResolvedLocation, WeatherError> {
+        debug!(
+            "Resolving location for address {}",
+            redact::address(address)
+        );
+        let address = self.resolve_alias(address)?;
+        let (provider, client) = self.client_for(provider, 0)?;
+
+        let location = client.resolve_location(&address);
+        self.track_call(provider);
+
+        location
+    }
+
+    /// Get weather for a location resolved by `resolve_location`, skipping geocoding.
+    pub fn get_weather_at(
+        &mut self,
+        location: &ResolvedLocation,
+        date: Option<String>,
+        provider: Option<Provider>,
+    ) -> Result<WeatherReport, WeatherError> {
+        debug!(
+            "Getting weather for location {}",
+            redact::address(&location.display_name())
+        );
+        let days = Self::days(date)?;
+        let (provider, client) = self.client_for(provider, days)?;
+
+        let report = client.get_weather_at(location, days);
+        self.track_call(provider);
+
+        report
+    }
+
+    /// Get weather for every day from `from` to `to` (inclusive), geocoding the address once.
+    pub fn get_weather_range(
+        &mut self,
+        address: String,
+        from: &str,
+        to: &str,
+        provider: Option<Provider>,
+    ) -> Result<Vec<WeatherReport>, WeatherError> {
+        debug!(
+            "Getting weather for address {} from {from} to {to}",
+            redact::address(&address)
+        );
+        let days = day_range(from, to)?;
+
+        self.get_weather_range_days_ahead(address, days, provider)
+    }
+
+    /// Get weather for every day `days` ahead of this machine's today, geocoding the address
+    /// once.
+    pub fn get_weather_range_days_ahead(
+        &mut self,
+        address: String,
+        days: RangeInclusive<u32>,
+        provider: Option<Provider>,
+    ) -> Result<Vec<WeatherReport>, WeatherError> {
+        debug!(
+            "Getting weather for address {} {days:?} days ahead",
+            redact::address(&address)
+        );
+        let address = self.resolve_alias(&address)?;
+        let (provider, client) = self.client_for(provider, *days.end())?;
+
+        let location = client.resolve_location(&address);
+        self.track_call(provider);
+        let location = location?;
+
+        let reports = client.get_weather_range_at(&location, days.clone());
+        // One call per day, as made by the default `get_weather_range_at` of providers with
+        // a call cap.
+        for _ in days {
+            self.track_call(provider);
+        }
+
+        reports
+    }
+
+    /// Providers with credentials in the current profile, in `Provider::ALL` order.
+    pub fn configured_providers(&self) -> Result<Vec<Provider>, WeatherError> {
+        let mut configured = Vec::new();
+        for &provider in Provider::ALL {
+            if self
+                .store
+                .get_credentials(&self.profile, provider)
+                .map_err(WeatherError::Store)?
+                .is_some()
+            {
+                configured.push(provider);
+            }
+        }
+
+        Ok(configured)
+    }
+
+    /// Check every provider: credentials present, reachable and, if configured, accepted.
+    ///
+    /// Failures are reported per provider, only store errors abort the check.
+    /// Verifying pings each configured provider, using one call of its quota.
+    pub fn status(&mut self) -> Result<Vec<ProviderStatus>, WeatherError> {
+        if self.offline {
+            return Err(WeatherError::Offline);
+        }
+        let mut statuses = Vec::with_capacity(Provider::ALL.len());
+
+        for &provider in Provider::ALL {
+            debug!("Checking status of {provider:?}");
+            let creds = self.credentials(provider)?;
+            let has_credentials = creds.is_some();
+            let reachable = self.factory.probe_reachability(provider);
+
+            let verified = creds.map(|creds| {
+                let client = self.factory.create_client(provider, creds)?;
+                let verified = client.ping();
+                self.track_call(provider);
+                verified
+            });
+
+            statuses.push(ProviderStatus {
+                provider,
+                has_credentials,
+                reachable,
+                verified,
+            });
+        }
+
+        Ok(statuses)
+    }
+
+    /// Ping `provider`, or the default one, before fetching anything.
+    ///
+    /// Tells rejected credentials and outages apart more clearly than a failed forecast,
+    /// at the cost of one call of quota. Does nothing offline.
+    pub fn preflight(&mut self, provider: Option<Provider>) -> Result<(), WeatherError> {
+        if self.offline {
+            debug!("Skipping preflight check offline");
+            return Ok(());
+        }
+        let (provider, _) = self.resolve_provider(provider)?;
+        let (provider, client) = self.checked_client(provider, 0)?;
+        debug!("Preflight check of {provider:?}");
+
+        let result = client.ping();
+        self.track_call(provider);
+
+        result
+    }
+
+    /// Save `address` under `alias`, so it can be used instead of the address later.
+    ///
+    /// If `address` is an alias itself, the address behind it is saved.
+    pub fn save_location_alias(&mut self, alias: &str, address: &str) -> Result<(), WeatherError> {
+        let alias = validate_address(alias)?;
+        let address = self.resolve_alias(address)?;
+        debug!(
+            "Saving location alias `{alias}` for {}",
+            redact::address(&address)
+        );
+
+        self.store
+            .set_location_alias(&alias, &address)
+            .map_err(WeatherError::Store)
+    }
+
+    /// Validate address, replacing it with the saved one if it exactly matches an alias.
+    fn resolve_alias(&self, address: &str) -> Result<String, WeatherError> {
+        let address = validate_address(address)?;
+
+        match self
+            .store
+            .get_location_alias(&address)
+            .map_err(WeatherError::Store)?
+        {
+            Some(saved) => {
+                debug!("Resolved alias `{address}` to {}", redact::address(&saved));
+                Ok(saved)
+            }
+            None => Ok(address),
+        }
+    }
+
+    /// Report cache key, e.g. `weatherapi:2024-11-29:lang=en&aqi:kyiv, ukraine`.
+    fn report_key(&self, provider: Provider, address: &str, today: NaiveDate, days: u32) -> String {
+        let date = today + Days::new(days.into());
+        let options = self.factory.request_options();
+
+        format!(
+            "{}:{date}:{options}:{}",
+            provider.as_str(),
+            normalize(address)
+        )
+    }
+
+    /// Cached report for `key`, if it was fetched less than `fresh_for` ago.
+    fn fresh_report(&mut self, key: &str) -> Option<WeatherReport> {
+        let fresh_for = self.fresh_for?;
+        let (report, fetched_at) = self.report_cache.as_ref()?.get(key)?;
+
+        let age = (Utc::now() - fetched_at).to_std().unwrap_or_default();
+        if age >= fresh_for {
+            return None;
+        }
+        debug!("Serving cached report fetched at {fetched_at}");
+        self.cached_at = Some(fetched_at);
+
+        Some(report)
+    }
+
+    /// Cache a fetched report, or fall back to the cached one if the provider is down
+    /// and stale reports are allowed.
+    fn cache_report(
+        &mut self,
+        key: &str,
+        report: Result<WeatherReport, WeatherError>,
+    ) -> Result<WeatherReport, WeatherError> {
+        let Some(cache) = &self.report_cache else {
+            return report;
+        };
+
+        match report {
+            Ok(report) => {
+                cache.put(key, &report);
+                Ok(report)
+            }
+            Err(err) if self.allow_stale && is_outage(&err) => match cache.get(key) {
+                Some((mut stale, fetched_at)) => {
+                    warn!("Serving stale report fetched at {fetched_at}: {err}");
+                    stale.stale_since = Some(fetched_at);
+                    self.cached_at = Some(fetched_at);
+                    Ok(stale)
+                }
+                None => Err(err),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    fn days(date: Option<String>) -> Result<u32, WeatherError> {
         let days = if let Some(date) = date {
             days_from_today(&date)?
         } else {
@@ -39,98 +580,1847 @@ where
         };
         debug!("Days from today: {days}");
 
-        let provider = self.resolve_provider(provider)?;
+        Ok(days)
+    }
 
+    /// Credentials of `provider` in the current profile.
+    fn credentials(&self, provider: Provider) -> Result<Option<Credentials>, WeatherError> {
         let creds = self
             .store
-            .get_credentials(provider)
-            .context("failed to read credentials from store")?
-            .ok_or_else(|| {
-                anyhow!(
-                    "No credentials found for provider `{provider:?}`. \
-                     Please configure it first."
-                )
-            })?;
+            .get_credentials(&self.profile, provider)
+            .map_err(WeatherError::Store)?;
+        // Mock provider works without `configure`.
+        #[cfg(feature = "mock")]
+        let creds = creds.or((provider == Provider::Mock).then_some(Credentials::Mock));
+
+        Ok(creds)
+    }
+
+    /// Create client for the resolved provider, checking it supports `days` ahead.
+    ///
+    /// With `auto_provider` set, falls back to other configured providers which do.
+    fn client_for(
+        &mut self,
+        provider: Option<Provider>,
+        days: u32,
+    ) -> Result<(Provider, Box<dyn ProviderClient>), WeatherError> {
+        let (provider, source) = self.resolve_provider(provider)?;
+        if !self.auto_provider {
+            let checked = self.checked_client(provider, days)?;
+            self.resolution = Some((provider, source));
+            return Ok(checked);
+        }
+
+        // Only providers the user configured, never the unconfigured mock one.
+        let fallbacks = self.configured_providers()?;
+        let fallbacks = fallbacks.into_iter().filter(|&p| p != provider);
+        let mut limits = Vec::new();
+        for candidate in std::iter::once(provider).chain(fallbacks) {
+            match self.checked_client(candidate, days) {
+                Err(WeatherError::ForecastRangeExceeded { max, .. }) => {
+                    limits.push((candidate, max));
+                }
+                Err(err) => return Err(err),
+                Ok(checked) => {
+                    let source = if candidate == provider {
+                        source
+                    } else {
+                        debug!(
+                            "{provider:?} can't forecast {days} days ahead, using {candidate:?}"
+                        );
+                        ResolutionSource::AutoFallback {
+                            requested: provider,
+                        }
+                    };
+                    self.resolution = Some((candidate, source));
+                    return Ok(checked);
+                }
+            }
+        }
+
+        Err(WeatherError::NoProviderCoversRange {
+            requested: days + 1,
+            limits,
+        })
+    }
+
+    /// Create client for `provider`, checking first it supports `days` ahead.
+    fn checked_client(
+        &mut self,
+        provider: Provider,
+        days: u32,
+    ) -> Result<(Provider, Box<dyn ProviderClient>), WeatherError> {
+        if self.offline {
+            return Err(WeatherError::Offline);
+        }
+        let creds = self
+            .credentials(provider)?
+            .ok_or(WeatherError::MissingCredentials(provider))?;
         debug!("Got credentials");
 
+        let requested = days + 1;
+        let free_max = provider.capabilities().max_forecast_days;
+        let max = self
+            .store
+            .get_max_forecast_days(provider)
+            .map_err(WeatherError::Store)?
+            .unwrap_or(free_max);
+        if requested > max {
+            return Err(WeatherError::ForecastRangeExceeded {
+                requested,
+                max,
+                provider,
+            });
+        }
+        if requested > free_max {
+            warn!(
+                "{requested} forecast days exceed {provider:?} free plan limit of {free_max}, \
+                 the request fails unless your plan allows it"
+            );
+        }
+
         let client = self.factory.create_client(provider, creds)?;
+        Ok((provider, client))
+    }
+
+    /// Count a call towards the monthly cap of providers without quota headers.
+    fn track_call(&mut self, provider: Provider) {
+        let Some(cap) = provider.monthly_call_cap() else {
+            return;
+        };
+
+        let month = Local::now().format("%Y-%m").to_string();
+        let calls = match self.store.record_call(provider, &month) {
+            Ok(calls) => calls,
+            Err(err) => {
+                warn!("Failed to record {provider:?} call: {err:#}");
+                return;
+            }
+        };
+        debug!("{provider:?} calls this month: {calls}");
+
+        if let Some(observer) = &self.quota_observer {
+            observer.observe(&QuotaStatus {
+                provider,
+                remaining: cap.saturating_sub(calls),
+                limit: Some(cap),
+            });
+        }
+    }
 
-        client.get_weather(address, days)
+    /// When the report of the last `get_weather` request was fetched, if it was served from
+    /// the report cache (fresh, stale or offline) rather than fetched just now.
+    pub fn cached_at(&self) -> Option<DateTime<Utc>> {
+        self.cached_at
     }
 
-    fn resolve_provider(&mut self, provider: Option<Provider>) -> Result<Provider> {
+    /// Provider which served the last forecast or current conditions request and why it
+    /// was picked, `None` before the first one.
+    ///
+    /// Unlike `resolve_provider`, accounts for `with_auto_provider` fallbacks.
+    pub fn resolution(&self) -> Option<(Provider, ResolutionSource)> {
+        self.resolution
+    }
+
+    /// Pick the provider to use when `provider` is not given explicitly, and say why.
+    pub fn resolve_provider(
+        &mut self,
+        provider: Option<Provider>,
+    ) -> Result<(Provider, ResolutionSource), WeatherError> {
         if let Some(p) = provider {
-            return Ok(p);
+            return Ok((p, ResolutionSource::Flag));
         }
 
-        self.store
-            .get_default_provider()
-            .context("failed to read default provider from store")?
-            .ok_or_else(|| {
-                anyhow!(
-                    "No provider specified and no default provider set. \
-                     Please configure a provider and/or set a default."
-                )
-            })
+        if let Some(default) = self
+            .store
+            .get_default_provider(&self.profile)
+            .map_err(WeatherError::Store)?
+        {
+            return Ok((default, ResolutionSource::StoredDefault));
+        }
+
+        match self.configured_providers()?[..] {
+            // First run, point at `configure` instead of asking for a default.
+            [] => Err(WeatherError::NoProvidersConfigured),
+            [only] => {
+                debug!("No default provider, using the only configured one: {only:?}");
+                Ok((only, ResolutionSource::OnlyConfigured))
+            }
+            _ => Err(WeatherError::NoDefaultProvider),
+        }
     }
 }
 
-pub fn days_from_today(date_str: &str) -> Result<u32> {
-    debug!("Calculating days from today for date `{date_str}`");
-    let target = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
-        .context("invalid date format (expected YYYY-MM-DD)")?;
-    debug!("Parsed date `{date_str}` as `{target:?}`");
+/// Forecast request of `WeatherService::prepare_fetch`.
+enum Prepared {
+    /// Answered by a cached report younger than `fresh_for`.
+    Fresh(Box<WeatherReport>),
+    /// Has to be fetched from the provider.
+    Fetch(Fetch),
+}
 
-    let today = Local::now().date_naive();
-    debug!("Today is `{today:?}`");
+impl Prepared {
+    /// Fetch the report unless it's cached, returning the request if it was sent.
+    fn send(self) -> (Option<Fetch>, Result<WeatherReport, WeatherError>) {
+        match self {
+            Prepared::Fresh(report) => (None, Ok(*report)),
+            Prepared::Fetch(fetch) => {
+                let report = fetch.client.get_weather(fetch.address.clone(), fetch.days);
+                (Some(fetch), report)
+            }
+        }
+    }
+}
+
+/// Forecast request ready to be sent.
+struct Fetch {
+    provider: Provider,
+    client: Box<dyn ProviderClient>,
+    address: String,
+    days: u32,
+    /// Report cache key, see `WeatherService::report_key`.
+    key: String,
+}
+
+/// Whether `err` means the provider can't serve requests right now, rather than
+/// the request itself being wrong.
+fn is_outage(err: &WeatherError) -> bool {
+    matches!(
+        err,
+        WeatherError::Http(_) | WeatherError::RateLimited | WeatherError::ProviderUnavailable(_)
+    )
+}
 
-    if target < today {
-        return Err(anyhow!("date is in the past"));
+/// Furthest date accepted, in days from today, far past any provider's forecast range.
+///
+/// Rejects typos like a wrong year before they reach a provider.
+pub const MAX_DAYS_AHEAD: u32 = 365;
+
+/// Longest address accepted, real-world addresses are far shorter.
+pub const MAX_ADDRESS_LEN: usize = 200;
+
+/// Trim address and check it's neither empty nor too long, before spending a provider call.
+fn validate_address(address: &str) -> Result<String, WeatherError> {
+    let address = address.trim();
+
+    if address.is_empty() {
+        return Err(WeatherError::EmptyAddress);
+    }
+    let len = address.chars().count();
+    if len > MAX_ADDRESS_LEN {
+        return Err(WeatherError::AddressTooLong {
+            len,
+            max: MAX_ADDRESS_LEN,
+        });
     }
 
-    Ok((target - today).num_days() as u32)
+    Ok(address.to_string())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::{Duration, Local, NaiveDate};
+/// Health of a single provider, see `WeatherService::status`.
+#[derive(Debug)]
+pub struct ProviderStatus {
+    pub provider: Provider,
+    pub has_credentials: bool,
+    /// Result of pinging the provider's base URL.
+    pub reachable: Result<(), WeatherError>,
+    /// Result of checking the credentials, `None` if there are none.
+    pub verified: Option<Result<(), WeatherError>>,
+}
 
-    fn fmt(d: NaiveDate) -> String {
-        d.format("%Y-%m-%d").to_string()
+impl ProviderStatus {
+    /// Reachable and, if configured, accepting the credentials.
+    pub fn is_ok(&self) -> bool {
+        self.reachable.is_ok() && !matches!(self.verified, Some(Err(_)))
     }
+}
 
-    #[test]
-    fn today_returns_zero() {
-        let today = Local::now().date_naive();
-        let date_str = fmt(today);
+/// Fluent builder for `WeatherService`.
+pub struct WeatherServiceBuilder<S, F>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+{
+    store: S,
+    factory: F,
+    profile: Option<String>,
+    quota_observer: Option<Arc<dyn QuotaObserver>>,
+    report_cache: Option<Arc<dyn ReportCache>>,
+    allow_stale: bool,
+    fresh_for: Option<Duration>,
+    auto_provider: bool,
+    tz_aware: bool,
+    offline: bool,
+}
 
-        let result = days_from_today(&date_str).unwrap();
-        assert_eq!(result, 0);
+impl<S, F> WeatherServiceBuilder<S, F>
+where
+    S: ServiceStore,
+    F: ProviderClientFactory,
+{
+    /// Use credentials from the given profile instead of the default one.
+    pub fn with_profile(mut self, profile: impl Into<String>) -> Self {
+        self.profile = Some(profile.into());
+        self
     }
 
-    #[test]
-    fn tomorrow_returns_one() {
-        let today = Local::now().date_naive();
-        let tomorrow = today + Duration::days(1);
-        let date_str = fmt(tomorrow);
+    /// Report locally counted quota to `observer`.
+    pub fn with_quota_observer(mut self, observer: Arc<dyn QuotaObserver>) -> Self {
+        self.quota_observer = Some(observer);
+        self
+    }
 
-        let result = days_from_today(&date_str).unwrap();
-        assert_eq!(result, 1);
+    /// Keep fetched reports in `cache`.
+    pub fn with_report_cache(mut self, cache: Arc<dyn ReportCache>) -> Self {
+        self.report_cache = Some(cache);
+        self
     }
 
-    #[test]
-    fn past_date_returns_error() {
-        let today = Local::now().date_naive();
-        let past = today - Duration::days(1);
-        let date_str = fmt(past);
+    /// Serve the cached report, marked stale, when the provider is unreachable.
+    pub fn with_allow_stale(mut self, allow_stale: bool) -> Self {
+        self.allow_stale = allow_stale;
+        self
+    }
 
-        let err = days_from_today(&date_str).unwrap_err();
-        let msg = err.to_string();
-        assert!(
-            msg.contains("in the past"),
-            "unexpected error message: {msg}"
-        );
+    /// Serve cached reports younger than `fresh_for` instead of calling the provider again.
+    ///
+    /// Needs a report cache, see `with_report_cache`.
+    pub fn with_fresh_for(mut self, fresh_for: Duration) -> Self {
+        self.fresh_for = Some(fresh_for);
+        self
+    }
+
+    /// Pick the first configured provider able to forecast the requested day, preferring
+    /// the requested or default one, instead of failing with `ForecastRangeExceeded`.
+    pub fn with_auto_provider(mut self, auto_provider: bool) -> Self {
+        self.auto_provider = auto_provider;
+        self
+    }
+
+    /// Count a requested date's days ahead from today where the location is, instead of
+    /// on this machine, e.g. Kyiv is a day ahead of Los Angeles in the evening.
+    ///
+    /// Resolves the location before fetching the forecast. Falls back to the machine's
+    /// date for providers which don't return a timezone.
+    pub fn with_tz_aware(mut self, tz_aware: bool) -> Self {
+        self.tz_aware = tz_aware;
+        self
+    }
+
+    /// Serve `get_weather` only from the report cache, regardless of age, and fail every
+    /// other request with `WeatherError::Offline`, without creating a provider client.
+    ///
+    /// Dates are counted from this machine's today, even with `with_tz_aware`.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn build(self) -> WeatherService<S, F> {
+        WeatherService {
+            store: self.store,
+            factory: self.factory,
+            profile: self.profile.unwrap_or_else(|| DEFAULT_PROFILE.to_string()),
+            quota_observer: self.quota_observer,
+            report_cache: self.report_cache,
+            allow_stale: self.allow_stale,
+            fresh_for: self.fresh_for,
+            auto_provider: self.auto_provider,
+            tz_aware: self.tz_aware,
+            offline: self.offline,
+            resolution: None,
+            cached_at: None,
+        }
+    }
+}
+
+pub fn days_from_today(date_str: &str) -> Result<u32, WeatherError> {
+    days_between(Local::now().date_naive(), date_str)
+}
+
+/// Days from `today` to the `date_str` date, "YYYY-MM-DD" or a Unix timestamp in seconds.
+pub fn days_between(today: NaiveDate, date_str: &str) -> Result<u32, WeatherError> {
+    debug!("Calculating days from {today} for date `{date_str}`");
+    let days = days_until(today, date_str)?;
+
+    u32::try_from(days).map_err(|_| WeatherError::DateInPast)
+}
+
+/// Days from `today` to the `date_str` date, negative if it's in the past.
+///
+/// Fails on dates more than `MAX_DAYS_AHEAD` days out.
+fn days_until(today: NaiveDate, date_str: &str) -> Result<i64, WeatherError> {
+    let target = parse_date(date_str, &Local)?;
+    debug!("Parsed date `{date_str}` as `{target:?}`");
+
+    let days = (target - today).num_days();
+    if days > i64::from(MAX_DAYS_AHEAD) {
+        return Err(WeatherError::DateTooFarAhead {
+            max: MAX_DAYS_AHEAD,
+        });
+    }
+
+    Ok(days)
+}
+
+/// Date of "YYYY-MM-DD", or of a Unix timestamp in seconds in `tz`, e.g. "1732881600".
+///
+/// Only all-digit strings are timestamps, so signed ones like "+3" are invalid dates.
+fn parse_date<Z: TimeZone>(date_str: &str, tz: &Z) -> Result<NaiveDate, WeatherError> {
+    if date_str.is_empty() || !date_str.bytes().all(|b| b.is_ascii_digit()) {
+        return NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(WeatherError::InvalidDate);
+    }
+
+    // Timestamps chrono can't represent are far past any forecast anyway.
+    date_str
+        .parse()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|time| time.with_timezone(tz).date_naive())
+        .ok_or(WeatherError::DateTooFarAhead {
+            max: MAX_DAYS_AHEAD,
+        })
+}
+
+/// Date at `now` in the IANA `timezone`, or on this machine if it's unknown or invalid.
+pub fn today_in(timezone: Option<&str>, now: DateTime<Utc>) -> NaiveDate {
+    match timezone.map(str::parse::<Tz>) {
+        Some(Ok(tz)) => now.with_timezone(&tz).date_naive(),
+        Some(Err(err)) => {
+            warn!("Ignoring unknown timezone: {err}");
+            now.with_timezone(&Local).date_naive()
+        }
+        None => now.with_timezone(&Local).date_naive(),
+    }
+}
+
+/// Day offsets from today for the inclusive `from`..=`to` date range.
+pub fn day_range(from: &str, to: &str) -> Result<RangeInclusive<u32>, WeatherError> {
+    let today = Local::now().date_naive();
+    let start = days_between(today, from)?;
+    let end = days_between(today, to)?;
+
+    if end < start {
+        return Err(WeatherError::InvalidDateRange {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+    }
+
+    Ok(start..=end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apis::report_cache::FileReportCache;
+    use crate::apis::retry::RetryPolicy;
+    use crate::apis::{HttpProviderClientFactory, ProviderClient};
+    use crate::testing::{self, FakeClient, FakeFactory, InMemoryStore};
+    use chrono::{Duration, Local, Months, NaiveDate};
+    use rstest::rstest;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Factory that must never be reached in these tests.
+    struct UnreachableFactory;
+
+    impl ProviderClientFactory for UnreachableFactory {
+        fn create_client(
+            &self,
+            _provider: Provider,
+            _credentials: Credentials,
+        ) -> Result<Box<dyn ProviderClient>, WeatherError> {
+            unreachable!("client must not be created")
+        }
+    }
+
+    fn fmt(d: NaiveDate) -> String {
+        d.format("%Y-%m-%d").to_string()
+    }
+
+    #[test]
+    fn empty_config_returns_no_providers_configured_error() {
+        let mut service = WeatherService::new(InMemoryStore::default(), UnreachableFactory);
+
+        let err = service.get_weather("Kyiv".into(), None, None).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::NoProvidersConfigured),
+            "unexpected error: {err:?}"
+        );
+        assert_eq!(err.to_string(), "no providers configured");
+    }
+
+    #[test]
+    fn single_configured_provider_is_used_without_default() {
+        let mut store = configured_store(Provider::WeatherApi);
+        store.defaults.clear();
+        let mut service = WeatherService::new(store, FakeFactory::default());
+
+        let body = service.get_weather_raw("Kyiv".into(), None, None).unwrap();
+
+        assert_eq!(body["provider"], "weatherapi");
+    }
+
+    #[test]
+    fn resolve_provider_reports_flag() {
+        let mut service = WeatherService::new(
+            configured_store(Provider::WeatherApi),
+            FakeFactory::default(),
+        );
+
+        let resolved = service
+            .resolve_provider(Some(Provider::AccuWeather))
+            .unwrap();
+
+        assert_eq!(resolved, (Provider::AccuWeather, ResolutionSource::Flag));
+    }
+
+    #[test]
+    fn resolve_provider_reports_stored_default() {
+        let mut service = WeatherService::new(
+            configured_store(Provider::WeatherApi),
+            FakeFactory::default(),
+        );
+
+        let resolved = service.resolve_provider(None).unwrap();
+
+        assert_eq!(
+            resolved,
+            (Provider::WeatherApi, ResolutionSource::StoredDefault)
+        );
+    }
+
+    #[test]
+    fn resolve_provider_reports_only_configured_provider() {
+        let mut store = configured_store(Provider::AccuWeather);
+        store.defaults.clear();
+        let mut service = WeatherService::new(store, FakeFactory::default());
+
+        let resolved = service.resolve_provider(None).unwrap();
+
+        assert_eq!(
+            resolved,
+            (Provider::AccuWeather, ResolutionSource::OnlyConfigured)
+        );
+    }
+
+    #[test]
+    fn several_configured_providers_without_default_return_no_default_provider_error() {
+        let mut store = configured_store(Provider::AccuWeather);
+        store.defaults.clear();
+        store.providers.insert(
+            (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+            Credentials::WeatherApi {
+                api_key: "KEY".into(),
+            },
+        );
+        let mut service = WeatherService::new(store, UnreachableFactory);
+
+        let err = service.get_weather("Kyiv".into(), None, None).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::NoDefaultProvider),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn missing_credentials_returns_missing_credentials_error() {
+        let mut service = WeatherService::new(InMemoryStore::default(), UnreachableFactory);
+
+        let err = service
+            .get_weather("Kyiv".into(), None, Some(Provider::AccuWeather))
+            .unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::MissingCredentials(Provider::AccuWeather)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::whitespace_only(" \t\n ")]
+    fn blank_address_is_rejected_before_request(#[case] address: &str) {
+        let mut service = service_with_unreachable_client();
+
+        let err = service.get_weather(address.into(), None, None).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::EmptyAddress),
+            "unexpected error: {err:?}"
+        );
+        assert!(matches!(
+            service.resolve_location(address, None),
+            Err(WeatherError::EmptyAddress)
+        ));
+    }
+
+    #[test]
+    fn overly_long_address_is_rejected_before_request() {
+        let mut service = service_with_unreachable_client();
+
+        let err = service
+            .get_weather("a".repeat(MAX_ADDRESS_LEN + 1), None, None)
+            .unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::AddressTooLong { len: 201, max: 200 }),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn address_is_trimmed_before_request() {
+        let mut server = mockito::Server::new();
+        let forecast = server
+            .mock("GET", "/forecast.json")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "q".into(),
+                "Kyiv, Ukraine".into(),
+            ))
+            .with_status(500)
+            .expect(1)
+            .create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let mut service = WeatherService::new(configured_store(Provider::WeatherApi), factory);
+
+        let _ = service.get_weather("  Kyiv, Ukraine \n".into(), None, None);
+
+        forecast.assert();
+    }
+
+    #[rstest]
+    #[case::alias("home", "Kyiv, Ukraine")]
+    #[case::alias_shadows_address(" Paris ", "Paris, Texas")]
+    #[case::address("Lviv", "Lviv")]
+    fn saved_alias_is_replaced_with_its_address(#[case] address: &str, #[case] expected: &str) {
+        let mut server = mockito::Server::new();
+        let forecast = server
+            .mock("GET", "/forecast.json")
+            .match_query(mockito::Matcher::UrlEncoded("q".into(), expected.into()))
+            .with_status(500)
+            .expect(1)
+            .create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let mut service = WeatherService::new(configured_store(Provider::WeatherApi), factory);
+        service
+            .save_location_alias("home", "Kyiv, Ukraine")
+            .unwrap();
+        service
+            .save_location_alias("Paris", "Paris, Texas")
+            .unwrap();
+
+        let _ = service.get_weather(address.into(), None, None);
+
+        forecast.assert();
+    }
+
+    #[test]
+    fn alias_of_alias_saves_underlying_address() {
+        let mut service = service_with_unreachable_client();
+
+        service
+            .save_location_alias("home", "Kyiv, Ukraine")
+            .unwrap();
+        service.save_location_alias("base", "home").unwrap();
+
+        assert_eq!(
+            service.store.locations.get("base").map(String::as_str),
+            Some("Kyiv, Ukraine")
+        );
+    }
+
+    #[test]
+    fn blank_alias_is_rejected() {
+        let mut service = service_with_unreachable_client();
+
+        let err = service.save_location_alias("  ", "Kyiv").unwrap_err();
+
+        assert!(matches!(err, WeatherError::EmptyAddress), "{err:?}");
+        assert!(service.store.locations.is_empty());
+    }
+
+    #[rstest]
+    #[case::allowed(true)]
+    #[case::not_allowed(false)]
+    fn cached_report_is_served_when_provider_is_down(#[case] allow_stale: bool) {
+        let mut server = mockito::Server::new();
+        let ok = server
+            .mock("GET", "/forecast.json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "location": {"name": "Kyiv", "country": "Ukraine"},
+                    "forecast": {"forecastday": [{
+                        "date": "2024-11-29",
+                        "day": {"maxtemp_c": 12.0, "mintemp_c": 3.0, "condition": {"code": 1000, "text": "Sunny"}}
+                    }]}
+                }"#,
+            )
+            .create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cache = FileReportCache::new(tmpdir.path().join("reports.json"));
+        let mut service = WeatherService::builder(configured_store(Provider::WeatherApi), factory)
+            .with_report_cache(Arc::new(cache))
+            .with_allow_stale(allow_stale)
+            .build();
+
+        let live = service.get_weather("Kyiv".into(), None, None).unwrap();
+        ok.remove();
+        let _down = server
+            .mock("GET", "/forecast.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .create();
+        let result = service.get_weather(" kyiv ".into(), None, None);
+
+        assert_eq!(live.stale_since, None);
+        if allow_stale {
+            let stale = result.unwrap();
+            assert_eq!(stale.stale_minutes(), Some(0));
+            assert_eq!(stale.location, live.location);
+        } else {
+            let err = result.unwrap_err();
+            assert!(matches!(err, WeatherError::Http(_)), "{err:?}");
+        }
+    }
+
+    #[test]
+    fn fresh_cached_report_skips_provider_call() {
+        let mut server = mockito::Server::new();
+        let forecast = server
+            .mock("GET", "/forecast.json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "location": {"name": "Kyiv", "country": "Ukraine"},
+                    "forecast": {"forecastday": [{
+                        "date": "2024-11-29",
+                        "day": {"maxtemp_c": 12.0, "mintemp_c": 3.0, "condition": {"code": 1000, "text": "Sunny"}}
+                    }]}
+                }"#,
+            )
+            .expect(1)
+            .create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .build()
+            .unwrap();
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cache = FileReportCache::new(tmpdir.path().join("reports.json"));
+        let mut service = WeatherService::builder(configured_store(Provider::WeatherApi), factory)
+            .with_report_cache(Arc::new(cache))
+            .with_fresh_for(std::time::Duration::from_secs(600))
+            .build();
+
+        let live = service.get_weather("Kyiv".into(), None, None).unwrap();
+        let cached = service.get_weather("kyiv".into(), None, None).unwrap();
+
+        forecast.assert();
+        assert_eq!(cached, live);
+    }
+
+    #[test]
+    fn cached_report_in_other_language_is_not_served() {
+        let mut server = mockito::Server::new();
+        let forecast = server
+            .mock("GET", "/forecast.json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "location": {"name": "Kyiv", "country": "Ukraine"},
+                    "forecast": {"forecastday": [{
+                        "date": "2024-11-29",
+                        "day": {"maxtemp_c": 12.0, "mintemp_c": 3.0, "condition": {"code": 1000, "text": "Sunny"}}
+                    }]}
+                }"#,
+            )
+            .expect(2)
+            .create();
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cache: Arc<dyn ReportCache> =
+            Arc::new(FileReportCache::new(tmpdir.path().join("reports.json")));
+        let service = |language: &str| {
+            let factory = HttpProviderClientFactory::builder()
+                .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+                .with_language(language)
+                .build()
+                .unwrap();
+            WeatherService::builder(configured_store(Provider::WeatherApi), factory)
+                .with_report_cache(cache.clone())
+                .with_fresh_for(std::time::Duration::from_secs(600))
+                .build()
+        };
+
+        service("en")
+            .get_weather("Kyiv".into(), None, None)
+            .unwrap();
+        service("uk")
+            .get_weather("Kyiv".into(), None, None)
+            .unwrap();
+
+        forecast.assert();
+    }
+
+    #[test]
+    fn offline_serves_cached_report_without_client() {
+        let mut server = mockito::Server::new();
+        let forecast = server
+            .mock("GET", "/forecast.json")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+                    "location": {"name": "Kyiv", "country": "Ukraine"},
+                    "forecast": {"forecastday": [{
+                        "date": "2024-11-29",
+                        "day": {"maxtemp_c": 12.0, "mintemp_c": 3.0, "condition": {"code": 1000, "text": "Sunny"}}
+                    }]}
+                }"#,
+            )
+            .expect(1)
+            .create();
+        let factory = || {
+            HttpProviderClientFactory::builder()
+                .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+                .build()
+                .unwrap()
+        };
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cache: Arc<dyn ReportCache> =
+            Arc::new(FileReportCache::new(tmpdir.path().join("reports.json")));
+        let live = WeatherService::builder(configured_store(Provider::WeatherApi), factory())
+            .with_report_cache(cache.clone())
+            .build()
+            .get_weather("Kyiv".into(), None, None)
+            .unwrap();
+        let mut offline =
+            WeatherService::builder(configured_store(Provider::WeatherApi), factory())
+                .with_report_cache(cache)
+                .with_offline(true)
+                .build();
+
+        let cached = offline.get_weather(" kyiv ".into(), None, None).unwrap();
+
+        forecast.assert();
+        assert_eq!(cached.location, live.location);
+        assert!(cached.stale_since.is_some());
+    }
+
+    #[test]
+    fn offline_without_cached_report_fails_without_client() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let cache = FileReportCache::new(tmpdir.path().join("reports.json"));
+        let mut service =
+            WeatherService::builder(configured_store(Provider::WeatherApi), UnreachableFactory)
+                .with_report_cache(Arc::new(cache))
+                .with_offline(true)
+                .build();
+
+        let err = service.get_weather("Kyiv".into(), None, None).unwrap_err();
+        assert!(matches!(err, WeatherError::Offline), "{err:?}");
+
+        let err = service.get_current("Kyiv".into(), None).unwrap_err();
+        assert!(matches!(err, WeatherError::Offline), "{err:?}");
+
+        let err = service.status().unwrap_err();
+        assert!(matches!(err, WeatherError::Offline), "{err:?}");
+    }
+
+    #[test]
+    fn preflight_reports_outage_of_default_provider() {
+        let mut server = mockito::Server::new();
+        let search = server
+            .mock("GET", "/search.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(503)
+            .expect(1)
+            .create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let mut service = WeatherService::new(configured_store(Provider::WeatherApi), factory);
+
+        let err = service.preflight(None).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::ProviderUnavailable(Provider::WeatherApi)),
+            "unexpected error: {err:?}"
+        );
+        search.assert();
+    }
+
+    #[test]
+    fn status_isolates_provider_failures() {
+        let mut server = mockito::Server::new();
+        let _ping = server
+            .mock("HEAD", "/")
+            .with_status(404)
+            .expect_at_least(1)
+            .create();
+        let _search = server
+            .mock("GET", "/search.json")
+            .match_query(mockito::Matcher::Any)
+            .with_status(401)
+            .create();
+        // Nothing listens on the port once the listener is dropped.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let unreachable = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, format!("{}/", server.url()))
+            .with_base_url(Provider::AccuWeather, unreachable)
+            .with_base_url(Provider::TomorrowIo, format!("{}/", server.url()))
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let mut service = WeatherService::new(configured_store(Provider::WeatherApi), factory);
+
+        let statuses = service.status().unwrap();
+
+        let status = |provider| statuses.iter().find(|s| s.provider == provider).unwrap();
+        let weatherapi = status(Provider::WeatherApi);
+        assert!(weatherapi.has_credentials);
+        assert!(weatherapi.reachable.is_ok());
+        assert!(
+            matches!(
+                weatherapi.verified,
+                Some(Err(WeatherError::InvalidApiKey(Provider::WeatherApi)))
+            ),
+            "{weatherapi:?}"
+        );
+        assert!(!weatherapi.is_ok());
+
+        let accuweather = status(Provider::AccuWeather);
+        assert!(!accuweather.has_credentials);
+        assert!(matches!(accuweather.reachable, Err(WeatherError::Http(_))));
+        assert!(accuweather.verified.is_none());
+        assert!(!accuweather.is_ok());
+
+        let tomorrowio = status(Provider::TomorrowIo);
+        assert!(!tomorrowio.has_credentials);
+        assert!(tomorrowio.is_ok(), "{tomorrowio:?}");
+    }
+
+    fn configured_store(provider: Provider) -> InMemoryStore {
+        let credentials = match provider {
+            Provider::AccuWeather => Credentials::AccuWeather {
+                api_key: "KEY".into(),
+                base_url: None,
+            },
+            _ => Credentials::WeatherApi {
+                api_key: "KEY".into(),
+            },
+        };
+
+        InMemoryStore {
+            defaults: HashMap::from([(DEFAULT_PROFILE.to_string(), provider)]),
+            providers: HashMap::from([((DEFAULT_PROFILE.to_string(), provider), credentials)]),
+            ..Default::default()
+        }
+    }
+
+    fn service_with_unreachable_client() -> WeatherService<InMemoryStore, UnreachableFactory> {
+        WeatherService::new(configured_store(Provider::WeatherApi), UnreachableFactory)
+    }
+
+    fn service_with(
+        provider: Provider,
+        credentials: Credentials,
+    ) -> WeatherService<InMemoryStore, HttpProviderClientFactory> {
+        let store = InMemoryStore {
+            defaults: HashMap::from([(DEFAULT_PROFILE.to_string(), provider)]),
+            providers: HashMap::from([((DEFAULT_PROFILE.to_string(), provider), credentials)]),
+            ..Default::default()
+        };
+
+        WeatherService::new(store, HttpProviderClientFactory::new())
+    }
+
+    #[test]
+    fn resolved_location_is_reused_for_each_day() {
+        let mut server = mockito::Server::new();
+        let search = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{
+                    "Key": "324505", "LocalizedName": "Kyiv", "Country": {"LocalizedName": "Ukraine"},
+                    "GeoPosition": {"Latitude": 50.45, "Longitude": 30.524}
+                }]"#,
+            )
+            .expect(1)
+            .create();
+        let day = r#"{
+            "Date": "2024-12-01T07:00:00+02:00",
+            "Temperature": {"Minimum": {"Value": 3.0}, "Maximum": {"Value": 12.0}},
+            "Day": {"Icon": 1, "IconPhrase": "Sunny"},
+            "Night": {"Icon": 33, "IconPhrase": "Clear"}
+        }"#;
+        let forecast = server
+            .mock("GET", "/forecasts/v1/daily/5day/324505")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"DailyForecasts": [{}]}}"#, [day; 5].join(",")))
+            .expect(4)
+            .create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::AccuWeather, format!("{}/", server.url()))
+            .build()
+            .unwrap();
+        let store = InMemoryStore {
+            defaults: HashMap::from([(DEFAULT_PROFILE.to_string(), Provider::AccuWeather)]),
+            providers: HashMap::from([(
+                (DEFAULT_PROFILE.to_string(), Provider::AccuWeather),
+                Credentials::AccuWeather {
+                    api_key: "KEY".into(),
+                    base_url: None,
+                },
+            )]),
+            ..Default::default()
+        };
+        let mut service = WeatherService::new(store, factory);
+
+        let location = service.resolve_location("Kyiv", None).unwrap();
+        // Today alone would be fetched with the 1 day forecast.
+        for days in 1..5 {
+            service
+                .get_weather_at(&location, Some(days_ahead(days)), None)
+                .unwrap();
+        }
+
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn range_resolves_location_and_requests_forecast_once() {
+        let mut server = mockito::Server::new();
+        let search = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{
+                    "Key": "324505", "LocalizedName": "Kyiv", "Country": {"LocalizedName": "Ukraine"},
+                    "GeoPosition": {"Latitude": 50.45, "Longitude": 30.524}
+                }]"#,
+            )
+            .expect(1)
+            .create();
+        let day = r#"{
+            "Date": "2024-12-01T07:00:00+02:00",
+            "Temperature": {"Minimum": {"Value": 3.0}, "Maximum": {"Value": 12.0}},
+            "Day": {"Icon": 1, "IconPhrase": "Sunny"},
+            "Night": {"Icon": 33, "IconPhrase": "Clear"}
+        }"#;
+        let forecast = server
+            .mock("GET", "/forecasts/v1/daily/5day/324505")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"DailyForecasts": [{}]}}"#, [day; 5].join(",")))
+            .expect(1)
+            .create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::AccuWeather, format!("{}/", server.url()))
+            .build()
+            .unwrap();
+        let mut service = WeatherService::new(configured_store(Provider::AccuWeather), factory);
+
+        let reports = service
+            .get_weather_range("Kyiv".into(), &days_ahead(1), &days_ahead(3), None)
+            .unwrap();
+
+        assert_eq!(reports.len(), 3);
+        search.assert();
+        forecast.assert();
+    }
+
+    #[test]
+    fn date_past_provider_max_is_rejected_before_request() {
+        let mut service = service_with(
+            Provider::AccuWeather,
+            Credentials::AccuWeather {
+                api_key: "KEY".into(),
+                base_url: None,
+            },
+        );
+
+        let err = service
+            .get_weather("Kyiv".into(), Some(days_ahead(5)), None)
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                WeatherError::ForecastRangeExceeded {
+                    requested: 6,
+                    max: 5,
+                    provider: Provider::AccuWeather
+                }
+            ),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn date_at_provider_max_reaches_provider() {
+        let client = FakeClient::default();
+        let mut service = WeatherService::new(
+            configured_store(Provider::AccuWeather),
+            FakeFactory::new(client.clone()),
+        );
+
+        service
+            .get_weather("Kyiv".into(), Some(days_ahead(4)), None)
+            .unwrap();
+
+        assert_eq!(client.forecasts(), [("Kyiv".to_string(), 4)]);
+    }
+
+    #[test]
+    fn range_past_provider_max_is_rejected_before_creating_client() {
+        let mut service =
+            WeatherService::new(configured_store(Provider::AccuWeather), UnreachableFactory);
+
+        let err = service
+            .get_weather("Kyiv".into(), Some(days_ahead(5)), None)
+            .unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::ForecastRangeExceeded { max: 5, .. }),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn tz_aware_far_future_date_is_rejected_before_location_lookup() {
+        // Nothing listens there, a location lookup would fail with `Http`.
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::WeatherApi, "http://127.0.0.1:9/")
+            .with_retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        let mut service = WeatherService::builder(configured_store(Provider::WeatherApi), factory)
+            .with_tz_aware(true)
+            .build();
+
+        let err = service
+            .get_weather("Kyiv".into(), Some(days_ahead(400)), None)
+            .unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::DateTooFarAhead { .. }),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn range_past_provider_max_is_rejected_before_request() {
+        let mut service = service_with(
+            Provider::AccuWeather,
+            Credentials::AccuWeather {
+                api_key: "KEY".into(),
+                base_url: None,
+            },
+        );
+
+        let err = service
+            .get_weather_range("Kyiv".into(), &days_ahead(2), &days_ahead(5), None)
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                WeatherError::ForecastRangeExceeded {
+                    requested: 6,
+                    max: 5,
+                    provider: Provider::AccuWeather
+                }
+            ),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    /// Factory of clients whose forecasts wait until two of them are in flight at once, so
+    /// requests sent one by one time out. "Atlantis" is never found, without waiting.
+    fn rendezvous_factory() -> FakeFactory {
+        let in_flight = Arc::new((Mutex::new(0_usize), std::sync::Condvar::new()));
+
+        FakeFactory::new(FakeClient::default().with_response(move |address, _days| {
+            if address == "Atlantis" {
+                return Err(WeatherError::AddressNotFound);
+            }
+            let (in_flight, arrived) = &*in_flight;
+            let mut in_flight = in_flight.lock().unwrap();
+            *in_flight += 1;
+            arrived.notify_all();
+            let (_in_flight, wait) = arrived
+                .wait_timeout_while(in_flight, std::time::Duration::from_secs(5), |n| *n < 2)
+                .unwrap();
+            if wait.timed_out() {
+                return Err(WeatherError::RateLimited);
+            }
+
+            Ok(WeatherReport {
+                location: address.to_string(),
+                ..testing::report()
+            })
+        }))
+    }
+
+    #[test]
+    fn get_weather_all_fetches_concurrently_and_keeps_failures_apart() {
+        let mut service =
+            WeatherService::new(configured_store(Provider::WeatherApi), rendezvous_factory());
+
+        let reports = service.get_weather_all(
+            vec![
+                ("Kyiv".into(), None),
+                ("Atlantis".into(), None),
+                ("Lviv".into(), None),
+            ],
+            None,
+        );
+
+        let locations: Vec<_> = reports
+            .iter()
+            .map(|report| report.as_ref().map(|report| report.location.as_str()))
+            .collect();
+        assert!(
+            matches!(
+                locations[..],
+                [Ok("Kyiv"), Err(WeatherError::AddressNotFound), Ok("Lviv")]
+            ),
+            "unexpected reports: {reports:?}"
+        );
+    }
+
+    /// AccuWeather by default, WeatherAPI configured too, Tomorrow.io not configured.
+    fn auto_provider_service() -> WeatherService<InMemoryStore, FakeFactory> {
+        let mut store = configured_store(Provider::AccuWeather);
+        store.providers.insert(
+            (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+            Credentials::WeatherApi {
+                api_key: "KEY".into(),
+            },
+        );
+
+        WeatherService::builder(store, FakeFactory::default())
+            .with_auto_provider(true)
+            .build()
+    }
+
+    #[rstest]
+    #[case(2, "accuweather")]
+    #[case(9, "weatherapi")]
+    fn auto_provider_prefers_default_and_falls_back_to_capable_one(
+        #[case] days: i64,
+        #[case] expected: &str,
+    ) {
+        let mut service = auto_provider_service();
+
+        let body = service
+            .get_weather_raw("Kyiv".into(), Some(days_ahead(days)), None)
+            .unwrap();
+
+        assert_eq!(body["provider"], expected);
+    }
+
+    #[rstest]
+    #[case(2, (Provider::AccuWeather, ResolutionSource::StoredDefault))]
+    #[case(
+        9,
+        (
+            Provider::WeatherApi,
+            ResolutionSource::AutoFallback {
+                requested: Provider::AccuWeather
+            }
+        )
+    )]
+    fn resolution_reports_provider_actually_used(
+        #[case] days: i64,
+        #[case] expected: (Provider, ResolutionSource),
+    ) {
+        let mut service = auto_provider_service();
+        assert_eq!(service.resolution(), None);
+
+        service
+            .get_weather_raw("Kyiv".into(), Some(days_ahead(days)), None)
+            .unwrap();
+
+        assert_eq!(service.resolution(), Some(expected));
+    }
+
+    #[test]
+    fn auto_provider_prefers_explicit_provider_over_default() {
+        let mut service = auto_provider_service();
+
+        let body = service
+            .get_weather_raw("Kyiv".into(), None, Some(Provider::WeatherApi))
+            .unwrap();
+
+        assert_eq!(body["provider"], "weatherapi");
+    }
+
+    #[test]
+    fn auto_provider_lists_every_limit_when_none_covers_the_day() {
+        let mut service = auto_provider_service();
+
+        let err = service
+            .get_weather_raw("Kyiv".into(), Some(days_ahead(20)), None)
+            .unwrap_err();
+
+        let WeatherError::NoProviderCoversRange { requested, limits } = &err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(*requested, 21);
+        assert_eq!(
+            *limits,
+            [(Provider::AccuWeather, 5), (Provider::WeatherApi, 14)]
+        );
+        assert!(
+            err.to_string()
+                .contains("`AccuWeather` up to 5, `WeatherApi` up to 14"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn without_auto_provider_default_provider_limit_is_an_error() {
+        let mut service = auto_provider_service();
+        service.auto_provider = false;
+
+        let err = service
+            .get_weather_raw("Kyiv".into(), Some(days_ahead(9)), None)
+            .unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::ForecastRangeExceeded { .. }),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    /// UTC+14, the first timezone to start a new day.
+    const KIRITIMATI: &str = "Pacific/Kiritimati";
+
+    /// Client resolving every address to Kiritimati, failing forecasts.
+    fn kiritimati_client() -> FakeClient {
+        FakeClient::default()
+            .with_location(ResolvedLocation {
+                name: "Kiritimati".into(),
+                country: "Kiribati".into(),
+                lat: 1.87,
+                lon: -157.4,
+                provider_key: None,
+                timezone: Some(KIRITIMATI.into()),
+            })
+            .with_error(|| WeatherError::AddressNotFound)
+    }
+
+    /// Day offsets of forecasts requested from `client`.
+    fn forecast_days(client: &FakeClient) -> Vec<u32> {
+        client
+            .forecasts()
+            .into_iter()
+            .map(|(_, days)| days)
+            .collect()
+    }
+
+    #[test]
+    fn tz_aware_counts_days_from_location_today() {
+        let client = kiritimati_client();
+        let mut service = WeatherService::builder(
+            configured_store(Provider::WeatherApi),
+            FakeFactory::new(client.clone()),
+        )
+        .with_tz_aware(true)
+        .build();
+        let today = fmt(today_in(Some(KIRITIMATI), Utc::now()));
+
+        service
+            .get_weather("Kiritimati".into(), Some(today), None)
+            .unwrap_err();
+
+        assert_eq!(forecast_days(&client), [0]);
+    }
+
+    #[test]
+    fn days_ahead_reach_client_as_is() {
+        let client = kiritimati_client();
+        let mut service = WeatherService::new(
+            configured_store(Provider::WeatherApi),
+            FakeFactory::new(client.clone()),
+        );
+
+        service
+            .get_weather_days_ahead("Kiritimati".into(), 3, None)
+            .unwrap_err();
+
+        assert_eq!(forecast_days(&client), [3]);
+    }
+
+    #[test]
+    fn tz_aware_rejects_date_already_past_at_location() {
+        let mut service = WeatherService::builder(
+            configured_store(Provider::WeatherApi),
+            FakeFactory::new(kiritimati_client()),
+        )
+        .with_tz_aware(true)
+        .build();
+        let yesterday = today_in(Some(KIRITIMATI), Utc::now()) - Duration::days(1);
+
+        let err = service
+            .get_weather("Kiritimati".into(), Some(fmt(yesterday)), None)
+            .unwrap_err();
+
+        assert!(matches!(err, WeatherError::DateInPast), "{err:?}");
+    }
+
+    #[rstest]
+    #[case::ahead_of_utc(Some("Europe/Kyiv"), "2024-11-30")]
+    #[case::behind_utc(Some("America/Los_Angeles"), "2024-11-29")]
+    #[case::legacy_name(Some("Europe/Kiev"), "2024-11-30")]
+    fn today_in_uses_location_date(#[case] timezone: Option<&str>, #[case] expected: &str) {
+        let now = "2024-11-29T23:30:00Z".parse().unwrap();
+
+        assert_eq!(fmt(today_in(timezone, now)), expected);
+    }
+
+    #[rstest]
+    #[case::unknown(None)]
+    #[case::invalid(Some("Mars/Olympus_Mons"))]
+    fn today_in_falls_back_to_local_date(#[case] timezone: Option<&str>) {
+        let now = Utc::now();
+
+        assert_eq!(
+            today_in(timezone, now),
+            now.with_timezone(&Local).date_naive()
+        );
+    }
+
+    #[test]
+    fn same_date_is_different_offset_across_timezones() {
+        let now = "2024-11-29T23:30:00Z".parse().unwrap();
+
+        let kyiv = days_between(today_in(Some("Europe/Kyiv"), now), "2024-11-30").unwrap();
+        let los_angeles =
+            days_between(today_in(Some("America/Los_Angeles"), now), "2024-11-30").unwrap();
+
+        assert_eq!((kyiv, los_angeles), (0, 1));
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingObserver(std::sync::Mutex<Vec<QuotaStatus>>);
+
+    impl QuotaObserver for RecordingObserver {
+        fn observe(&self, status: &QuotaStatus) {
+            self.0.lock().unwrap().push(*status);
+        }
+    }
+
+    #[rstest]
+    #[case(Credentials::WeatherApi { api_key: "KEY".into() }, vec![999_999, 999_998])]
+    #[case(Credentials::AccuWeather { api_key: "KEY".into(), base_url: None }, vec![])]
+    fn counts_calls_of_providers_without_quota_headers(
+        #[case] credentials: Credentials,
+        #[case] expected: Vec<u32>,
+    ) {
+        let provider = credentials.provider();
+        let store = InMemoryStore {
+            providers: HashMap::from([((DEFAULT_PROFILE.to_string(), provider), credentials)]),
+            ..Default::default()
+        };
+        let observer = Arc::new(RecordingObserver::default());
+        let mut service = WeatherService::builder(
+            store,
+            FakeFactory::new(FakeClient::default().with_error(|| WeatherError::AddressNotFound)),
+        )
+        .with_quota_observer(observer.clone())
+        .build();
+
+        let _ = service.get_weather("Kyiv".into(), None, Some(provider));
+        let _ = service.resolve_location("Kyiv", Some(provider));
+
+        let remaining: Vec<_> = observer
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|s| s.remaining)
+            .collect();
+        assert_eq!(remaining, expected);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn mock_provider_works_without_configured_credentials() {
+        let mut service =
+            WeatherService::new(InMemoryStore::default(), HttpProviderClientFactory::new());
+
+        let first = service
+            .get_weather("Kyiv".into(), Some(days_ahead(2)), Some(Provider::Mock))
+            .unwrap();
+        let second = service
+            .get_weather("Kyiv".into(), Some(days_ahead(2)), Some(Provider::Mock))
+            .unwrap();
+
+        assert_eq!(first.date, days_ahead(2));
+        assert_eq!(first, second);
+    }
+
+    fn days_ahead(days: i64) -> String {
+        fmt(Local::now().date_naive() + Duration::days(days))
+    }
+
+    #[test]
+    fn uses_credentials_and_default_provider_from_selected_profile() {
+        let store = InMemoryStore {
+            defaults: HashMap::from([
+                (DEFAULT_PROFILE.to_string(), Provider::AccuWeather),
+                ("work".to_string(), Provider::WeatherApi),
+            ]),
+            providers: HashMap::from([
+                (
+                    (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+                    Credentials::WeatherApi {
+                        api_key: "PERSONAL".into(),
+                    },
+                ),
+                (
+                    ("work".to_string(), Provider::WeatherApi),
+                    Credentials::WeatherApi {
+                        api_key: "WORK".into(),
+                    },
+                ),
+            ]),
+            ..Default::default()
+        };
+        let factory = FakeFactory::default();
+        let mut service = WeatherService::builder(store, factory.clone())
+            .with_profile("work")
+            .build();
+
+        let _ = service.get_weather("Kyiv".into(), None, None);
+
+        assert!(
+            matches!(&factory.created()[..], [Credentials::WeatherApi { api_key }] if api_key == "WORK"),
+            "unexpected credentials"
+        );
+    }
+
+    #[test]
+    fn missing_credentials_in_profile_returns_missing_credentials_error() {
+        let store = InMemoryStore {
+            defaults: HashMap::from([("work".to_string(), Provider::WeatherApi)]),
+            providers: HashMap::from([(
+                (DEFAULT_PROFILE.to_string(), Provider::WeatherApi),
+                Credentials::WeatherApi {
+                    api_key: "PERSONAL".into(),
+                },
+            )]),
+            ..Default::default()
+        };
+        let mut service = WeatherService::builder(store, UnreachableFactory)
+            .with_profile("work")
+            .build();
+
+        let err = service.get_weather("Kyiv".into(), None, None).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::MissingCredentials(Provider::WeatherApi)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn default_provider_is_not_shared_between_profiles() {
+        let store = InMemoryStore {
+            defaults: HashMap::from([(DEFAULT_PROFILE.to_string(), Provider::WeatherApi)]),
+            providers: HashMap::from([
+                (
+                    ("work".to_string(), Provider::WeatherApi),
+                    Credentials::WeatherApi {
+                        api_key: "KEY".into(),
+                    },
+                ),
+                (
+                    ("work".to_string(), Provider::AccuWeather),
+                    Credentials::AccuWeather {
+                        api_key: "KEY".into(),
+                        base_url: None,
+                    },
+                ),
+            ]),
+            ..Default::default()
+        };
+        let mut service = WeatherService::builder(store, UnreachableFactory)
+            .with_profile("work")
+            .build();
+
+        let err = service.get_weather("Kyiv".into(), None, None).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::NoDefaultProvider),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn configured_max_forecast_days_lets_request_reach_provider() {
+        let mut server = mockito::Server::new();
+        let _search = server
+            .mock("GET", "/locations/v1/search")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{
+                    "Key": "324505", "LocalizedName": "Kyiv", "Country": {"LocalizedName": "Ukraine"},
+                    "GeoPosition": {"Latitude": 50.45, "Longitude": 30.524}
+                }]"#,
+            )
+            .create();
+        let forecast = server
+            .mock("GET", "/forecasts/v1/daily/10day/324505")
+            .match_query(mockito::Matcher::Any)
+            .with_status(403)
+            .expect(1)
+            .create();
+        let factory = HttpProviderClientFactory::builder()
+            .with_base_url(Provider::AccuWeather, format!("{}/", server.url()))
+            .build()
+            .unwrap();
+        let store = InMemoryStore {
+            max_forecast_days: HashMap::from([(Provider::AccuWeather, 10)]),
+            ..configured_store(Provider::AccuWeather)
+        };
+        let mut service = WeatherService::new(store, factory);
+
+        let err = service
+            .get_weather("Kyiv".into(), Some(days_ahead(6)), None)
+            .unwrap_err();
+
+        forecast.assert();
+        assert!(
+            matches!(err, WeatherError::InvalidApiKey(Provider::AccuWeather)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn accuweather_range_over_five_days_is_rejected_before_request() {
+        let mut service = service_with(
+            Provider::AccuWeather,
+            Credentials::AccuWeather {
+                api_key: "KEY".into(),
+                base_url: None,
+            },
+        );
+
+        let err = service
+            .get_weather("Kyiv".into(), Some(days_ahead(5)), None)
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                WeatherError::ForecastRangeExceeded {
+                    requested: 6,
+                    max: 5,
+                    provider: Provider::AccuWeather
+                }
+            ),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn weatherapi_range_over_fourteen_days_is_rejected_before_request() {
+        let mut service = service_with(
+            Provider::WeatherApi,
+            Credentials::WeatherApi {
+                api_key: "KEY".into(),
+            },
+        );
+
+        let err = service
+            .get_weather("Kyiv".into(), Some(days_ahead(14)), None)
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                WeatherError::ForecastRangeExceeded {
+                    requested: 15,
+                    max: 14,
+                    provider: Provider::WeatherApi
+                }
+            ),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn today_returns_zero() {
+        let today = Local::now().date_naive();
+        let date_str = fmt(today);
+
+        let result = days_from_today(&date_str).unwrap();
+        assert_eq!(result, 0);
+    }
+
+    #[test]
+    fn tomorrow_returns_one() {
+        let today = Local::now().date_naive();
+        let tomorrow = today + Duration::days(1);
+        let date_str = fmt(tomorrow);
+
+        let result = days_from_today(&date_str).unwrap();
+        assert_eq!(result, 1);
+    }
+
+    #[test]
+    fn past_date_returns_error() {
+        let today = Local::now().date_naive();
+        let past = today - Duration::days(1);
+        let date_str = fmt(past);
+
+        let err = days_from_today(&date_str).unwrap_err();
+        let msg = err.to_string();
+        assert!(
+            msg.contains("in the past"),
+            "unexpected error message: {msg}"
+        );
+    }
+
+    #[rstest]
+    #[case::behind_utc(chrono_tz::America::New_York, "2024-11-29")]
+    #[case::ahead_of_utc(chrono_tz::Pacific::Auckland, "2024-11-30")]
+    fn unix_timestamp_resolves_to_date_in_timezone(#[case] tz: Tz, #[case] expected: &str) {
+        // 2024-11-29 12:00 UTC.
+        let date = parse_date("1732881600", &tz).unwrap();
+
+        assert_eq!(date.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case::signed_offset("+2")]
+    #[case::negative("-2")]
+    #[case::empty("")]
+    fn only_unsigned_digits_are_timestamps(#[case] date: &str) {
+        let err = days_from_today(date).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::InvalidDate(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn small_number_is_a_timestamp_in_the_past() {
+        let err = days_from_today("2").unwrap_err();
+
+        assert!(matches!(err, WeatherError::DateInPast), "{err:?}");
+    }
+
+    #[test]
+    fn timestamp_past_chrono_range_is_too_far_ahead() {
+        let err = days_from_today("99999999999999999999").unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::DateTooFarAhead { .. }),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn date_a_year_and_a_day_ahead_returns_error() {
+        let today = Local::now().date_naive();
+        let date = today.checked_add_months(Months::new(12)).unwrap() + Duration::days(1);
+
+        let err = days_from_today(&fmt(date)).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::DateTooFarAhead { max: 365 }),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn date_max_days_ahead_is_accepted() {
+        let result = days_from_today(&days_ahead(MAX_DAYS_AHEAD.into())).unwrap();
+
+        assert_eq!(result, MAX_DAYS_AHEAD);
+    }
+
+    #[test]
+    fn date_one_day_past_max_days_ahead_returns_error() {
+        let err = days_from_today(&days_ahead(i64::from(MAX_DAYS_AHEAD) + 1)).unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                WeatherError::DateTooFarAhead {
+                    max: MAX_DAYS_AHEAD
+                }
+            ),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[rstest]
+    #[case::single_day(2, 2, 2..=2)]
+    #[case::today_onwards(0, 3, 0..=3)]
+    #[case::future(4, 6, 4..=6)]
+    fn day_range_computes_offsets_for_both_ends(
+        #[case] from: i64,
+        #[case] to: i64,
+        #[case] expected: RangeInclusive<u32>,
+    ) {
+        assert_eq!(
+            day_range(&days_ahead(from), &days_ahead(to)).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn reversed_day_range_returns_error() {
+        let err = day_range(&days_ahead(3), &days_ahead(1)).unwrap_err();
+
+        assert!(
+            matches!(err, WeatherError::InvalidDateRange { .. }),
+            "unexpected error: {err:?}"
+        );
+        assert!(err.to_string().contains("is before start date"), "{err}");
+    }
+
+    #[test]
+    fn day_range_starting_in_past_returns_error() {
+        let err = day_range(&days_ahead(-1), &days_ahead(1)).unwrap_err();
+
+        assert!(matches!(err, WeatherError::DateInPast), "{err:?}");
     }
 
     #[test]